@@ -0,0 +1,259 @@
+use std::collections::{BTreeMap, HashMap};
+
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::device::helpers::get_current_time_u32;
+use crate::device::MeshDevice;
+use crate::graph::ds::graph::MeshGraph;
+
+use super::geo::GeoPoint;
+use super::propagation::{LinkBudget, PropagationModel};
+
+/// Fewer links than this can't separate terrain from measurement noise
+pub const MIN_CALIBRATION_SAMPLES: usize = 5;
+
+/// Nodes closer than this are likely sharing a location fix
+const MIN_SAMPLE_DISTANCE_M: f64 = 10.0;
+
+/// Fade margin covering 90% of links, assuming log-normal shadowing
+const FADE_MARGIN_SIGMAS: f64 = 1.28;
+
+/// Exponents outside this range point at bad positions rather than terrain
+const PATH_LOSS_EXPONENT_RANGE: (f64, f64) = (1.6, 6.0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CalibrationSampleSource {
+    NeighborInfo, // SNR reported by a node for one of its neighbors
+    DirectPacket, // RSSI of a packet the connected device heard without relays
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationSample {
+    pub from: u32,
+    pub to: u32,
+    pub distance_m: f64,
+    pub path_loss_db: f64,
+    pub source: CalibrationSampleSource,
+}
+
+/// Propagation model fitted to links observed on the mesh
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PropagationCalibration {
+    pub model: PropagationModel,
+    pub sample_count: u32,
+    pub rmse_db: f64,       // spread of observed path loss around the fitted model
+    pub r_squared: f64,     // share of path loss variation explained by distance
+    pub calibrated_at: u32, // secs
+    pub samples: Vec<CalibrationSample>,
+}
+
+/// Path loss of every observed link between two nodes with known positions.
+/// Neighbor SNR is converted using the receiver noise floor, so it reads
+/// low at short range where LoRa SNR saturates. Both directions of a
+/// neighbor link share a path, so they're averaged into a single sample.
+pub fn collect_samples(
+    device: &MeshDevice,
+    graph: &MeshGraph,
+    positions: &HashMap<u32, GeoPoint>,
+    budget: &LinkBudget,
+) -> Vec<CalibrationSample> {
+    let eirp_dbm = budget.tx_power_dbm + 2.0 * budget.antenna_gain_dbi;
+
+    let sample = |from: u32, to: u32, rssi_dbm: f64, source: CalibrationSampleSource| {
+        let distance_m = positions.get(&from)?.distance_to(positions.get(&to)?);
+
+        (distance_m >= MIN_SAMPLE_DISTANCE_M).then_some(CalibrationSample {
+            from,
+            to,
+            distance_m,
+            path_loss_db: eirp_dbm - rssi_dbm,
+            source,
+        })
+    };
+
+    let mut neighbor_links: BTreeMap<(u32, u32), Vec<f64>> = BTreeMap::new();
+
+    for (from, to, edge) in graph.edges() {
+        if from.node_num == to.node_num {
+            continue;
+        }
+
+        neighbor_links
+            .entry((
+                from.node_num.min(to.node_num),
+                from.node_num.max(to.node_num),
+            ))
+            .or_default()
+            .push(edge.snr());
+    }
+
+    let neighbor_samples = neighbor_links.iter().filter_map(|((a, b), snrs)| {
+        let mean_snr = snrs.iter().sum::<f64>() / snrs.len() as f64;
+
+        sample(
+            *a,
+            *b,
+            mean_snr + budget.noise_floor_dbm(),
+            CalibrationSampleSource::NeighborInfo,
+        )
+    });
+
+    let my_node_num = device.my_node_info.my_node_num;
+
+    let direct_samples = device.nodes.values().filter_map(|node| {
+        let last_heard = node.last_heard.as_ref()?;
+
+        // Relayed and MQTT packets say nothing about the path from the sender
+        if node.node_num == my_node_num || last_heard.via_mqtt || last_heard.hops_away != Some(0) {
+            return None;
+        }
+
+        let rssi_dbm = match last_heard.rssi {
+            0 => last_heard.snr as f64 + budget.noise_floor_dbm(),
+            rssi => rssi as f64,
+        };
+
+        sample(
+            node.node_num,
+            my_node_num,
+            rssi_dbm,
+            CalibrationSampleSource::DirectPacket,
+        )
+    });
+
+    neighbor_samples.chain(direct_samples).collect()
+}
+
+/// Fits the path loss exponent with the model anchored at free space loss
+/// at the reference distance, then sizes the fade margin from the residuals
+pub fn fit_propagation_model(
+    samples: Vec<CalibrationSample>,
+    budget: &LinkBudget,
+) -> Result<PropagationCalibration, String> {
+    if samples.len() < MIN_CALIBRATION_SAMPLES {
+        return Err(format!(
+            "Calibration needs at least {} links between nodes with known positions, found {}",
+            MIN_CALIBRATION_SAMPLES,
+            samples.len()
+        ));
+    }
+
+    let reference = PropagationModel::default().reference_distance_m;
+    let reference_loss =
+        PropagationModel::free_space().path_loss_db(reference, budget.frequency_mhz);
+
+    // Least squares through the origin of excess loss against 10 log10(d / d0)
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|s| {
+            (
+                10.0 * (s.distance_m / reference).log10(),
+                s.path_loss_db - reference_loss,
+            )
+        })
+        .collect();
+
+    let sum_xy: f64 = points.iter().map(|(x, y)| x * y).sum();
+    let sum_xx: f64 = points.iter().map(|(x, _)| x * x).sum();
+
+    let (min_exponent, max_exponent) = PATH_LOSS_EXPONENT_RANGE;
+    let path_loss_exponent = (sum_xy / sum_xx).clamp(min_exponent, max_exponent);
+
+    let n = points.len() as f64;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let ss_res: f64 = points
+        .iter()
+        .map(|(x, y)| (y - path_loss_exponent * x).powi(2))
+        .sum();
+    let ss_tot: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+
+    let rmse_db = (ss_res / n).sqrt();
+    let r_squared = if ss_tot > 0.0 {
+        1.0 - ss_res / ss_tot
+    } else {
+        0.0
+    };
+
+    Ok(PropagationCalibration {
+        model: PropagationModel {
+            path_loss_exponent,
+            reference_distance_m: reference,
+            fade_margin_db: FADE_MARGIN_SIGMAS * rmse_db,
+        },
+        sample_count: samples.len() as u32,
+        rmse_db,
+        r_squared,
+        calibrated_at: get_current_time_u32(),
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use meshtastic::protobufs::Neighbor;
+
+    use super::*;
+    use crate::graph::ds::{edge::GraphEdge, node::GraphNode};
+
+    #[test]
+    fn exponent_recovered_from_observed_links() {
+        let budget = LinkBudget::default();
+        let terrain = PropagationModel {
+            path_loss_exponent: 3.2,
+            ..PropagationModel::default()
+        };
+
+        let samples: Vec<CalibrationSample> = [500.0, 1_200.0, 2_500.0, 4_000.0, 7_000.0, 9_000.0]
+            .iter()
+            .enumerate()
+            .map(|(i, distance_m)| CalibrationSample {
+                from: i as u32,
+                to: 100,
+                distance_m: *distance_m,
+                // Alternating shadowing of 3 dB either way
+                path_loss_db: terrain.path_loss_db(*distance_m, budget.frequency_mhz)
+                    + if i % 2 == 0 { 3.0 } else { -3.0 },
+                source: CalibrationSampleSource::NeighborInfo,
+            })
+            .collect();
+
+        let calibration = fit_propagation_model(samples.clone(), &budget).unwrap();
+
+        assert!((calibration.model.path_loss_exponent - 3.2).abs() < 0.1);
+        assert!((calibration.rmse_db - 3.0).abs() < 0.5);
+        assert!(calibration.r_squared > 0.9);
+        assert!(fit_propagation_model(samples[..3].to_vec(), &budget).is_err());
+    }
+
+    #[test]
+    fn link_directions_merged_into_one_sample() {
+        let neighbor = |node_id: u32, snr: f32| Neighbor {
+            node_id,
+            snr,
+            ..Default::default()
+        };
+
+        let mut graph = MeshGraph::new();
+        let a = graph.upsert_node(GraphNode::from(neighbor(1, 0.0)));
+        let b = graph.upsert_node(GraphNode::from(neighbor(2, 0.0)));
+        graph.upsert_edge(a, b, GraphEdge::from_neighbor(2, neighbor(1, 2.0)));
+        graph.upsert_edge(b, a, GraphEdge::from_neighbor(1, neighbor(2, 6.0)));
+
+        let node_a = GeoPoint::new(52.0, 5.0);
+        let positions = HashMap::from([(1, node_a), (2, node_a.offset(1_000.0, 0.0))]);
+        let budget = LinkBudget::default();
+
+        let samples = collect_samples(&MeshDevice::new(), &graph, &positions, &budget);
+        let eirp_dbm = budget.tx_power_dbm + 2.0 * budget.antenna_gain_dbi;
+
+        assert_eq!(samples.len(), 1);
+        assert_eq!((samples[0].from, samples[0].to), (1, 2));
+        assert!(
+            (samples[0].path_loss_db - (eirp_dbm - 4.0 - budget.noise_floor_dbm())).abs() < 1e-9
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use geojson::{Feature, FeatureCollection, Geometry, JsonObject, Value};
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use super::geo::{GeoBounds, GeoPoint};
+use super::propagation::{LinkBudget, LinkPrediction, PropagationModel};
+
+const DEFAULT_CELL_SIZE_M: f64 = 250.0;
+
+/// Cells are grown past the requested size to stay under this count
+const MAX_COVERAGE_CELLS: u32 = 10_000;
+
+/// Furthest the estimate extends past the outermost nodes, regardless of
+/// the predicted range
+const MAX_COVERAGE_MARGIN_M: f64 = 20_000.0;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageOptions {
+    pub bounds: Option<GeoBounds>, // area around known nodes within predicted range if unset
+    pub cell_size_m: Option<f64>,
+    pub model: Option<PropagationModel>, // calibrated or default model if unset
+}
+
+/// Predicted signal over a grid, not exported as a TS type since the grid is
+/// a GeoJSON `FeatureCollection` of cell polygons
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReport {
+    pub budget: LinkBudget,
+    pub model: PropagationModel,
+    pub bounds: GeoBounds,
+    pub rows: u32,
+    pub columns: u32,
+    pub cell_size_m: f64,
+    pub covered_fraction: f64, // of cells where at least one node is predicted to be heard
+    pub grid: FeatureCollection,
+}
+
+fn cell_feature(cell: &GeoBounds, node_num: u32, prediction: &LinkPrediction) -> Feature {
+    let ring = vec![
+        vec![cell.west, cell.south],
+        vec![cell.east, cell.south],
+        vec![cell.east, cell.north],
+        vec![cell.west, cell.north],
+        vec![cell.west, cell.south],
+    ];
+
+    let mut properties = JsonObject::new();
+    properties.insert("nodeNum".into(), node_num.into());
+    properties.insert("rssiDbm".into(), prediction.rssi_dbm.into());
+    properties.insert("snrDb".into(), prediction.snr_db.into());
+    properties.insert("marginDb".into(), prediction.margin_db.into());
+    properties.insert("covered".into(), prediction.is_feasible().into());
+
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(Value::Polygon(vec![ring]))),
+        id: None,
+        properties: Some(properties),
+        foreign_members: None,
+    }
+}
+
+/// Estimates the strongest signal from any known node in each grid cell.
+/// Terrain is not considered, and every node is assumed to share the
+/// connected device's LoRa settings since they must to hear each other.
+pub fn estimate_coverage(
+    positions: &HashMap<u32, GeoPoint>,
+    budget: &LinkBudget,
+    model: &PropagationModel,
+    options: &CoverageOptions,
+) -> Option<CoverageReport> {
+    if positions.is_empty() {
+        return None;
+    }
+
+    let model = options.model.as_ref().unwrap_or(model);
+
+    let bounds = match options.bounds {
+        Some(bounds) => bounds,
+        None => GeoBounds::from_points(positions.values())?
+            .expand(model.max_range_m(budget).min(MAX_COVERAGE_MARGIN_M)),
+    };
+
+    let (width_m, height_m) = (bounds.width_m(), bounds.height_m());
+    let mut cell_size_m = options
+        .cell_size_m
+        .filter(|size| *size > 0.0)
+        .unwrap_or(DEFAULT_CELL_SIZE_M);
+
+    let cell_count = (width_m / cell_size_m).ceil() * (height_m / cell_size_m).ceil();
+    if cell_count > MAX_COVERAGE_CELLS as f64 {
+        cell_size_m *= (cell_count / MAX_COVERAGE_CELLS as f64).sqrt();
+    }
+
+    let rows = ((height_m / cell_size_m).ceil() as u32).clamp(1, MAX_COVERAGE_CELLS);
+    let columns = ((width_m / cell_size_m).ceil() as u32).clamp(1, MAX_COVERAGE_CELLS / rows);
+
+    let mut covered_cells = 0;
+
+    let features: Vec<Feature> = bounds
+        .cells(rows, columns)
+        .iter()
+        .filter_map(|cell| {
+            let center = cell.center();
+
+            let (node_num, prediction) = positions
+                .iter()
+                .map(|(node_num, position)| {
+                    (
+                        *node_num,
+                        model.predict(budget, center.distance_to(position)),
+                    )
+                })
+                .max_by(|(_, a), (_, b)| a.rssi_dbm.total_cmp(&b.rssi_dbm))?;
+
+            if prediction.is_feasible() {
+                covered_cells += 1;
+            }
+
+            Some(cell_feature(cell, node_num, &prediction))
+        })
+        .collect();
+
+    Some(CoverageReport {
+        budget: *budget,
+        model: *model,
+        bounds,
+        rows,
+        columns,
+        cell_size_m,
+        covered_fraction: covered_cells as f64 / features.len() as f64,
+        grid: FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_fades_with_distance() {
+        let node = GeoPoint::new(52.0, 5.0);
+        let bounds = GeoBounds::from_points([&node]).unwrap().expand(30_000.0);
+
+        let report = estimate_coverage(
+            &HashMap::from([(1, node)]),
+            &LinkBudget::default(),
+            &PropagationModel::default(),
+            &CoverageOptions {
+                bounds: Some(bounds),
+                cell_size_m: Some(2_000.0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let covered = |feature: &Feature| feature.property("covered").and_then(|c| c.as_bool());
+
+        assert_eq!(
+            report.grid.features.len() as u32,
+            report.rows * report.columns
+        );
+        assert!(report.covered_fraction > 0.0 && report.covered_fraction < 1.0);
+        assert_eq!(covered(&report.grid.features[0]), Some(false));
+
+        // Cells are ordered by row, so this is the cell next to the node
+        let center = (report.rows / 2 * report.columns + report.columns / 2) as usize;
+        assert_eq!(covered(&report.grid.features[center]), Some(true));
+    }
+
+    #[test]
+    fn bounds_derived_from_node_positions() {
+        let node_a = GeoPoint::new(52.0, 5.0);
+        let node_b = node_a.offset(3_000.0, 4_000.0);
+        let model = PropagationModel {
+            path_loss_exponent: 3.5,
+            ..PropagationModel::default()
+        };
+        let budget = LinkBudget::default();
+
+        let report = estimate_coverage(
+            &HashMap::from([(1, node_a), (2, node_b)]),
+            &budget,
+            &PropagationModel::default(),
+            &CoverageOptions {
+                model: Some(model),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let margin_m = model.max_range_m(&budget).min(MAX_COVERAGE_MARGIN_M);
+        let expected = GeoBounds::from_points([&node_a, &node_b])
+            .unwrap()
+            .expand(margin_m);
+
+        assert_eq!(report.model, model);
+        assert_eq!(report.bounds, expected);
+        assert!(report.bounds.south < node_a.latitude && report.bounds.north > node_b.latitude);
+        assert!(report.bounds.west < node_a.longitude && report.bounds.east > node_b.longitude);
+        assert!(report.rows * report.columns <= MAX_COVERAGE_CELLS);
+    }
+}
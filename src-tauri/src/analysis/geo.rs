@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::device::MeshDevice;
+
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Furthest latitude the map's Web Mercator projection can show, which also
+/// keeps longitude offsets finite near the poles
+const MAX_LATITUDE: f64 = 85.051_128_78;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Great-circle distance in meters
+    pub fn distance_to(&self, other: &GeoPoint) -> f64 {
+        let lat1 = self.latitude.to_radians();
+        let lat2 = other.latitude.to_radians();
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS_M * a.sqrt().atan2((1.0 - a).sqrt())
+    }
+
+    /// Point offset by the given distances in meters, accurate over the
+    /// few kilometers a mesh spans. Latitudes are clamped to what the map
+    /// can show.
+    pub fn offset(&self, north_m: f64, east_m: f64) -> GeoPoint {
+        let latitude = self.latitude.clamp(-MAX_LATITUDE, MAX_LATITUDE);
+        let d_lat = (north_m / EARTH_RADIUS_M).to_degrees();
+        let d_lon = (east_m / (EARTH_RADIUS_M * latitude.to_radians().cos())).to_degrees();
+
+        GeoPoint::new(
+            (latitude + d_lat).clamp(-MAX_LATITUDE, MAX_LATITUDE),
+            self.longitude + d_lon,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoBounds {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl GeoBounds {
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a GeoPoint>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, p| {
+            Some(match bounds {
+                None => GeoBounds {
+                    south: p.latitude,
+                    west: p.longitude,
+                    north: p.latitude,
+                    east: p.longitude,
+                },
+                Some(b) => GeoBounds {
+                    south: b.south.min(p.latitude),
+                    west: b.west.min(p.longitude),
+                    north: b.north.max(p.latitude),
+                    east: b.east.max(p.longitude),
+                },
+            })
+        })
+    }
+
+    /// Bounds grown by `margin_m` on every side
+    pub fn expand(&self, margin_m: f64) -> Self {
+        let south_west = GeoPoint::new(self.south, self.west).offset(-margin_m, -margin_m);
+        let north_east = GeoPoint::new(self.north, self.east).offset(margin_m, margin_m);
+
+        GeoBounds {
+            south: south_west.latitude,
+            west: south_west.longitude,
+            north: north_east.latitude,
+            east: north_east.longitude,
+        }
+    }
+
+    pub fn center(&self) -> GeoPoint {
+        GeoPoint::new(
+            (self.south + self.north) / 2.0,
+            (self.west + self.east) / 2.0,
+        )
+    }
+
+    /// East-west extent in meters, measured through the center
+    pub fn width_m(&self) -> f64 {
+        let latitude = self.center().latitude;
+
+        GeoPoint::new(latitude, self.west).distance_to(&GeoPoint::new(latitude, self.east))
+    }
+
+    /// North-south extent in meters
+    pub fn height_m(&self) -> f64 {
+        GeoPoint::new(self.south, self.west).distance_to(&GeoPoint::new(self.north, self.west))
+    }
+
+    /// Splits the bounds into a `rows` x `columns` grid of cells, ordered
+    /// south to north then west to east
+    pub fn cells(&self, rows: u32, columns: u32) -> Vec<GeoBounds> {
+        let lat_step = (self.north - self.south) / rows as f64;
+        let lon_step = (self.east - self.west) / columns as f64;
+
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    let south = self.south + lat_step * row as f64;
+                    let west = self.west + lon_step * column as f64;
+
+                    GeoBounds {
+                        south,
+                        west,
+                        north: south + lat_step,
+                        east: west + lon_step,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Centers of a `rows` x `columns` grid of cells covering the bounds
+    pub fn grid(&self, rows: u32, columns: u32) -> Vec<GeoPoint> {
+        self.cells(rows, columns)
+            .iter()
+            .map(GeoBounds::center)
+            .collect()
+    }
+}
+
+/// Most recent known location of every node that has reported a position
+pub fn node_positions(device: &MeshDevice) -> HashMap<u32, GeoPoint> {
+    device
+        .nodes
+        .values()
+        .filter_map(|node| {
+            let position = node.position_metrics.last()?;
+
+            // Firmware reports 0, 0 when the node has no fix
+            if position.latitude == 0.0 && position.longitude == 0.0 {
+                return None;
+            }
+
+            Some((
+                node.node_num,
+                GeoPoint::new(position.latitude as f64, position.longitude as f64),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_clamped_near_poles() {
+        let point = GeoPoint::new(85.0, 10.0).offset(50_000.0, 1_000.0);
+
+        assert_eq!(point.latitude, MAX_LATITUDE);
+        assert!(point.longitude.is_finite() && point.longitude > 10.0);
+
+        let expanded = GeoBounds::from_points([&GeoPoint::new(-89.9, 0.0)])
+            .unwrap()
+            .expand(20_000.0);
+
+        assert_eq!(expanded.south, -MAX_LATITUDE);
+        assert!(expanded.west.is_finite() && expanded.east.is_finite());
+    }
+}
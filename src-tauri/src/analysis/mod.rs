@@ -0,0 +1,5 @@
+pub mod calibration;
+pub mod coverage;
+pub mod geo;
+pub mod propagation;
+pub mod relay;
@@ -0,0 +1,263 @@
+use meshtastic::protobufs::{
+    self,
+    config::lo_ra_config::{ModemPreset, RegionCode},
+};
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::device::MeshDevice;
+
+/// Thermal noise density at room temperature, dBm/Hz
+const THERMAL_NOISE_DBM_HZ: f64 = -174.0;
+
+/// Typical noise figure of the SX126x/SX127x receive chain
+const RECEIVER_NOISE_FIGURE_DB: f64 = 6.0;
+
+/// Transmit power used when the radio is configured to the region default
+const DEFAULT_TX_POWER_DBM: f64 = 20.0;
+
+/// Center of each region's band, used when no frequency override is set
+fn region_frequency_mhz(region: RegionCode) -> f64 {
+    match region {
+        RegionCode::Unset | RegionCode::Us => 915.0,
+        RegionCode::Eu433 => 433.5,
+        RegionCode::Eu868 => 869.525,
+        RegionCode::Cn => 490.0,
+        RegionCode::Jp => 924.3,
+        RegionCode::Anz => 921.5,
+        RegionCode::Kr => 921.5,
+        RegionCode::Tw => 922.5,
+        RegionCode::Ru => 868.95,
+        RegionCode::In => 866.0,
+        RegionCode::Nz865 => 866.0,
+        RegionCode::Th => 922.5,
+        RegionCode::Lora24 => 2441.75,
+        RegionCode::Ua433 => 433.85,
+        RegionCode::Ua868 => 868.3,
+    }
+}
+
+/// Spreading factor and bandwidth (kHz) of each modem preset
+fn preset_modulation(preset: ModemPreset) -> (u32, f64) {
+    match preset {
+        ModemPreset::LongFast => (11, 250.0),
+        ModemPreset::LongModerate => (11, 125.0),
+        ModemPreset::LongSlow => (12, 125.0),
+        ModemPreset::VeryLongSlow => (12, 62.5),
+        ModemPreset::MediumSlow => (10, 250.0),
+        ModemPreset::MediumFast => (9, 250.0),
+        ModemPreset::ShortSlow => (8, 250.0),
+        ModemPreset::ShortFast => (7, 250.0),
+    }
+}
+
+/// Firmware encodes fractional bandwidths by their integer part
+fn custom_bandwidth_khz(bandwidth: u32) -> f64 {
+    match bandwidth {
+        31 => 31.25,
+        62 => 62.5,
+        200 => 203.125,
+        400 => 406.25,
+        800 => 812.5,
+        1600 => 1625.0,
+        bw => bw as f64,
+    }
+}
+
+/// Transmit and receive characteristics of a link between two radios
+/// sharing the same LoRa settings
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkBudget {
+    pub tx_power_dbm: f64,
+    pub antenna_gain_dbi: f64, // per side
+    pub frequency_mhz: f64,
+    pub bandwidth_khz: f64,
+    pub spread_factor: u32,
+}
+
+impl Default for LinkBudget {
+    fn default() -> Self {
+        let (spread_factor, bandwidth_khz) = preset_modulation(ModemPreset::LongFast);
+
+        Self {
+            tx_power_dbm: DEFAULT_TX_POWER_DBM,
+            antenna_gain_dbi: 0.0,
+            frequency_mhz: region_frequency_mhz(RegionCode::Us),
+            bandwidth_khz,
+            spread_factor,
+        }
+    }
+}
+
+impl LinkBudget {
+    pub fn from_lora_config(lora: &protobufs::config::LoRaConfig) -> Self {
+        let (spread_factor, bandwidth_khz) = if lora.use_preset || lora.spread_factor == 0 {
+            preset_modulation(lora.modem_preset())
+        } else {
+            (lora.spread_factor, custom_bandwidth_khz(lora.bandwidth))
+        };
+
+        let frequency_mhz = if lora.override_frequency > 0.0 {
+            lora.override_frequency as f64
+        } else {
+            region_frequency_mhz(lora.region())
+        };
+
+        let tx_power_dbm = match lora.tx_power {
+            0 => DEFAULT_TX_POWER_DBM,
+            power => power as f64,
+        };
+
+        Self {
+            tx_power_dbm,
+            antenna_gain_dbi: 0.0,
+            frequency_mhz,
+            bandwidth_khz,
+            spread_factor,
+        }
+    }
+
+    /// Link budget of the device's LoRa settings
+    pub fn from_device(device: &MeshDevice) -> Self {
+        device
+            .config
+            .lora
+            .as_ref()
+            .map(LinkBudget::from_lora_config)
+            .unwrap_or_default()
+    }
+
+    pub fn noise_floor_dbm(&self) -> f64 {
+        THERMAL_NOISE_DBM_HZ
+            + 10.0 * (self.bandwidth_khz * 1000.0).log10()
+            + RECEIVER_NOISE_FIGURE_DB
+    }
+
+    /// Lowest SNR the demodulator can decode at this spreading factor
+    pub fn snr_limit_db(&self) -> f64 {
+        -2.5 * (self.spread_factor as f64 - 4.0)
+    }
+
+    pub fn sensitivity_dbm(&self) -> f64 {
+        self.noise_floor_dbm() + self.snr_limit_db()
+    }
+}
+
+/// Log-distance path loss model, anchored to free space loss at the
+/// reference distance. An exponent of 2 is free space.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PropagationModel {
+    pub path_loss_exponent: f64,
+    pub reference_distance_m: f64,
+    pub fade_margin_db: f64, // kept in reserve for fading and obstructions
+}
+
+impl Default for PropagationModel {
+    /// Textbook values for suburban terrain with antennas near the ground
+    fn default() -> Self {
+        Self {
+            path_loss_exponent: 2.7,
+            reference_distance_m: 1.0,
+            fade_margin_db: 10.0,
+        }
+    }
+}
+
+impl PropagationModel {
+    pub fn free_space() -> Self {
+        Self {
+            path_loss_exponent: 2.0,
+            reference_distance_m: 1.0,
+            fade_margin_db: 0.0,
+        }
+    }
+
+    pub fn path_loss_db(&self, distance_m: f64, frequency_mhz: f64) -> f64 {
+        let d0 = self.reference_distance_m.max(0.1);
+        let distance_m = distance_m.max(d0);
+
+        let reference_loss = 20.0 * d0.log10() + 20.0 * frequency_mhz.log10() - 27.55;
+
+        reference_loss + 10.0 * self.path_loss_exponent * (distance_m / d0).log10()
+    }
+
+    /// Distance at which a link runs out of margin
+    pub fn max_range_m(&self, budget: &LinkBudget) -> f64 {
+        let d0 = self.reference_distance_m.max(0.1);
+        let max_path_loss = budget.tx_power_dbm + 2.0 * budget.antenna_gain_dbi
+            - budget.sensitivity_dbm()
+            - self.fade_margin_db;
+        let excess_loss = max_path_loss - self.path_loss_db(d0, budget.frequency_mhz);
+
+        d0 * 10f64.powf(excess_loss / (10.0 * self.path_loss_exponent))
+    }
+
+    pub fn predict(&self, budget: &LinkBudget, distance_m: f64) -> LinkPrediction {
+        let path_loss_db = self.path_loss_db(distance_m, budget.frequency_mhz);
+        let rssi_dbm = budget.tx_power_dbm + 2.0 * budget.antenna_gain_dbi - path_loss_db;
+        let snr_db = rssi_dbm - budget.noise_floor_dbm();
+
+        LinkPrediction {
+            distance_m,
+            path_loss_db,
+            rssi_dbm,
+            snr_db,
+            margin_db: snr_db - budget.snr_limit_db() - self.fade_margin_db,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkPrediction {
+    pub distance_m: f64,
+    pub path_loss_db: f64,
+    pub rssi_dbm: f64,
+    pub snr_db: f64,
+    pub margin_db: f64, // above the decode limit and fade margin
+}
+
+impl LinkPrediction {
+    pub fn is_feasible(&self) -> bool {
+        self.margin_db >= 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_fast_link_budget() {
+        let budget = LinkBudget::from_lora_config(&protobufs::config::LoRaConfig {
+            use_preset: true,
+            modem_preset: ModemPreset::LongFast as i32,
+            region: RegionCode::Eu868 as i32,
+            tx_power: 27,
+            ..Default::default()
+        });
+
+        assert_eq!(budget.spread_factor, 11);
+        assert_eq!(budget.tx_power_dbm, 27.0);
+        assert!((budget.sensitivity_dbm() - -131.5).abs() < 0.1);
+
+        let model = PropagationModel::default();
+        assert!(model.predict(&budget, 1_000.0).is_feasible());
+        assert!(!model.predict(&budget, 100_000.0).is_feasible());
+        assert!(
+            model
+                .predict(&budget, model.max_range_m(&budget))
+                .margin_db
+                .abs()
+                < 0.01
+        );
+        assert!(
+            PropagationModel::free_space()
+                .predict(&budget, 100_000.0)
+                .margin_db
+                > model.predict(&budget, 100_000.0).margin_db
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::{BTreeSet, HashMap};
+
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::ds::graph::MeshGraph;
+
+use super::geo::{GeoBounds, GeoPoint};
+use super::propagation::{LinkBudget, LinkPrediction, PropagationModel};
+
+/// Observed links with less SNR than this above the decode limit are
+/// considered too unreliable to carry traffic
+pub const WEAK_LINK_MARGIN_DB: f64 = 5.0;
+
+const DEFAULT_GRID_SIZE: u32 = 12;
+const MAX_GRID_SIZE: u32 = 50;
+const DEFAULT_MAX_RESULTS: u32 = 5;
+
+/// Distance the search grid extends past the outermost known nodes
+const GRID_MARGIN_M: f64 = 1_000.0;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayPlacementOptions {
+    pub candidates: Option<Vec<GeoPoint>>, // searches a grid around known nodes if unset
+    pub grid_size: Option<u32>,            // cells per side of the search grid
+    pub max_results: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum NodeLinkStatus {
+    Isolated, // no links observed
+    Weak,     // only weak links observed
+    Connected,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeConnectivity {
+    pub node_num: u32,
+    pub position: Option<GeoPoint>,
+    pub neighbor_count: u32,
+    pub best_snr: Option<f64>,
+    pub status: NodeLinkStatus,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayLink {
+    pub node_num: u32,
+    pub prediction: LinkPrediction,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayCandidate {
+    pub location: GeoPoint,
+    pub links: Vec<RelayLink>, // nodes the relay is predicted to reach
+    pub newly_connected_pairs: u32,
+    pub weak_nodes_served: u32,
+    pub score: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayPlacementReport {
+    pub budget: LinkBudget,
+    pub model: PropagationModel,
+    pub nodes: Vec<NodeConnectivity>,
+    pub component_count: u32, // groups of nodes joined by reliable links
+    pub candidates: Vec<RelayCandidate>, // best first
+}
+
+/// Groups of nodes that can reach each other over reliable links
+struct Components {
+    component_of: HashMap<u32, usize>,
+    sizes: Vec<u32>,
+}
+
+impl Components {
+    fn new(nodes: &BTreeSet<u32>, links: &[(u32, u32)]) -> Self {
+        let index: HashMap<u32, usize> = nodes.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+        let mut parent: Vec<usize> = (0..nodes.len()).collect();
+
+        fn find(parent: &mut [usize], i: usize) -> usize {
+            let mut root = i;
+            while parent[root] != root {
+                root = parent[root];
+            }
+            parent[i] = root;
+            root
+        }
+
+        for (a, b) in links {
+            let root_a = find(&mut parent, index[a]);
+            let root_b = find(&mut parent, index[b]);
+            parent[root_a] = root_b;
+        }
+
+        let mut roots: HashMap<usize, usize> = HashMap::new();
+        let mut component_of = HashMap::new();
+        let mut sizes = vec![];
+
+        for (node_num, i) in index.iter() {
+            let root = find(&mut parent, *i);
+            let component = *roots.entry(root).or_insert_with(|| {
+                sizes.push(0);
+                sizes.len() - 1
+            });
+
+            sizes[component] += 1;
+            component_of.insert(*node_num, component);
+        }
+
+        Self {
+            component_of,
+            sizes,
+        }
+    }
+
+    /// Pairs of nodes that become reachable when the given nodes are joined
+    fn pairs_joined(&self, nodes: impl Iterator<Item = u32>) -> u32 {
+        let components: BTreeSet<usize> = nodes
+            .filter_map(|n| self.component_of.get(&n).copied())
+            .collect();
+        let sizes: Vec<u32> = components.iter().map(|c| self.sizes[*c]).collect();
+
+        let total: u32 = sizes.iter().sum();
+        let within: u32 = sizes.iter().map(|s| s * s).sum();
+
+        (total * total - within) / 2
+    }
+}
+
+/// Ranks locations for a new relay by how much of the mesh it would join
+/// together, based on observed link quality and predicted coverage
+pub fn recommend_relay_placement(
+    graph: &MeshGraph,
+    positions: &HashMap<u32, GeoPoint>,
+    budget: &LinkBudget,
+    model: &PropagationModel,
+    options: &RelayPlacementOptions,
+) -> RelayPlacementReport {
+    let min_reliable_snr = budget.snr_limit_db() + WEAK_LINK_MARGIN_DB;
+
+    // Edges are directional, a link is as good as its best direction
+    let mut link_snr: HashMap<(u32, u32), f64> = HashMap::new();
+
+    for (from, to, edge) in graph.edges() {
+        if from.node_num == to.node_num {
+            continue;
+        }
+
+        let key = (
+            from.node_num.min(to.node_num),
+            from.node_num.max(to.node_num),
+        );
+        let snr = link_snr.entry(key).or_insert(edge.snr());
+        *snr = snr.max(edge.snr());
+    }
+
+    let all_nodes: BTreeSet<u32> = graph
+        .nodes_lookup
+        .keys()
+        .chain(positions.keys())
+        .chain(link_snr.keys().flat_map(|(a, b)| [a, b]))
+        .copied()
+        .collect();
+
+    let reliable_links: Vec<(u32, u32)> = link_snr
+        .iter()
+        .filter(|(_, snr)| **snr >= min_reliable_snr)
+        .map(|(link, _)| *link)
+        .collect();
+
+    let components = Components::new(&all_nodes, &reliable_links);
+
+    let nodes: Vec<NodeConnectivity> = all_nodes
+        .iter()
+        .map(|node_num| {
+            let snrs: Vec<f64> = link_snr
+                .iter()
+                .filter(|((a, b), _)| a == node_num || b == node_num)
+                .map(|(_, snr)| *snr)
+                .collect();
+            let best_snr = snrs.iter().copied().reduce(f64::max);
+
+            let status = match best_snr {
+                None => NodeLinkStatus::Isolated,
+                Some(snr) if snr < min_reliable_snr => NodeLinkStatus::Weak,
+                Some(_) => NodeLinkStatus::Connected,
+            };
+
+            NodeConnectivity {
+                node_num: *node_num,
+                position: positions.get(node_num).copied(),
+                neighbor_count: snrs.len() as u32,
+                best_snr,
+                status,
+            }
+        })
+        .collect();
+
+    let locations = options.candidates.clone().unwrap_or_else(|| {
+        let grid_size = options
+            .grid_size
+            .unwrap_or(DEFAULT_GRID_SIZE)
+            .clamp(1, MAX_GRID_SIZE);
+
+        GeoBounds::from_points(positions.values())
+            .map(|bounds| bounds.expand(GRID_MARGIN_M).grid(grid_size, grid_size))
+            .unwrap_or_default()
+    });
+
+    let mut candidates: Vec<RelayCandidate> = locations
+        .into_iter()
+        .filter_map(|location| {
+            let links: Vec<RelayLink> = positions
+                .iter()
+                .map(|(node_num, position)| RelayLink {
+                    node_num: *node_num,
+                    prediction: model.predict(budget, location.distance_to(position)),
+                })
+                .filter(|link| link.prediction.is_feasible())
+                .collect();
+
+            let newly_connected_pairs = components.pairs_joined(links.iter().map(|l| l.node_num));
+            let weak_nodes_served = nodes
+                .iter()
+                .filter(|n| n.status != NodeLinkStatus::Connected)
+                .filter(|n| links.iter().any(|l| l.node_num == n.node_num))
+                .count() as u32;
+
+            let score = newly_connected_pairs as f64 + weak_nodes_served as f64;
+
+            if score == 0.0 {
+                return None;
+            }
+
+            Some(RelayCandidate {
+                location,
+                links,
+                newly_connected_pairs,
+                weak_nodes_served,
+                score,
+            })
+        })
+        .collect();
+
+    // Among equally useful locations, prefer the one with the strongest links
+    let mean_margin = |c: &RelayCandidate| {
+        c.links.iter().map(|l| l.prediction.margin_db).sum::<f64>() / c.links.len() as f64
+    };
+
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| mean_margin(b).total_cmp(&mean_margin(a)))
+    });
+    candidates.truncate(options.max_results.unwrap_or(DEFAULT_MAX_RESULTS) as usize);
+
+    RelayPlacementReport {
+        budget: *budget,
+        model: *model,
+        nodes,
+        component_count: components.sizes.len() as u32,
+        candidates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use meshtastic::protobufs::Neighbor;
+
+    use super::*;
+    use crate::graph::ds::{edge::GraphEdge, node::GraphNode};
+
+    fn neighbor(node_id: u32, snr: f32) -> Neighbor {
+        Neighbor {
+            node_id,
+            snr,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn relay_suggested_between_isolated_node_and_mesh() {
+        let mut graph = MeshGraph::new();
+        let a = graph.upsert_node(GraphNode::from(neighbor(1, 0.0)));
+        let b = graph.upsert_node(GraphNode::from(neighbor(2, 0.0)));
+        graph.upsert_edge(a, b, GraphEdge::from_neighbor(2, neighbor(1, 6.0)));
+
+        let node_a = GeoPoint::new(52.0, 5.0);
+        let node_b = node_a.offset(1_000.0, 0.0);
+        let node_c = node_b.offset(12_000.0, 0.0);
+        let positions = HashMap::from([(1, node_a), (2, node_b), (3, node_c)]);
+
+        let between = node_b.offset(6_000.0, 0.0);
+        let far_away = node_a.offset(-50_000.0, 0.0);
+
+        let report = recommend_relay_placement(
+            &graph,
+            &positions,
+            &LinkBudget::default(),
+            &PropagationModel::default(),
+            &RelayPlacementOptions {
+                candidates: Some(vec![far_away, between]),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(report.component_count, 2);
+        assert_eq!(report.candidates.len(), 1);
+        assert_eq!(report.candidates[0].location, between);
+        assert_eq!(report.candidates[0].newly_connected_pairs, 2);
+        assert_eq!(report.candidates[0].weak_nodes_served, 1);
+        assert_eq!(
+            report
+                .nodes
+                .iter()
+                .find(|n| n.node_num == 3)
+                .map(|n| n.status),
+            Some(NodeLinkStatus::Isolated)
+        );
+    }
+
+    #[test]
+    fn default_grid_finds_relay_between_clusters() {
+        let mut graph = MeshGraph::new();
+        let a = graph.upsert_node(GraphNode::from(neighbor(1, 0.0)));
+        let b = graph.upsert_node(GraphNode::from(neighbor(2, 0.0)));
+        graph.upsert_edge(a, b, GraphEdge::from_neighbor(2, neighbor(1, 6.0)));
+
+        // Node 3 is just out of range of both other nodes
+        let node_a = GeoPoint::new(52.0, 5.0);
+        let node_b = node_a.offset(1_000.0, 0.0);
+        let node_c = node_b.offset(12_000.0, 0.0);
+        let positions = HashMap::from([(1, node_a), (2, node_b), (3, node_c)]);
+
+        let report = recommend_relay_placement(
+            &graph,
+            &positions,
+            &LinkBudget::default(),
+            &PropagationModel::default(),
+            &RelayPlacementOptions::default(),
+        );
+
+        let best = report.candidates.first().expect("No relay suggested");
+
+        assert!(report.candidates.len() <= DEFAULT_MAX_RESULTS as usize);
+        assert_eq!(best.newly_connected_pairs, 2);
+        assert!(best.location.latitude > node_b.latitude);
+        assert!(best.location.latitude < node_c.latitude);
+    }
+}
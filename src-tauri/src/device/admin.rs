@@ -0,0 +1,115 @@
+use bytes::Buf;
+use meshtastic::protobufs;
+use meshtastic::Message;
+use prost::encoding::{self, DecodeContext, WireType};
+
+/// Field number of `AdminMessage.session_passkey`, introduced in firmware 2.5.
+/// The protobufs bundled with our version of the `meshtastic` crate predate
+/// this field, so it is written and read by hand.
+pub const SESSION_PASSKEY_FIELD_NUMBER: u32 = 101;
+
+/// Firmware invalidates an issued session passkey after 300 seconds
+pub const SESSION_PASSKEY_TTL_SECS: u32 = 300;
+
+/// Encodes an admin message, appending the remote node's session passkey
+/// if one is available. Firmware older than 2.5 ignores the unknown field.
+///
+/// # Arguments
+///
+/// * `message` - The admin message to encode.
+/// * `session_passkey` - The most recent passkey issued by the remote node.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The encoded admin message payload.
+pub fn encode_admin_message(
+    message: &protobufs::AdminMessage,
+    session_passkey: Option<&[u8]>,
+) -> Vec<u8> {
+    let mut payload = message.encode_to_vec();
+
+    if let Some(passkey) = session_passkey.filter(|p| !p.is_empty()) {
+        encoding::bytes::encode(
+            SESSION_PASSKEY_FIELD_NUMBER,
+            &passkey.to_vec(),
+            &mut payload,
+        );
+    }
+
+    payload
+}
+
+/// Decodes an admin message payload along with the session passkey
+/// the remote node attached to it, if any.
+///
+/// # Arguments
+///
+/// * `payload` - The raw payload of an `AdminApp` packet.
+///
+/// # Returns
+///
+/// * `Result<(protobufs::AdminMessage, Option<Vec<u8>>), prost::DecodeError>` - The decoded
+///   admin message and session passkey.
+pub fn decode_admin_message(
+    payload: &[u8],
+) -> Result<(protobufs::AdminMessage, Option<Vec<u8>>), prost::DecodeError> {
+    let message = protobufs::AdminMessage::decode(payload)?;
+
+    let mut session_passkey = None;
+    let mut buf = payload;
+
+    while buf.has_remaining() {
+        let (tag, wire_type) = encoding::decode_key(&mut buf)?;
+
+        if tag == SESSION_PASSKEY_FIELD_NUMBER && wire_type == WireType::LengthDelimited {
+            let mut passkey: Vec<u8> = vec![];
+            encoding::bytes::merge(wire_type, &mut passkey, &mut buf, DecodeContext::default())?;
+            session_passkey = Some(passkey);
+        } else {
+            encoding::skip_field(wire_type, tag, &mut buf, DecodeContext::default())?;
+        }
+    }
+
+    Ok((message, session_passkey))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owner_request() -> protobufs::AdminMessage {
+        protobufs::AdminMessage {
+            payload_variant: Some(protobufs::admin_message::PayloadVariant::GetOwnerRequest(
+                true,
+            )),
+        }
+    }
+
+    #[test]
+    fn session_passkey_round_trip() {
+        let passkey = vec![0xde, 0xad, 0xbe, 0xef];
+        let payload = encode_admin_message(&owner_request(), Some(&passkey));
+
+        let (message, decoded_passkey) = decode_admin_message(&payload).unwrap();
+
+        assert_eq!(message, owner_request());
+        assert_eq!(decoded_passkey, Some(passkey));
+    }
+
+    #[test]
+    fn missing_session_passkey() {
+        let payload = encode_admin_message(&owner_request(), None);
+
+        let (message, decoded_passkey) = decode_admin_message(&payload).unwrap();
+
+        assert_eq!(message, owner_request());
+        assert_eq!(decoded_passkey, None);
+    }
+
+    #[test]
+    fn empty_session_passkey_not_encoded() {
+        let payload = encode_admin_message(&owner_request(), Some(&[]));
+
+        assert_eq!(payload, owner_request().encode_to_vec());
+    }
+}
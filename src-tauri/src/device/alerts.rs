@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use log::{debug, trace};
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use super::telemetry::TelemetrySeriesKind;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryAlertError {
+    #[error("Alert rule id \"{0}\" is used more than once")]
+    DuplicateRuleId(String),
+    #[error("Alert rule \"{0}\" has a non-finite threshold")]
+    InvalidThreshold(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ThresholdComparison {
+    Above,
+    Below,
+}
+
+impl ThresholdComparison {
+    fn exceeded(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            ThresholdComparison::Above => value > threshold,
+            ThresholdComparison::Below => value < threshold,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryAlertRule {
+    pub id: String,
+    pub kind: TelemetrySeriesKind,
+    pub field: String, // camelCase field name, e.g. "pm25Standard" or "ch1Voltage"
+    pub comparison: ThresholdComparison,
+    pub threshold: f64,
+    pub node_num: Option<u32>, // applies to all nodes when unset
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryAlert {
+    pub rule_id: String,
+    pub node_num: u32,
+    pub field: String,
+    pub value: f64,
+    pub comparison: ThresholdComparison,
+    pub threshold: f64,
+    pub timestamp: u32,
+}
+
+/// Checks incoming telemetry against user-defined thresholds. An alert is
+/// only raised when a reading crosses its threshold, not for every reading
+/// that stays past it.
+#[derive(Clone, Debug, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryAlertMonitor {
+    pub rules: Vec<TelemetryAlertRule>,
+
+    #[serde(skip)]
+    active: HashSet<(String, u32)>, // (rule id, node num) pairs currently past their threshold
+}
+
+impl TelemetryAlertMonitor {
+    pub fn set_rules(&mut self, rules: Vec<TelemetryAlertRule>) -> Result<(), TelemetryAlertError> {
+        let mut ids = HashSet::new();
+
+        for rule in rules.iter() {
+            if !ids.insert(rule.id.clone()) {
+                return Err(TelemetryAlertError::DuplicateRuleId(rule.id.clone()));
+            }
+
+            if !rule.threshold.is_finite() {
+                return Err(TelemetryAlertError::InvalidThreshold(rule.id.clone()));
+            }
+        }
+
+        debug!("Setting {} telemetry alert rules", rules.len());
+
+        // Rules that were edited start over, so a lowered threshold can fire
+        self.active.retain(|(id, _)| {
+            self.rules
+                .iter()
+                .find(|r| &r.id == id)
+                .map(|old| rules.contains(old))
+                .unwrap_or(false)
+        });
+
+        self.rules = rules;
+
+        Ok(())
+    }
+
+    pub fn evaluate(
+        &mut self,
+        node_num: u32,
+        kind: TelemetrySeriesKind,
+        fields: &[(&'static str, f64)],
+        timestamp: u32,
+    ) -> Vec<TelemetryAlert> {
+        let mut alerts = vec![];
+
+        for rule in self.rules.iter() {
+            if rule.kind != kind || rule.node_num.is_some_and(|n| n != node_num) {
+                continue;
+            }
+
+            let value = match fields.iter().find(|(name, _)| *name == rule.field) {
+                Some((_, value)) if value.is_finite() => *value,
+                _ => continue,
+            };
+
+            let key = (rule.id.clone(), node_num);
+
+            if !rule.comparison.exceeded(value, rule.threshold) {
+                self.active.remove(&key);
+                continue;
+            }
+
+            if self.active.insert(key) {
+                trace!(
+                    "Telemetry alert \"{}\" raised for node {}",
+                    rule.id,
+                    node_num
+                );
+
+                alerts.push(TelemetryAlert {
+                    rule_id: rule.id.clone(),
+                    node_num,
+                    field: rule.field.clone(),
+                    value,
+                    comparison: rule.comparison,
+                    threshold: rule.threshold,
+                    timestamp,
+                });
+            }
+        }
+
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alert_raised_once_per_crossing() {
+        let mut monitor = TelemetryAlertMonitor::default();
+
+        monitor
+            .set_rules(vec![TelemetryAlertRule {
+                id: "smoke".into(),
+                kind: TelemetrySeriesKind::AirQualityMetrics,
+                field: "pm25Standard".into(),
+                comparison: ThresholdComparison::Above,
+                threshold: 35.0,
+                node_num: None,
+            }])
+            .unwrap();
+
+        let kind = TelemetrySeriesKind::AirQualityMetrics;
+        let mut raised = |value: f64| {
+            monitor
+                .evaluate(1, kind, &[("pm25Standard", value)], 0)
+                .len()
+        };
+
+        assert_eq!(raised(10.0), 0);
+        assert_eq!(raised(50.0), 1);
+        assert_eq!(raised(60.0), 0);
+        assert_eq!(raised(20.0), 0);
+        assert_eq!(raised(40.0), 1);
+    }
+}
@@ -0,0 +1,293 @@
+use std::path::Path;
+
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use super::helpers::get_current_time_u32;
+use super::MeshDevice;
+
+/// Version of the backup file format written by this client. Bump this
+/// whenever `DeviceConfigBackup` changes in a non-backwards-compatible way.
+pub const CONFIG_BACKUP_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigBackupError {
+    #[error("Failed to access backup file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse YAML backup: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Failed to parse JSON backup: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Backup version {0} is newer than the supported version {1}")]
+    UnsupportedVersion(u32, u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigBackupFormat {
+    Yaml,
+    Json,
+}
+
+impl ConfigBackupFormat {
+    /// Infers the backup format from a file extension, defaulting to YAML
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ConfigBackupFormat::Json,
+            _ => ConfigBackupFormat::Yaml,
+        }
+    }
+}
+
+/// A versioned snapshot of all user-editable configuration on a device
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceConfigBackup {
+    pub version: u32,
+    pub created_at: u32, // secs
+    pub owner: Option<protobufs::User>,
+    pub config: protobufs::LocalConfig,
+    pub module_config: protobufs::LocalModuleConfig,
+    pub channels: Vec<protobufs::Channel>,
+}
+
+impl DeviceConfigBackup {
+    pub fn from_device(device: &MeshDevice) -> Self {
+        let owner = device
+            .nodes
+            .get(&device.my_node_info.my_node_num)
+            .and_then(|node| node.user.clone());
+
+        let mut channels: Vec<protobufs::Channel> = device
+            .channels
+            .values()
+            .map(|channel| channel.config.clone())
+            .collect();
+
+        channels.sort_by_key(|channel| channel.index);
+
+        Self {
+            version: CONFIG_BACKUP_VERSION,
+            created_at: get_current_time_u32(),
+            owner,
+            config: device.config.clone(),
+            module_config: device.module_config.clone(),
+            channels,
+        }
+    }
+
+    pub fn read_from_file(path: &Path) -> Result<Self, ConfigBackupError> {
+        let contents = std::fs::read_to_string(path)?;
+
+        let backup: Self = match ConfigBackupFormat::from_path(path) {
+            ConfigBackupFormat::Json => serde_json::from_str(&contents)?,
+            ConfigBackupFormat::Yaml => serde_yaml::from_str(&contents)?,
+        };
+
+        if backup.version > CONFIG_BACKUP_VERSION {
+            return Err(ConfigBackupError::UnsupportedVersion(
+                backup.version,
+                CONFIG_BACKUP_VERSION,
+            ));
+        }
+
+        Ok(backup)
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> Result<(), ConfigBackupError> {
+        let contents = match ConfigBackupFormat::from_path(path) {
+            ConfigBackupFormat::Json => serde_json::to_string_pretty(self)?,
+            ConfigBackupFormat::Yaml => serde_yaml::to_string(self)?,
+        };
+
+        std::fs::write(path, contents)?;
+
+        Ok(())
+    }
+}
+
+/// A single configuration field that differs between two backups.
+/// Values are rendered as JSON, and are `None` when the field is absent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFieldDiff {
+    pub path: String,
+    pub current_value: Option<String>,
+    pub incoming_value: Option<String>,
+}
+
+/// Computes a field-level diff of the configuration stored in two backups,
+/// ignoring backup metadata such as the version and creation time.
+///
+/// # Arguments
+///
+/// * `current` - The configuration currently on the device.
+/// * `incoming` - The configuration that would be applied to the device.
+///
+/// # Returns
+///
+/// * `Result<Vec<ConfigFieldDiff>, ConfigBackupError>` - All differing fields, keyed by
+///   dot-separated path (e.g., `config.lora.region`).
+pub fn diff_config_backups(
+    current: &DeviceConfigBackup,
+    incoming: &DeviceConfigBackup,
+) -> Result<Vec<ConfigFieldDiff>, ConfigBackupError> {
+    let mut diffs = vec![];
+
+    let sections: [(&str, serde_json::Value, serde_json::Value); 4] = [
+        (
+            "owner",
+            serde_json::to_value(&current.owner)?,
+            serde_json::to_value(&incoming.owner)?,
+        ),
+        (
+            "config",
+            serde_json::to_value(&current.config)?,
+            serde_json::to_value(&incoming.config)?,
+        ),
+        (
+            "moduleConfig",
+            serde_json::to_value(&current.module_config)?,
+            serde_json::to_value(&incoming.module_config)?,
+        ),
+        (
+            "channels",
+            serde_json::to_value(&current.channels)?,
+            serde_json::to_value(&incoming.channels)?,
+        ),
+    ];
+
+    for (path, current_value, incoming_value) in sections.iter() {
+        diff_values(
+            path.to_string(),
+            Some(current_value),
+            Some(incoming_value),
+            &mut diffs,
+        );
+    }
+
+    Ok(diffs)
+}
+
+fn diff_values(
+    path: String,
+    current: Option<&serde_json::Value>,
+    incoming: Option<&serde_json::Value>,
+    diffs: &mut Vec<ConfigFieldDiff>,
+) {
+    use serde_json::Value;
+
+    match (current, incoming) {
+        (Some(Value::Object(current_map)), Some(Value::Object(incoming_map))) => {
+            let mut keys: Vec<&String> = current_map.keys().chain(incoming_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                diff_values(
+                    format!("{}.{}", path, key),
+                    current_map.get(key),
+                    incoming_map.get(key),
+                    diffs,
+                );
+            }
+        }
+        (Some(Value::Array(current_list)), Some(Value::Array(incoming_list)))
+            if current_list
+                .iter()
+                .chain(incoming_list.iter())
+                .all(|v| v.is_object()) =>
+        {
+            for index in 0..current_list.len().max(incoming_list.len()) {
+                diff_values(
+                    format!("{}[{}]", path, index),
+                    current_list.get(index),
+                    incoming_list.get(index),
+                    diffs,
+                );
+            }
+        }
+        (current, incoming) => {
+            if current != incoming {
+                diffs.push(ConfigFieldDiff {
+                    path,
+                    current_value: current.map(|v| v.to_string()),
+                    incoming_value: incoming.map(|v| v.to_string()),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup_with_lora_region(region: i32) -> DeviceConfigBackup {
+        DeviceConfigBackup {
+            version: CONFIG_BACKUP_VERSION,
+            created_at: 0,
+            owner: None,
+            config: protobufs::LocalConfig {
+                lora: Some(protobufs::config::LoRaConfig {
+                    region,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            module_config: protobufs::LocalModuleConfig::default(),
+            channels: vec![],
+        }
+    }
+
+    #[test]
+    fn identical_backups_have_no_diff() {
+        let backup = backup_with_lora_region(1);
+        let diffs = diff_config_backups(&backup, &backup).unwrap();
+
+        assert!(diffs.is_empty());
+    }
+
+    #[test]
+    fn changed_field_is_reported() {
+        let current = backup_with_lora_region(1);
+        let incoming = backup_with_lora_region(3);
+
+        let diffs = diff_config_backups(&current, &incoming).unwrap();
+
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].path, "config.lora.region");
+        assert_eq!(diffs[0].current_value.as_deref(), Some("1"));
+        assert_eq!(diffs[0].incoming_value.as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn added_channel_is_reported() {
+        let current = backup_with_lora_region(1);
+        let mut incoming = backup_with_lora_region(1);
+        incoming.channels.push(protobufs::Channel {
+            index: 0,
+            role: 1,
+            ..Default::default()
+        });
+
+        let diffs = diff_config_backups(&current, &incoming).unwrap();
+
+        assert!(!diffs.is_empty());
+        assert!(diffs.iter().all(|d| d.path.starts_with("channels[0]")));
+        assert!(diffs.iter().all(|d| d.current_value.is_none()));
+    }
+
+    #[test]
+    fn format_inferred_from_extension() {
+        assert_eq!(
+            ConfigBackupFormat::from_path(Path::new("radio.json")),
+            ConfigBackupFormat::Json
+        );
+        assert_eq!(
+            ConfigBackupFormat::from_path(Path::new("radio.yaml")),
+            ConfigBackupFormat::Yaml
+        );
+    }
+}
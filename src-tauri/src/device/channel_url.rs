@@ -0,0 +1,398 @@
+use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use meshtastic::Message;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::{Deserialize, Serialize};
+
+use super::MeshDevice;
+
+pub const CHANNEL_URL_PREFIX: &str = "https://meshtastic.org/e/";
+
+/// Firmware supports a fixed number of channel slots
+pub const MAX_CHANNELS: usize = 8;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChannelUrlError {
+    #[error("URL does not contain an encoded channel set")]
+    MissingChannelSet,
+    #[error("Failed to decode channel set: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("Failed to decode channel set: {0}")]
+    Protobuf(#[from] prost::DecodeError),
+    #[error("Channel set contains no channels")]
+    Empty,
+    #[error("Channel set contains {0} channels, but devices only support {MAX_CHANNELS}")]
+    TooManyChannels(usize),
+    #[error("No free channel slots left to add channel \"{0}\"")]
+    NoFreeSlots(String),
+    #[error("Failed to generate QR code: {0}")]
+    QrCode(String),
+}
+
+/// A channel set decoded from a Meshtastic channel URL
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedChannelUrl {
+    pub channel_set: protobufs::ChannelSet,
+
+    /// Whether the channels should be added alongside existing channels
+    /// (`/e/?add=true#...`) rather than replacing them
+    pub add: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelUrl {
+    pub url: String,
+    pub qr_code_svg: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ChannelImportAction {
+    Added,
+    Replaced,
+    Unchanged,
+    Removed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelImportChange {
+    pub index: u32,
+    pub name: String,
+    pub action: ChannelImportAction,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelImportPreview {
+    pub changes: Vec<ChannelImportChange>,
+    pub channels: Vec<protobufs::Channel>, // channels that will be written to the device
+    pub lora_config: Option<protobufs::config::LoRaConfig>,
+    pub lora_config_changed: bool,
+}
+
+/// Builds the channel set shared by a device, containing all enabled
+/// channels ordered by index and optionally the device's LoRa config.
+pub fn build_channel_set(device: &MeshDevice, include_lora_config: bool) -> protobufs::ChannelSet {
+    let mut channels: Vec<&protobufs::Channel> = device
+        .channels
+        .values()
+        .map(|channel| &channel.config)
+        .filter(|channel| is_enabled(channel))
+        .collect();
+
+    channels.sort_by_key(|channel| channel.index);
+
+    protobufs::ChannelSet {
+        settings: channels
+            .into_iter()
+            .filter_map(|channel| channel.settings.clone())
+            .collect(),
+        lora_config: if include_lora_config {
+            device.config.lora.clone()
+        } else {
+            None
+        },
+    }
+}
+
+pub fn encode_channel_url(channel_set: &protobufs::ChannelSet) -> String {
+    format!(
+        "{}#{}",
+        CHANNEL_URL_PREFIX,
+        URL_SAFE_NO_PAD.encode(channel_set.encode_to_vec())
+    )
+}
+
+pub fn decode_channel_url(url: &str) -> Result<ParsedChannelUrl, ChannelUrlError> {
+    let (path, fragment) = url
+        .trim()
+        .split_once('#')
+        .ok_or(ChannelUrlError::MissingChannelSet)?;
+
+    if fragment.is_empty() {
+        return Err(ChannelUrlError::MissingChannelSet);
+    }
+
+    // Shared URLs use URL-safe base64, but some clients pad or use the
+    // standard alphabet, so normalize before decoding
+    let normalized: String = fragment
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+
+    let bytes = STANDARD_NO_PAD.decode(normalized)?;
+    let channel_set = protobufs::ChannelSet::decode(bytes.as_slice())?;
+
+    if channel_set.settings.is_empty() {
+        return Err(ChannelUrlError::Empty);
+    }
+
+    if channel_set.settings.len() > MAX_CHANNELS {
+        return Err(ChannelUrlError::TooManyChannels(channel_set.settings.len()));
+    }
+
+    Ok(ParsedChannelUrl {
+        channel_set,
+        add: path.contains("add=true"),
+    })
+}
+
+pub fn render_channel_url_qr_code(url: &str) -> Result<String, ChannelUrlError> {
+    let code = QrCode::new(url.as_bytes()).map_err(|e| ChannelUrlError::QrCode(e.to_string()))?;
+
+    Ok(code.render::<svg::Color>().min_dimensions(256, 256).build())
+}
+
+fn is_enabled(channel: &protobufs::Channel) -> bool {
+    channel.role != protobufs::channel::Role::Disabled as i32
+}
+
+fn channel_name(settings: &protobufs::ChannelSettings) -> String {
+    settings.name.clone()
+}
+
+/// Computes which of the device's channels would be added, replaced or
+/// removed by importing a channel set, along with the resulting channels.
+pub fn preview_channel_import(
+    device: &MeshDevice,
+    parsed_url: ParsedChannelUrl,
+) -> Result<ChannelImportPreview, ChannelUrlError> {
+    let mut slots: Vec<Option<protobufs::Channel>> = (0..MAX_CHANNELS as u32)
+        .map(|index| {
+            device
+                .channels
+                .get(&index)
+                .map(|channel| channel.config.clone())
+                .filter(is_enabled)
+        })
+        .collect();
+
+    let mut changes = vec![];
+
+    if parsed_url.add {
+        for settings in parsed_url.channel_set.settings {
+            let duplicate = slots.iter().flatten().any(|channel| {
+                channel
+                    .settings
+                    .as_ref()
+                    .map(|s| s.name == settings.name && s.psk == settings.psk)
+                    .unwrap_or(false)
+            });
+
+            if duplicate {
+                continue;
+            }
+
+            let free_index = slots
+                .iter()
+                .position(|slot| slot.is_none())
+                .ok_or_else(|| ChannelUrlError::NoFreeSlots(settings.name.clone()))?;
+
+            changes.push(ChannelImportChange {
+                index: free_index as u32,
+                name: channel_name(&settings),
+                action: ChannelImportAction::Added,
+            });
+
+            slots[free_index] = Some(protobufs::Channel {
+                index: free_index as i32,
+                settings: Some(settings),
+                role: if free_index == 0 {
+                    protobufs::channel::Role::Primary as i32
+                } else {
+                    protobufs::channel::Role::Secondary as i32
+                },
+            });
+        }
+    } else {
+        let incoming_count = parsed_url.channel_set.settings.len();
+
+        for (index, settings) in parsed_url.channel_set.settings.into_iter().enumerate() {
+            let action = match &slots[index] {
+                Some(existing) if existing.settings.as_ref() == Some(&settings) => {
+                    ChannelImportAction::Unchanged
+                }
+                Some(_) => ChannelImportAction::Replaced,
+                None => ChannelImportAction::Added,
+            };
+
+            changes.push(ChannelImportChange {
+                index: index as u32,
+                name: channel_name(&settings),
+                action,
+            });
+
+            slots[index] = Some(protobufs::Channel {
+                index: index as i32,
+                settings: Some(settings),
+                role: if index == 0 {
+                    protobufs::channel::Role::Primary as i32
+                } else {
+                    protobufs::channel::Role::Secondary as i32
+                },
+            });
+        }
+
+        for (index, slot) in slots.iter_mut().enumerate().skip(incoming_count) {
+            if let Some(existing) = slot.take() {
+                changes.push(ChannelImportChange {
+                    index: index as u32,
+                    name: existing
+                        .settings
+                        .as_ref()
+                        .map(channel_name)
+                        .unwrap_or_default(),
+                    action: ChannelImportAction::Removed,
+                });
+            }
+        }
+    }
+
+    let channels = slots
+        .into_iter()
+        .enumerate()
+        .map(|(index, slot)| {
+            slot.unwrap_or(protobufs::Channel {
+                index: index as i32,
+                settings: None,
+                role: protobufs::channel::Role::Disabled as i32,
+            })
+        })
+        .collect();
+
+    let lora_config = parsed_url.channel_set.lora_config;
+    let lora_config_changed = lora_config.is_some() && lora_config != device.config.lora;
+
+    Ok(ChannelImportPreview {
+        changes,
+        channels,
+        lora_config,
+        lora_config_changed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::MeshChannel;
+
+    fn channel_settings(name: &str) -> protobufs::ChannelSettings {
+        protobufs::ChannelSettings {
+            name: name.into(),
+            psk: vec![1],
+            ..Default::default()
+        }
+    }
+
+    fn device_with_channels(names: &[&str]) -> MeshDevice {
+        let mut device = MeshDevice::new();
+
+        for (index, name) in names.iter().enumerate() {
+            device.add_channel(MeshChannel {
+                config: protobufs::Channel {
+                    index: index as i32,
+                    settings: Some(channel_settings(name)),
+                    role: if index == 0 {
+                        protobufs::channel::Role::Primary as i32
+                    } else {
+                        protobufs::channel::Role::Secondary as i32
+                    },
+                },
+                last_interaction: 0,
+                messages: vec![],
+            });
+        }
+
+        device
+    }
+
+    #[test]
+    fn channel_url_round_trip() {
+        let device = device_with_channels(&["base", "ops"]);
+        let channel_set = build_channel_set(&device, false);
+
+        let url = encode_channel_url(&channel_set);
+        let parsed = decode_channel_url(&url).unwrap();
+
+        assert!(url.starts_with(CHANNEL_URL_PREFIX));
+        assert_eq!(parsed.channel_set, channel_set);
+        assert!(!parsed.add);
+    }
+
+    #[test]
+    fn padded_standard_base64_accepted() {
+        let channel_set = build_channel_set(&device_with_channels(&["base"]), false);
+        let fragment =
+            base64::engine::general_purpose::STANDARD.encode(channel_set.encode_to_vec());
+
+        let parsed =
+            decode_channel_url(&format!("https://meshtastic.org/e/?add=true#{}", fragment))
+                .unwrap();
+
+        assert_eq!(parsed.channel_set, channel_set);
+        assert!(parsed.add);
+    }
+
+    #[test]
+    fn missing_fragment_rejected() {
+        assert!(decode_channel_url("https://meshtastic.org/e/").is_err());
+    }
+
+    #[test]
+    fn replace_preview() {
+        let device = device_with_channels(&["base", "ops", "old"]);
+        let parsed = ParsedChannelUrl {
+            channel_set: protobufs::ChannelSet {
+                settings: vec![channel_settings("base"), channel_settings("medic")],
+                lora_config: None,
+            },
+            add: false,
+        };
+
+        let preview = preview_channel_import(&device, parsed).unwrap();
+        let actions: Vec<(u32, ChannelImportAction)> = preview
+            .changes
+            .iter()
+            .map(|c| (c.index, c.action.clone()))
+            .collect();
+
+        assert_eq!(
+            actions,
+            vec![
+                (0, ChannelImportAction::Unchanged),
+                (1, ChannelImportAction::Replaced),
+                (2, ChannelImportAction::Removed),
+            ]
+        );
+        assert_eq!(preview.channels.len(), MAX_CHANNELS);
+        assert!(!preview.lora_config_changed);
+    }
+
+    #[test]
+    fn add_preview_uses_free_slots() {
+        let device = device_with_channels(&["base"]);
+        let parsed = ParsedChannelUrl {
+            channel_set: protobufs::ChannelSet {
+                settings: vec![channel_settings("base"), channel_settings("medic")],
+                lora_config: None,
+            },
+            add: true,
+        };
+
+        let preview = preview_channel_import(&device, parsed).unwrap();
+
+        assert_eq!(preview.changes.len(), 1);
+        assert_eq!(preview.changes[0].index, 1);
+        assert_eq!(preview.changes[0].action, ChannelImportAction::Added);
+    }
+}
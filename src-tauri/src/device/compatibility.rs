@@ -0,0 +1,235 @@
+use log::warn;
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+/// Firmware version as reported in `DeviceMetadata`, e.g. "2.2.15.abc1234"
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FirmwareVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl FirmwareVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses the numeric part of a version, ignoring any build suffix
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim().trim_start_matches('v').split('.');
+
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts
+            .next()
+            .and_then(|p| {
+                let digits: String = p.chars().take_while(|c| c.is_ascii_digit()).collect();
+                digits.parse().ok()
+            })
+            .unwrap_or(0);
+
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl std::fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Client features that depend on the connected device's firmware
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceFeature {
+    NeighborInfo,
+    DetectionSensor,
+    Paxcounter,
+}
+
+impl DeviceFeature {
+    pub const ALL: [DeviceFeature; 3] = [
+        DeviceFeature::NeighborInfo,
+        DeviceFeature::DetectionSensor,
+        DeviceFeature::Paxcounter,
+    ];
+
+    /// Oldest firmware release that services the feature
+    pub fn min_firmware(&self) -> FirmwareVersion {
+        match self {
+            // NEIGHBORINFO_APP (portnum 71) and NEIGHBORINFO_CONFIG were
+            // introduced with the 2.2 firmware series
+            DeviceFeature::NeighborInfo => FirmwareVersion::new(2, 2, 0),
+            // DETECTION_SENSOR_APP and DETECTIONSENSOR_CONFIG (admin.proto)
+            // aren't in every 2.2 build, so the module is gated on 2.3.0
+            DeviceFeature::DetectionSensor => FirmwareVersion::new(2, 3, 0),
+            // PAXCOUNTER_APP (portnum 34) and PAXCOUNTER_CONFIG aren't in
+            // every 2.2 build either, so the module is gated on 2.3.0 too
+            DeviceFeature::Paxcounter => FirmwareVersion::new(2, 3, 0),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            DeviceFeature::NeighborInfo => "Neighbor info",
+            DeviceFeature::DetectionSensor => "Detection sensor",
+            DeviceFeature::Paxcounter => "Paxcounter",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureSupport {
+    pub feature: DeviceFeature,
+    pub supported: bool,
+    pub reason: Option<String>, // why the feature is unavailable
+}
+
+/// Features the connected device's firmware can service, derived from the
+/// metadata it reports while configuring
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCompatibility {
+    pub firmware_version: Option<FirmwareVersion>, // None if the version couldn't be parsed
+    pub features: Vec<FeatureSupport>,
+    pub warnings: Vec<String>,
+}
+
+impl DeviceCompatibility {
+    pub fn from_metadata(metadata: &protobufs::DeviceMetadata) -> Self {
+        let firmware_version = FirmwareVersion::parse(&metadata.firmware_version);
+        let mut warnings = vec![];
+
+        if firmware_version.is_none() {
+            warnings.push(format!(
+                "Unrecognized firmware version \"{}\"",
+                metadata.firmware_version
+            ));
+        }
+
+        let features = DeviceFeature::ALL
+            .iter()
+            .map(|feature| {
+                let min_firmware = feature.min_firmware();
+
+                let reason = match firmware_version {
+                    Some(version) if version < min_firmware => Some(format!(
+                        "{} requires firmware {} or newer, device has {}",
+                        feature.name(),
+                        min_firmware,
+                        version
+                    )),
+                    _ => None,
+                };
+
+                FeatureSupport {
+                    feature: *feature,
+                    supported: reason.is_none(),
+                    reason,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for reason in features.iter().filter_map(|s| s.reason.as_ref()) {
+            warn!("{}", reason);
+            warnings.push(reason.clone());
+        }
+
+        Self {
+            firmware_version,
+            features,
+            warnings,
+        }
+    }
+
+    /// Returns an error describing why the feature is unavailable. Features
+    /// are assumed supported until the device reports its metadata.
+    pub fn require(&self, feature: DeviceFeature) -> Result<(), String> {
+        match self.features.iter().find(|s| s.feature == feature) {
+            Some(FeatureSupport {
+                supported: false,
+                reason,
+                ..
+            }) => Err(reason
+                .clone()
+                .unwrap_or_else(|| format!("{} is not supported", feature.name()))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns an error if the module config sets a module the firmware
+    /// doesn't service, before any of it is sent to the device
+    pub fn require_module_config(
+        &self,
+        module_config: &protobufs::LocalModuleConfig,
+    ) -> Result<(), String> {
+        if module_config.neighbor_info.is_some() {
+            self.require(DeviceFeature::NeighborInfo)?;
+        }
+
+        if module_config.detection_sensor.is_some() {
+            self.require(DeviceFeature::DetectionSensor)?;
+        }
+
+        if module_config.paxcounter.is_some() {
+            self.require(DeviceFeature::Paxcounter)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn firmware_versions_parsed_and_ordered() {
+        assert_eq!(
+            FirmwareVersion::parse("2.2.15.abc1234"),
+            Some(FirmwareVersion::new(2, 2, 15))
+        );
+        assert_eq!(
+            FirmwareVersion::parse("v2.3"),
+            Some(FirmwareVersion::new(2, 3, 0))
+        );
+        assert_eq!(FirmwareVersion::parse("unknown"), None);
+        assert!(FirmwareVersion::new(2, 1, 9) < FirmwareVersion::new(2, 2, 0));
+    }
+
+    #[test]
+    fn old_firmware_disables_features() {
+        let compatibility = DeviceCompatibility::from_metadata(&protobufs::DeviceMetadata {
+            firmware_version: "2.2.15.deadbee".into(),
+            ..Default::default()
+        });
+
+        assert!(compatibility.require(DeviceFeature::NeighborInfo).is_ok());
+        assert!(compatibility.require(DeviceFeature::Paxcounter).is_err());
+        assert_eq!(compatibility.warnings.len(), 2);
+
+        assert!(compatibility
+            .require_module_config(&protobufs::LocalModuleConfig {
+                neighbor_info: Some(Default::default()),
+                ..Default::default()
+            })
+            .is_ok());
+        assert!(compatibility
+            .require_module_config(&protobufs::LocalModuleConfig {
+                detection_sensor: Some(Default::default()),
+                ..Default::default()
+            })
+            .is_err());
+        assert!(DeviceCompatibility::default()
+            .require(DeviceFeature::NeighborInfo)
+            .is_ok());
+    }
+}
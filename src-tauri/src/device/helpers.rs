@@ -1,3 +1,4 @@
+use meshtastic::protobufs;
 use rand::{distributions::Standard, prelude::Distribution, Rng};
 use std::time::UNIX_EPOCH;
 
@@ -12,6 +13,16 @@ pub fn get_current_time_u32() -> u32 {
         .expect("Could not convert u128 to u32")
 }
 
+/// Returns the number of hops a packet took, or `None` if the sender's
+/// firmware doesn't report `hop_start`
+pub fn get_hops_away(packet: &protobufs::MeshPacket) -> Option<u32> {
+    if packet.hop_start > 0 {
+        Some(packet.hop_start.saturating_sub(packet.hop_limit))
+    } else {
+        None
+    }
+}
+
 pub fn generate_rand_id<T>() -> T
 where
     Standard: Distribution<T>,
@@ -38,6 +49,25 @@ pub fn get_channel_name(device: &mut MeshDevice, channel_id: &u32) -> Option<Str
     Some(db_channel_settings.name.clone())
 }
 
+/// Returns the index of the channel used for remote administration.
+/// Firmware prior to 2.5 only accepts remote admin messages on a
+/// channel named "admin", otherwise the primary channel is used.
+pub fn get_admin_channel_index(device: &MeshDevice) -> u32 {
+    device
+        .channels
+        .iter()
+        .find(|(_, channel)| {
+            channel
+                .config
+                .settings
+                .as_ref()
+                .map(|settings| settings.name.eq_ignore_ascii_case("admin"))
+                .unwrap_or(false)
+        })
+        .map(|(index, _)| *index)
+        .unwrap_or(0)
+}
+
 /// Converts a mesh location field (e.g., latitude) from
 /// its mesh integer representation to a float.
 ///
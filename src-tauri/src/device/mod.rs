@@ -5,13 +5,23 @@ use meshtastic::ts::specta::{self, Type};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use self::alerts::TelemetryAlertMonitor;
+use self::compatibility::DeviceCompatibility;
 use self::helpers::{
-    convert_location_field_to_protos, generate_rand_id, get_current_time_u32,
+    convert_location_field_to_protos, generate_rand_id, get_current_time_u32, get_hops_away,
     normalize_location_field,
 };
+use self::telemetry::{TelemetryRetentionPolicy, TelemetrySeries};
 
+pub mod admin;
+pub mod alerts;
+pub mod backup;
+pub mod channel_url;
+pub mod compatibility;
 pub mod helpers;
 pub mod state;
+pub mod telemetry;
+pub mod telemetry_export;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Type)]
 #[serde(rename_all = "camelCase")]
@@ -57,6 +67,30 @@ pub struct MeshNodeEnvironmentMetrics {
     // channel: u32,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshNodeAirQualityMetrics {
+    metrics: protobufs::AirQualityMetrics,
+    timestamp: u32,
+    snr: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshNodePowerMetrics {
+    metrics: protobufs::PowerMetrics,
+    timestamp: u32,
+    snr: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshNodePaxcountMetrics {
+    metrics: protobufs::Paxcount,
+    timestamp: u32,
+    snr: f32,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct MeshNodePositionMetrics {
@@ -71,7 +105,23 @@ pub struct MeshNodePositionMetrics {
 pub struct LastHeardMetadata {
     pub timestamp: u32,
     pub snr: f32,
+    pub rssi: i32,
     pub channel: u32,
+    pub hops_away: Option<u32>, // None if the sender's firmware doesn't report `hop_start`
+    pub via_mqtt: bool,
+}
+
+impl LastHeardMetadata {
+    pub fn from_mesh_packet(packet: &protobufs::MeshPacket) -> Self {
+        Self {
+            timestamp: get_current_time_u32(),
+            snr: packet.rx_snr,
+            rssi: packet.rx_rssi,
+            channel: packet.channel,
+            hops_away: get_hops_away(packet),
+            via_mqtt: packet.via_mqtt,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -80,9 +130,26 @@ pub struct MeshNode {
     pub node_num: u32,
     pub last_heard: Option<LastHeardMetadata>,
     pub user: Option<protobufs::User>,
-    pub device_metrics: Vec<MeshNodeDeviceMetrics>,
+    pub device_metrics: Vec<MeshNodeDeviceMetrics>, // most recent samples, bounded by the retention policy
     pub environment_metrics: Vec<MeshNodeEnvironmentMetrics>,
+    pub air_quality_metrics: Vec<MeshNodeAirQualityMetrics>,
+    pub power_metrics: Vec<MeshNodePowerMetrics>,
+    pub paxcount_metrics: Vec<MeshNodePaxcountMetrics>,
     pub position_metrics: Vec<NormalizedPosition>,
+
+    // Full history is only returned through range queries
+    #[serde(skip)]
+    pub device_metrics_history: TelemetrySeries<MeshNodeDeviceMetrics>,
+    #[serde(skip)]
+    pub environment_metrics_history: TelemetrySeries<MeshNodeEnvironmentMetrics>,
+    #[serde(skip)]
+    pub air_quality_metrics_history: TelemetrySeries<MeshNodeAirQualityMetrics>,
+    #[serde(skip)]
+    pub power_metrics_history: TelemetrySeries<MeshNodePowerMetrics>,
+    #[serde(skip)]
+    pub paxcount_history: TelemetrySeries<MeshNodePaxcountMetrics>,
+    #[serde(skip)]
+    pub position_history: TelemetrySeries<MeshNodePositionMetrics>,
 }
 
 impl MeshNode {
@@ -93,15 +160,31 @@ impl MeshNode {
             user: None,
             device_metrics: Vec::new(),
             environment_metrics: Vec::new(),
+            air_quality_metrics: Vec::new(),
+            power_metrics: Vec::new(),
+            paxcount_metrics: Vec::new(),
             position_metrics: Vec::new(),
+            device_metrics_history: TelemetrySeries::default(),
+            environment_metrics_history: TelemetrySeries::default(),
+            air_quality_metrics_history: TelemetrySeries::default(),
+            power_metrics_history: TelemetrySeries::default(),
+            paxcount_history: TelemetrySeries::default(),
+            position_history: TelemetrySeries::default(),
         }
     }
 
-    pub fn update_from_node_info(&mut self, node_info: protobufs::NodeInfo) {
+    pub fn update_from_node_info(
+        &mut self,
+        node_info: protobufs::NodeInfo,
+        policy: &TelemetryRetentionPolicy,
+    ) {
         self.last_heard = Some(LastHeardMetadata {
             timestamp: get_current_time_u32(),
             snr: node_info.snr,
+            rssi: 0,
             channel: node_info.channel,
+            hops_away: None,
+            via_mqtt: false,
         });
 
         if let Some(user) = node_info.user {
@@ -109,15 +192,18 @@ impl MeshNode {
         }
 
         if let Some(device_metrics) = node_info.device_metrics {
-            self.device_metrics.push(MeshNodeDeviceMetrics {
-                metrics: device_metrics,
-                timestamp: get_current_time_u32(),
-                snr: node_info.snr,
-            });
+            self.record_device_metrics(
+                MeshNodeDeviceMetrics {
+                    metrics: device_metrics,
+                    timestamp: get_current_time_u32(),
+                    snr: node_info.snr,
+                },
+                policy,
+            );
         }
 
         if let Some(position) = node_info.position {
-            self.position_metrics.push(position.into());
+            self.record_position(position.into(), node_info.snr, policy);
         }
     }
 }
@@ -215,6 +301,13 @@ pub struct TelemetryPacket {
     pub data: protobufs::Telemetry,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PaxcountPacket {
+    pub packet: protobufs::MeshPacket,
+    pub data: protobufs::Paxcount,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct UserPacket {
@@ -235,6 +328,20 @@ pub struct NeighborInfoPacket {
     pub packet: protobufs::MeshPacket,
     pub data: protobufs::NeighborInfo,
 }
+
+/// Most recent detection events kept for each node
+pub const MAX_DETECTION_EVENTS_PER_NODE: usize = 100;
+
+/// A detection sensor trigger, such as a PIR or door sensor, reported by a node
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionEvent {
+    pub node_num: u32,
+    pub text: String,   // sensor name and state, as configured on the node
+    pub timestamp: u32, // secs
+    pub position: Option<NormalizedPosition>, // last known position of the node
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct TextPacket {
@@ -321,8 +428,11 @@ pub enum ChannelMessagePayload {
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub enum ChannelMessageState {
+    Queued, // held in the client until the radio has room in its TX queue
     Pending,
+    Sent, // accepted into the radio's TX queue
     Acknowledged,
+    Cancelled, // removed from the client's queue before being sent
     Error(String),
 }
 
@@ -333,6 +443,48 @@ pub struct ChannelMessageWithState {
     pub state: ChannelMessageState,
 }
 
+/// Configuration of a remote node, populated from admin message responses
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteNodeConfig {
+    pub node_num: u32,
+    pub config: protobufs::LocalConfig,
+    pub module_config: protobufs::LocalModuleConfig,
+    pub channels: HashMap<u32, protobufs::Channel>,
+    pub user: Option<protobufs::User>,
+    pub metadata: Option<protobufs::DeviceMetadata>,
+    pub last_response: Option<u32>, // secs
+
+    /// Passkey the remote node requires on admin messages that modify its state
+    #[serde(skip)]
+    pub session_passkey: Vec<u8>,
+
+    /// Time at which `session_passkey` was issued by the remote node
+    #[serde(skip)]
+    pub session_passkey_received: u32, // secs
+}
+
+impl RemoteNodeConfig {
+    pub fn new(node_num: u32) -> Self {
+        Self {
+            node_num,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the session passkey if the remote node hasn't expired it yet
+    pub fn active_session_passkey(&self, current_time: u32) -> Option<&[u8]> {
+        if self.session_passkey.is_empty()
+            || current_time.saturating_sub(self.session_passkey_received)
+                >= admin::SESSION_PASSKEY_TTL_SECS
+        {
+            return None;
+        }
+
+        Some(&self.session_passkey)
+    }
+}
+
 // TODO can't deserialize `SerialConnection`
 #[derive(Clone, Debug, Default, Serialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -344,12 +496,18 @@ pub struct MeshDevice {
     pub config: protobufs::LocalConfig, // local-only device configuration
     pub module_config: protobufs::LocalModuleConfig, // configuration for meshtastic modules
     pub my_node_info: protobufs::MyNodeInfo, // debug information specific to device
+    pub metadata: Option<protobufs::DeviceMetadata>, // firmware version and capabilities, if reported
+    pub compatibility: DeviceCompatibility,          // features the firmware can service
     pub nodes: HashMap<u32, MeshNode>, // network devices this device has communicated with
-    pub region_unset: bool,         // flag for whether device has an unset LoRa region
+    pub region_unset: bool,            // flag for whether device has an unset LoRa region
     pub device_metrics: protobufs::DeviceMetrics, // information about functioning of device (e.g. battery level)
     pub waypoints: HashMap<u32, NormalizedWaypoint>, // updatable GPS positions managed by this device
     pub neighbors: HashMap<u32, NeighborInfoPacket>, //updated packets from each node containing their neighbors
+    pub detection_events: HashMap<u32, Vec<DetectionEvent>>, // most recent detection sensor triggers from each node
     pub config_in_progress: bool, // flag for whether the user has started a configuration transaction
+    pub remote_nodes: HashMap<u32, RemoteNodeConfig>, // configuration retrieved from remote nodes via admin messages
+    pub telemetry_retention: TelemetryRetentionPolicy, // limits on telemetry stored per node
+    pub telemetry_alerts: TelemetryAlertMonitor, // user-defined thresholds checked against incoming telemetry
 }
 
 impl MeshDevice {
@@ -361,4 +519,34 @@ impl MeshDevice {
             ..Default::default()
         }
     }
+
+    /// Copies the device without its nodes' telemetry history, which isn't
+    /// serialized and can be large, for use in event payloads
+    pub fn clone_without_history(&self) -> Self {
+        Self {
+            config_id: self.config_id,
+            ready: self.ready,
+            status: self.status.clone(),
+            channels: self.channels.clone(),
+            config: self.config.clone(),
+            module_config: self.module_config.clone(),
+            my_node_info: self.my_node_info.clone(),
+            metadata: self.metadata.clone(),
+            compatibility: self.compatibility.clone(),
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(node_num, node)| (*node_num, node.clone_without_history()))
+                .collect(),
+            region_unset: self.region_unset,
+            device_metrics: self.device_metrics.clone(),
+            waypoints: self.waypoints.clone(),
+            neighbors: self.neighbors.clone(),
+            detection_events: self.detection_events.clone(),
+            config_in_progress: self.config_in_progress,
+            remote_nodes: self.remote_nodes.clone(),
+            telemetry_retention: self.telemetry_retention.clone(),
+            telemetry_alerts: self.telemetry_alerts.clone(),
+        }
+    }
 }
@@ -1,16 +1,21 @@
 #![allow(dead_code)]
 
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use meshtastic::protobufs;
 
+use super::alerts::TelemetryAlert;
+use super::compatibility::DeviceCompatibility;
 use super::helpers::get_current_time_u32;
+use super::telemetry::{TelemetryRetentionPolicy, TelemetrySample, TelemetrySeriesKind};
 use super::{
-    ChannelMessagePayload, ChannelMessageWithState, MeshChannel, MeshDevice, MeshNode,
-    MeshNodeDeviceMetrics, MeshNodeEnvironmentMetrics, NeighborInfoPacket, NormalizedWaypoint,
-    PositionPacket, SerialDeviceStatus, TelemetryPacket, TextPacket, UserPacket, WaypointPacket,
+    ChannelMessagePayload, ChannelMessageWithState, DetectionEvent, MeshChannel, MeshDevice,
+    MeshNode, MeshNodeAirQualityMetrics, MeshNodeDeviceMetrics, MeshNodeEnvironmentMetrics,
+    MeshNodePaxcountMetrics, MeshNodePowerMetrics, NeighborInfoPacket, NormalizedWaypoint,
+    PaxcountPacket, PositionPacket, RemoteNodeConfig, SerialDeviceStatus, TelemetryPacket,
+    TextPacket, UserPacket, WaypointPacket,
 };
 
-use crate::device::{ChannelMessageState, LastHeardMetadata};
+use crate::device::{ChannelMessageState, LastHeardMetadata, MAX_DETECTION_EVENTS_PER_NODE};
 
 impl MeshDevice {
     pub fn set_ready(&mut self, ready: bool) {
@@ -26,92 +31,18 @@ impl MeshDevice {
     pub fn set_config(&mut self, config: protobufs::Config) {
         debug!("Updating own config");
 
-        if let Some(payload_variant) = config.payload_variant {
-            match payload_variant {
-                protobufs::config::PayloadVariant::Device(device) => {
-                    trace!("Updated own device config: {:?}", device);
-                    self.config.device = Some(device);
-                }
-                protobufs::config::PayloadVariant::Position(position) => {
-                    trace!("Updated own position config: {:?}", position);
-                    self.config.position = Some(position);
-                }
-                protobufs::config::PayloadVariant::Power(power) => {
-                    trace!("Updated own power config: {:?}", power);
-                    self.config.power = Some(power);
-                }
-                protobufs::config::PayloadVariant::Network(network) => {
-                    trace!("Updated own network config: {:?}", network);
-                    self.config.network = Some(network);
-                }
-                protobufs::config::PayloadVariant::Display(display) => {
-                    trace!("Updated own display config: {:?}", display);
-                    self.config.display = Some(display);
-                }
-                protobufs::config::PayloadVariant::Lora(lora) => {
-                    trace!("Updated own LoRa config: {:?}", lora);
-                    self.region_unset =
-                        lora.region == protobufs::config::lo_ra_config::RegionCode::Unset as i32;
-                    self.config.lora = Some(lora);
-                }
-                protobufs::config::PayloadVariant::Bluetooth(bluetooth) => {
-                    trace!("Updated own bluetooth config: {:?}", bluetooth);
-                    self.config.bluetooth = Some(bluetooth);
-                }
-            }
+        if let Some(protobufs::config::PayloadVariant::Lora(ref lora)) = config.payload_variant {
+            self.region_unset =
+                lora.region == protobufs::config::lo_ra_config::RegionCode::Unset as i32;
         }
+
+        merge_config(&mut self.config, config);
     }
 
     pub fn set_module_config(&mut self, module_config: protobufs::ModuleConfig) {
         debug!("Updating own module config");
 
-        if let Some(payload_variant) = module_config.payload_variant {
-            match payload_variant {
-                protobufs::module_config::PayloadVariant::Audio(config) => {
-                    trace!("Updated own audio module config: {:?}", config);
-                    self.module_config.audio = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::CannedMessage(config) => {
-                    trace!("Updated own canned message module config: {:?}", config);
-                    self.module_config.canned_message = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::ExternalNotification(config) => {
-                    trace!(
-                        "Updated own external notification module config: {:?}",
-                        config
-                    );
-                    self.module_config.external_notification = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::Mqtt(config) => {
-                    trace!("Updated own mqtt module config: {:?}", config);
-                    self.module_config.mqtt = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::RangeTest(config) => {
-                    trace!("Updated own range test module config: {:?}", config);
-                    self.module_config.range_test = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::RemoteHardware(config) => {
-                    trace!("Updated own remote hardware module config: {:?}", config);
-                    self.module_config.remote_hardware = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::Serial(config) => {
-                    trace!("Updated own serial module config: {:?}", config);
-                    self.module_config.serial = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::StoreForward(config) => {
-                    trace!("Updated own store-forward module config: {:?}", config);
-                    self.module_config.store_forward = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::Telemetry(config) => {
-                    trace!("Updated own telemetry module config: {:?}", config);
-                    self.module_config.telemetry = Some(config);
-                }
-                protobufs::module_config::PayloadVariant::NeighborInfo(_config) => {}
-                protobufs::module_config::PayloadVariant::AmbientLighting(_config) => {}
-                protobufs::module_config::PayloadVariant::DetectionSensor(_config) => {}
-                protobufs::module_config::PayloadVariant::Paxcounter(_config) => {}
-            }
-        }
+        merge_module_config(&mut self.module_config, module_config);
     }
 
     pub fn set_my_node_info(&mut self, info: protobufs::MyNodeInfo) {
@@ -121,75 +52,155 @@ impl MeshDevice {
         self.my_node_info = info;
     }
 
-    pub fn set_device_metrics(&mut self, metrics: TelemetryPacket) {
-        let origin_node = self.nodes.get_mut(&metrics.packet.from);
-
-        if let Some(node) = origin_node {
-            if let Some(variant) = metrics.data.variant {
-                match variant {
-                    protobufs::telemetry::Variant::DeviceMetrics(device_metrics) => {
-                        debug!("Adding device metrics to node {:?}", metrics.packet.from);
-                        trace!("{:?}", device_metrics);
-
-                        self.device_metrics.battery_level = device_metrics.battery_level;
-                        self.device_metrics.voltage = device_metrics.voltage;
-                        self.device_metrics.air_util_tx = device_metrics.air_util_tx;
-                        self.device_metrics.channel_utilization =
-                            device_metrics.channel_utilization;
-
-                        node.device_metrics.push(MeshNodeDeviceMetrics {
-                            metrics: protobufs::DeviceMetrics { ..device_metrics },
-                            timestamp: get_current_time_u32(),
-                            snr: metrics.packet.rx_snr,
-                        });
-                    }
-                    protobufs::telemetry::Variant::EnvironmentMetrics(environment_metrics) => {
-                        debug!(
-                            "Adding environment metrics to node {:?}",
-                            metrics.packet.from
-                        );
-                        trace!("{:?}", environment_metrics);
-
-                        node.environment_metrics.push(MeshNodeEnvironmentMetrics {
-                            metrics: protobufs::EnvironmentMetrics {
-                                ..environment_metrics
-                            },
-                            timestamp: get_current_time_u32(),
-                            snr: metrics.packet.rx_snr,
-                        });
-                    }
-                    protobufs::telemetry::Variant::AirQualityMetrics(air_quality_metrics) => {
-                        debug!("Received air quality metrics, not handling");
-                        trace!("{:?}", air_quality_metrics);
-                    }
-                    protobufs::telemetry::Variant::PowerMetrics(power_metrics) => {
-                        debug!("Received power metrics, not handling");
-                        trace!("{:?}", power_metrics);
-                    }
+    /// Inserts the sender of a mesh packet into the node database if it
+    /// isn't already known, and updates when and how it was last heard.
+    /// Called for every incoming mesh packet regardless of portnum, so
+    /// nodes are recorded consistently by whichever packet reveals them first.
+    pub fn upsert_node_from_packet(&mut self, packet: &protobufs::MeshPacket) -> &mut MeshNode {
+        let is_own_packet = packet.from == self.my_node_info.my_node_num;
+
+        let node = self.nodes.entry(packet.from).or_insert_with(|| {
+            debug!("Inserting new node with id {} from packet", packet.from);
+            MeshNode::new(packet.from)
+        });
+
+        // Packets echoed back from our own radio carry no reception metadata
+        if !is_own_packet {
+            node.last_heard = Some(LastHeardMetadata::from_mesh_packet(packet));
+        }
+
+        node
+    }
+
+    /// Records a telemetry sample on the sending node, returning any alerts
+    /// raised by the sample crossing a threshold
+    pub fn set_device_metrics(&mut self, metrics: TelemetryPacket) -> Vec<TelemetryAlert> {
+        let variant = match metrics.data.variant {
+            Some(v) => v,
+            None => return vec![],
+        };
+
+        let node_num = metrics.packet.from;
+        let from_local_node = node_num == self.my_node_info.my_node_num;
+        let timestamp = get_current_time_u32();
+        let policy = self.telemetry_retention.clone();
+        let node = self.upsert_node_from_packet(&metrics.packet);
+
+        let (kind, fields) = match variant {
+            protobufs::telemetry::Variant::DeviceMetrics(device_metrics) => {
+                debug!("Adding device metrics to node {:?}", metrics.packet.from);
+                trace!("{:?}", device_metrics);
+
+                let sample = MeshNodeDeviceMetrics {
+                    metrics: protobufs::DeviceMetrics { ..device_metrics },
+                    timestamp,
+                    snr: metrics.packet.rx_snr,
+                };
+                let fields = sample.numeric_fields();
+
+                node.record_device_metrics(sample, &policy);
+
+                // Only the connected radio's own telemetry describes the device
+                if from_local_node {
+                    self.device_metrics.battery_level = device_metrics.battery_level;
+                    self.device_metrics.voltage = device_metrics.voltage;
+                    self.device_metrics.air_util_tx = device_metrics.air_util_tx;
+                    self.device_metrics.channel_utilization = device_metrics.channel_utilization;
                 }
+
+                (TelemetrySeriesKind::DeviceMetrics, fields)
             }
-        } else {
-            let new_node = MeshNode {
-                node_num: metrics.packet.from,
-                last_heard: Some(LastHeardMetadata {
-                    timestamp: get_current_time_u32(),
+            protobufs::telemetry::Variant::EnvironmentMetrics(environment_metrics) => {
+                debug!(
+                    "Adding environment metrics to node {:?}",
+                    metrics.packet.from
+                );
+                trace!("{:?}", environment_metrics);
+
+                let sample = MeshNodeEnvironmentMetrics {
+                    metrics: protobufs::EnvironmentMetrics {
+                        ..environment_metrics
+                    },
+                    timestamp,
                     snr: metrics.packet.rx_snr,
-                    channel: metrics.packet.channel,
-                }),
-                user: None,
-                device_metrics: vec![],
-                environment_metrics: vec![],
-                position_metrics: vec![],
-            };
+                };
+                let fields = sample.numeric_fields();
 
-            debug!(
-                "Inserting new node with id {} from metrics",
-                metrics.packet.from,
-            );
-            trace!("{:?}", new_node);
+                node.record_environment_metrics(sample, &policy);
+
+                (TelemetrySeriesKind::EnvironmentMetrics, fields)
+            }
+            protobufs::telemetry::Variant::AirQualityMetrics(air_quality_metrics) => {
+                debug!(
+                    "Adding air quality metrics to node {:?}",
+                    metrics.packet.from
+                );
+                trace!("{:?}", air_quality_metrics);
+
+                let sample = MeshNodeAirQualityMetrics {
+                    metrics: air_quality_metrics,
+                    timestamp,
+                    snr: metrics.packet.rx_snr,
+                };
+                let fields = sample.numeric_fields();
+
+                node.record_air_quality_metrics(sample, &policy);
 
-            self.nodes.insert(metrics.packet.from, new_node);
+                (TelemetrySeriesKind::AirQualityMetrics, fields)
+            }
+            protobufs::telemetry::Variant::PowerMetrics(power_metrics) => {
+                debug!("Adding power metrics to node {:?}", metrics.packet.from);
+                trace!("{:?}", power_metrics);
+
+                let sample = MeshNodePowerMetrics {
+                    metrics: power_metrics,
+                    timestamp,
+                    snr: metrics.packet.rx_snr,
+                };
+                let fields = sample.numeric_fields();
+
+                node.record_power_metrics(sample, &policy);
+
+                (TelemetrySeriesKind::PowerMetrics, fields)
+            }
+        };
+
+        self.telemetry_alerts
+            .evaluate(node_num, kind, &fields, timestamp)
+    }
+
+    /// Records a paxcounter sample on the sending node, returning any alerts
+    /// raised by the sample crossing a threshold
+    pub fn add_paxcount(&mut self, paxcount: PaxcountPacket) -> Vec<TelemetryAlert> {
+        debug!("Adding paxcount to node {:?}", paxcount.packet.from);
+        trace!("{:?}", paxcount.data);
+
+        let node_num = paxcount.packet.from;
+        let timestamp = get_current_time_u32();
+        let policy = self.telemetry_retention.clone();
+        let node = self.upsert_node_from_packet(&paxcount.packet);
+
+        let sample = MeshNodePaxcountMetrics {
+            metrics: paxcount.data,
+            timestamp,
+            snr: paxcount.packet.rx_snr,
+        };
+        let fields = sample.numeric_fields();
+
+        node.record_paxcount(sample, &policy);
+
+        self.telemetry_alerts
+            .evaluate(node_num, TelemetrySeriesKind::Paxcount, &fields, timestamp)
+    }
+
+    pub fn set_telemetry_retention(&mut self, policy: TelemetryRetentionPolicy) {
+        debug!("Setting telemetry retention policy: {:?}", policy);
+
+        for node in self.nodes.values_mut() {
+            node.apply_retention_policy(&policy);
         }
+
+        self.telemetry_retention = policy;
     }
 
     pub fn add_channel(&mut self, channel: MeshChannel) {
@@ -218,69 +229,39 @@ impl MeshDevice {
             debug!("Updating existing node with id {} from info", node_info.num,);
             trace!("{:?}", node_info);
 
-            node.update_from_node_info(node_info);
+            node.update_from_node_info(node_info, &self.telemetry_retention);
         } else {
             debug!("Inserting new node with id {} from info", node_info.num,);
             trace!("{:?}", node_info);
 
             let mut new_node = MeshNode::new(node_info.num);
-            new_node.update_from_node_info(node_info.clone());
+            new_node.update_from_node_info(node_info.clone(), &self.telemetry_retention);
 
             self.nodes.insert(node_info.num, new_node);
         }
     }
 
     pub fn add_user(&mut self, user: UserPacket) {
-        let found_node = self.nodes.get_mut(&user.packet.from);
-
-        if let Some(node) = found_node {
-            trace!(
-                "Updating user of existing node {:?}: {:?}",
-                user.packet.from,
-                user.data
-            );
-            node.user = Some(user.data);
-        } else {
-            trace!(
-                "Adding user to new node {:?}: {:?}",
-                user.packet.from,
-                user.data
-            );
-
-            let mut new_node = MeshNode::new(self.my_node_info.my_node_num);
-            new_node.user = Some(user.data);
-            new_node.last_heard = Some(LastHeardMetadata {
-                timestamp: get_current_time_u32(),
-                snr: user.packet.rx_snr,
-                channel: user.packet.channel,
-            });
+        trace!(
+            "Updating user of node {:?}: {:?}",
+            user.packet.from,
+            user.data
+        );
 
-            self.nodes.insert(user.packet.from, new_node);
-        }
+        let node = self.upsert_node_from_packet(&user.packet);
+        node.user = Some(user.data);
     }
 
     pub fn add_position(&mut self, position: PositionPacket) {
-        let found_node = self.nodes.get_mut(&position.packet.from);
-
-        if let Some(node) = found_node {
-            trace!(
-                "Updating position of existing node {:?}: {:?}",
-                position.packet.from,
-                position.data
-            );
-            node.position_metrics.push(position.data.into());
-        } else {
-            trace!(
-                "Adding position to new node {:?}: {:?}",
-                position.packet.from,
-                position.data
-            );
-
-            let mut new_node = MeshNode::new(self.my_node_info.my_node_num);
-            new_node.position_metrics.push(position.data.into());
+        trace!(
+            "Updating position of node {:?}: {:?}",
+            position.packet.from,
+            position.data
+        );
 
-            self.nodes.insert(position.packet.from, new_node);
-        }
+        let policy = self.telemetry_retention.clone();
+        let node = self.upsert_node_from_packet(&position.packet);
+        node.record_position(position.data.into(), position.packet.rx_snr, &policy);
     }
 
     pub fn add_neighborinfo(&mut self, neighborinfo: NeighborInfoPacket) {
@@ -303,6 +284,38 @@ impl MeshDevice {
         }
     }
 
+    /// Records a detection sensor trigger on the sending node, along with the
+    /// node's last known position
+    pub fn add_detection_event(
+        &mut self,
+        packet: &protobufs::MeshPacket,
+        text: String,
+    ) -> DetectionEvent {
+        let node = self.upsert_node_from_packet(packet);
+
+        let event = DetectionEvent {
+            node_num: packet.from,
+            text,
+            timestamp: get_current_time_u32(),
+            position: node.position_metrics.last().cloned(),
+        };
+
+        trace!(
+            "Adding detection event from node {}: {:?}",
+            packet.from,
+            event
+        );
+
+        let events = self.detection_events.entry(packet.from).or_default();
+        events.push(event.clone());
+
+        if events.len() > MAX_DETECTION_EVENTS_PER_NODE {
+            events.drain(..events.len() - MAX_DETECTION_EVENTS_PER_NODE);
+        }
+
+        event
+    }
+
     pub fn add_text_message(&mut self, message: TextPacket) {
         let channel = self.channels.get_mut(&message.packet.channel);
 
@@ -339,7 +352,80 @@ impl MeshDevice {
         }
     }
 
-    // TODO add device metadata
+    pub fn set_metadata(&mut self, metadata: protobufs::DeviceMetadata) {
+        debug!("Set device metadata: {:?}", metadata);
+
+        self.compatibility = DeviceCompatibility::from_metadata(&metadata);
+        self.metadata = Some(metadata);
+    }
+
+    pub fn add_remote_admin_message(
+        &mut self,
+        node_num: u32,
+        message: protobufs::AdminMessage,
+        session_passkey: Option<Vec<u8>>,
+    ) {
+        let remote_node = self
+            .remote_nodes
+            .entry(node_num)
+            .or_insert_with(|| RemoteNodeConfig::new(node_num));
+
+        remote_node.last_response = Some(get_current_time_u32());
+
+        if let Some(passkey) = session_passkey.filter(|p| !p.is_empty()) {
+            trace!("Received session passkey from remote node {}", node_num);
+            remote_node.session_passkey = passkey;
+            remote_node.session_passkey_received = get_current_time_u32();
+        }
+
+        let payload_variant = match message.payload_variant {
+            Some(v) => v,
+            None => return,
+        };
+
+        match payload_variant {
+            protobufs::admin_message::PayloadVariant::GetChannelResponse(channel) => {
+                let index = match u32::try_from(channel.index) {
+                    Ok(index) => index,
+                    Err(_) => {
+                        warn!(
+                            "Ignoring channel with invalid index {} from remote node {}",
+                            channel.index, node_num
+                        );
+                        trace!("{:?}", channel);
+                        return;
+                    }
+                };
+
+                debug!("Updating channel {} of remote node {}", index, node_num);
+
+                remote_node.channels.insert(index, channel);
+            }
+            protobufs::admin_message::PayloadVariant::GetOwnerResponse(user) => {
+                debug!("Updating owner of remote node {}", node_num);
+                remote_node.user = Some(user);
+            }
+            protobufs::admin_message::PayloadVariant::GetConfigResponse(config) => {
+                debug!("Updating config of remote node {}", node_num);
+                merge_config(&mut remote_node.config, config);
+            }
+            protobufs::admin_message::PayloadVariant::GetModuleConfigResponse(module_config) => {
+                debug!("Updating module config of remote node {}", node_num);
+                merge_module_config(&mut remote_node.module_config, module_config);
+            }
+            protobufs::admin_message::PayloadVariant::GetDeviceMetadataResponse(metadata) => {
+                debug!("Updating metadata of remote node {}", node_num);
+                remote_node.metadata = Some(metadata);
+            }
+            variant => {
+                debug!(
+                    "Received unhandled admin message from remote node {}",
+                    node_num
+                );
+                trace!("{:?}", variant);
+            }
+        }
+    }
 
     pub fn set_message_state(
         &mut self,
@@ -364,3 +450,196 @@ impl MeshDevice {
         }
     }
 }
+
+fn merge_config(local_config: &mut protobufs::LocalConfig, config: protobufs::Config) {
+    if let Some(payload_variant) = config.payload_variant {
+        match payload_variant {
+            protobufs::config::PayloadVariant::Device(device) => {
+                trace!("Updated device config: {:?}", device);
+                local_config.device = Some(device);
+            }
+            protobufs::config::PayloadVariant::Position(position) => {
+                trace!("Updated position config: {:?}", position);
+                local_config.position = Some(position);
+            }
+            protobufs::config::PayloadVariant::Power(power) => {
+                trace!("Updated power config: {:?}", power);
+                local_config.power = Some(power);
+            }
+            protobufs::config::PayloadVariant::Network(network) => {
+                trace!("Updated network config: {:?}", network);
+                local_config.network = Some(network);
+            }
+            protobufs::config::PayloadVariant::Display(display) => {
+                trace!("Updated display config: {:?}", display);
+                local_config.display = Some(display);
+            }
+            protobufs::config::PayloadVariant::Lora(lora) => {
+                trace!("Updated LoRa config: {:?}", lora);
+                local_config.lora = Some(lora);
+            }
+            protobufs::config::PayloadVariant::Bluetooth(bluetooth) => {
+                trace!("Updated bluetooth config: {:?}", bluetooth);
+                local_config.bluetooth = Some(bluetooth);
+            }
+        }
+    }
+}
+
+fn merge_module_config(
+    local_module_config: &mut protobufs::LocalModuleConfig,
+    module_config: protobufs::ModuleConfig,
+) {
+    if let Some(payload_variant) = module_config.payload_variant {
+        match payload_variant {
+            protobufs::module_config::PayloadVariant::Audio(config) => {
+                trace!("Updated audio module config: {:?}", config);
+                local_module_config.audio = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::CannedMessage(config) => {
+                trace!("Updated canned message module config: {:?}", config);
+                local_module_config.canned_message = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::ExternalNotification(config) => {
+                trace!("Updated external notification module config: {:?}", config);
+                local_module_config.external_notification = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::Mqtt(config) => {
+                trace!("Updated mqtt module config: {:?}", config);
+                local_module_config.mqtt = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::RangeTest(config) => {
+                trace!("Updated range test module config: {:?}", config);
+                local_module_config.range_test = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::RemoteHardware(config) => {
+                trace!("Updated remote hardware module config: {:?}", config);
+                local_module_config.remote_hardware = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::Serial(config) => {
+                trace!("Updated serial module config: {:?}", config);
+                local_module_config.serial = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::StoreForward(config) => {
+                trace!("Updated store-forward module config: {:?}", config);
+                local_module_config.store_forward = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::Telemetry(config) => {
+                trace!("Updated telemetry module config: {:?}", config);
+                local_module_config.telemetry = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::NeighborInfo(config) => {
+                trace!("Updated neighbor info module config: {:?}", config);
+                local_module_config.neighbor_info = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::AmbientLighting(config) => {
+                trace!("Updated ambient lighting module config: {:?}", config);
+                local_module_config.ambient_lighting = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::DetectionSensor(config) => {
+                trace!("Updated detection sensor module config: {:?}", config);
+                local_module_config.detection_sensor = Some(config);
+            }
+            protobufs::module_config::PayloadVariant::Paxcounter(config) => {
+                trace!("Updated paxcounter module config: {:?}", config);
+                local_module_config.paxcounter = Some(config);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mesh_packet(from: u32) -> protobufs::MeshPacket {
+        protobufs::MeshPacket {
+            from,
+            rx_snr: 6.5,
+            rx_rssi: -90,
+            hop_start: 3,
+            hop_limit: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn position_from_unknown_node_uses_sender_num() {
+        let mut device = MeshDevice::new();
+        device.my_node_info.my_node_num = 1;
+
+        device.add_position(PositionPacket {
+            packet: mesh_packet(42),
+            data: protobufs::Position::default(),
+        });
+
+        let node = device.nodes.get(&42).expect("Node not inserted");
+        let last_heard = node.last_heard.as_ref().expect("Last heard not set");
+
+        assert_eq!(node.node_num, 42);
+        assert_eq!(node.position_metrics.len(), 1);
+        assert_eq!(last_heard.rssi, -90);
+        assert_eq!(last_heard.hops_away, Some(2));
+    }
+
+    #[test]
+    fn telemetry_from_unknown_node_is_stored() {
+        let mut device = MeshDevice::new();
+
+        device.set_device_metrics(TelemetryPacket {
+            packet: mesh_packet(42),
+            data: protobufs::Telemetry {
+                variant: Some(protobufs::telemetry::Variant::DeviceMetrics(
+                    protobufs::DeviceMetrics {
+                        battery_level: 12,
+                        ..Default::default()
+                    },
+                )),
+                ..Default::default()
+            },
+        });
+
+        assert_eq!(device.nodes.get(&42).unwrap().device_metrics.len(), 1);
+        assert_eq!(device.device_metrics.battery_level, 0);
+    }
+
+    #[test]
+    fn detection_events_keep_last_position_and_are_bounded() {
+        let mut device = MeshDevice::new();
+
+        device.add_position(PositionPacket {
+            packet: mesh_packet(42),
+            data: protobufs::Position::default(),
+        });
+
+        for i in 0..MAX_DETECTION_EVENTS_PER_NODE + 5 {
+            device.add_detection_event(&mesh_packet(42), format!("Door {}", i));
+        }
+
+        let events = device.detection_events.get(&42).unwrap();
+
+        assert_eq!(events.len(), MAX_DETECTION_EVENTS_PER_NODE);
+        assert_eq!(events[0].text, "Door 5");
+        assert!(events[0].position.is_some());
+    }
+
+    #[test]
+    fn paxcounts_recorded_as_telemetry_series() {
+        let mut device = MeshDevice::new();
+
+        device.add_paxcount(PaxcountPacket {
+            packet: mesh_packet(42),
+            data: protobufs::Paxcount {
+                wifi: 12,
+                ble: 30,
+                uptime: 600,
+            },
+        });
+
+        let node = device.nodes.get(&42).unwrap();
+        let range = node.telemetry_in_range(TelemetrySeriesKind::Paxcount, 0, u32::MAX);
+
+        assert_eq!(node.paxcount_metrics.len(), 1);
+        assert_eq!(range.aggregates[0].fields.get("ble").unwrap().max, 30.0);
+    }
+}
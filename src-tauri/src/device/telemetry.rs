@@ -0,0 +1,585 @@
+use std::collections::{HashMap, VecDeque};
+
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use super::helpers::get_current_time_u32;
+use super::{
+    MeshNode, MeshNodeAirQualityMetrics, MeshNodeDeviceMetrics, MeshNodeEnvironmentMetrics,
+    MeshNodePaxcountMetrics, MeshNodePositionMetrics, MeshNodePowerMetrics, NormalizedPosition,
+};
+
+const AGGREGATE_BUCKET_SECS: u32 = 60 * 60;
+
+/// Limits applied to every telemetry series stored on a node
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryRetentionPolicy {
+    pub max_raw_samples: u32, // raw samples kept per series before the oldest are dropped
+    pub max_hourly_aggregates: u32, // hourly buckets kept per series
+    pub recent_samples: u32,  // samples included in `device_update` events
+}
+
+impl Default for TelemetryRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_raw_samples: 1000,
+            max_hourly_aggregates: 24 * 7,
+            recent_samples: 20,
+        }
+    }
+}
+
+/// A single telemetry sample that can be stored in a `TelemetrySeries`
+pub trait TelemetrySample {
+    /// Time the sample was received, in seconds since the epoch
+    fn timestamp(&self) -> u32;
+
+    /// Named numeric values that are aggregated into hourly buckets
+    fn numeric_fields(&self) -> Vec<(&'static str, f64)>;
+}
+
+impl TelemetrySample for MeshNodeDeviceMetrics {
+    fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    fn numeric_fields(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("batteryLevel", self.metrics.battery_level as f64),
+            ("voltage", self.metrics.voltage as f64),
+            (
+                "channelUtilization",
+                self.metrics.channel_utilization as f64,
+            ),
+            ("airUtilTx", self.metrics.air_util_tx as f64),
+            ("snr", self.snr as f64),
+        ]
+    }
+}
+
+impl TelemetrySample for MeshNodeEnvironmentMetrics {
+    fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    fn numeric_fields(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("temperature", self.metrics.temperature as f64),
+            ("relativeHumidity", self.metrics.relative_humidity as f64),
+            (
+                "barometricPressure",
+                self.metrics.barometric_pressure as f64,
+            ),
+            ("gasResistance", self.metrics.gas_resistance as f64),
+            ("voltage", self.metrics.voltage as f64),
+            ("current", self.metrics.current as f64),
+            ("snr", self.snr as f64),
+        ]
+    }
+}
+
+impl TelemetrySample for MeshNodeAirQualityMetrics {
+    fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    fn numeric_fields(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("pm10Standard", self.metrics.pm10_standard as f64),
+            ("pm25Standard", self.metrics.pm25_standard as f64),
+            ("pm100Standard", self.metrics.pm100_standard as f64),
+            ("pm10Environmental", self.metrics.pm10_environmental as f64),
+            ("pm25Environmental", self.metrics.pm25_environmental as f64),
+            (
+                "pm100Environmental",
+                self.metrics.pm100_environmental as f64,
+            ),
+            ("particles03um", self.metrics.particles_03um as f64),
+            ("particles05um", self.metrics.particles_05um as f64),
+            ("particles10um", self.metrics.particles_10um as f64),
+            ("particles25um", self.metrics.particles_25um as f64),
+            ("particles50um", self.metrics.particles_50um as f64),
+            ("particles100um", self.metrics.particles_100um as f64),
+            ("snr", self.snr as f64),
+        ]
+    }
+}
+
+impl TelemetrySample for MeshNodePowerMetrics {
+    fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    fn numeric_fields(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("ch1Voltage", self.metrics.ch1_voltage as f64),
+            ("ch1Current", self.metrics.ch1_current as f64),
+            ("ch2Voltage", self.metrics.ch2_voltage as f64),
+            ("ch2Current", self.metrics.ch2_current as f64),
+            ("ch3Voltage", self.metrics.ch3_voltage as f64),
+            ("ch3Current", self.metrics.ch3_current as f64),
+            ("snr", self.snr as f64),
+        ]
+    }
+}
+
+impl TelemetrySample for MeshNodePaxcountMetrics {
+    fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    fn numeric_fields(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("wifi", self.metrics.wifi as f64),
+            ("ble", self.metrics.ble as f64),
+            ("uptime", self.metrics.uptime as f64),
+            ("snr", self.snr as f64),
+        ]
+    }
+}
+
+impl TelemetrySample for MeshNodePositionMetrics {
+    fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    fn numeric_fields(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("latitude", self.metrics.latitude as f64),
+            ("longitude", self.metrics.longitude as f64),
+            ("altitude", self.metrics.altitude as f64),
+            ("satsInView", self.metrics.sats_in_view as f64),
+            ("snr", self.snr as f64),
+        ]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryFieldAggregate {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub count: u32,
+}
+
+impl TelemetryFieldAggregate {
+    fn new(value: f64) -> Self {
+        Self {
+            min: value,
+            max: value,
+            mean: value,
+            count: 1,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.mean += (value - self.mean) / self.count as f64;
+    }
+}
+
+/// Summary of all samples in a series received within one hour
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryAggregate {
+    pub bucket_start: u32, // secs
+    pub sample_count: u32,
+    pub fields: HashMap<String, TelemetryFieldAggregate>,
+}
+
+impl TelemetryAggregate {
+    fn new(bucket_start: u32) -> Self {
+        Self {
+            bucket_start,
+            sample_count: 0,
+            fields: HashMap::new(),
+        }
+    }
+
+    fn add<T: TelemetrySample>(&mut self, sample: &T) {
+        self.sample_count += 1;
+
+        for (name, value) in sample.numeric_fields() {
+            if !value.is_finite() {
+                continue;
+            }
+
+            self.fields
+                .entry(name.to_string())
+                .and_modify(|field| field.add(value))
+                .or_insert_with(|| TelemetryFieldAggregate::new(value));
+        }
+    }
+}
+
+/// A bounded telemetry series made up of a ring buffer of raw samples and
+/// hourly aggregates covering a longer period than the raw samples.
+#[derive(Clone, Debug)]
+pub struct TelemetrySeries<T> {
+    samples: VecDeque<T>,
+    hourly: VecDeque<TelemetryAggregate>,
+}
+
+impl<T> Default for TelemetrySeries<T> {
+    fn default() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            hourly: VecDeque::new(),
+        }
+    }
+}
+
+impl<T: TelemetrySample + Clone> TelemetrySeries<T> {
+    pub fn push(&mut self, sample: T, policy: &TelemetryRetentionPolicy) {
+        self.add_to_aggregate(&sample);
+
+        // Samples are almost always received in order, so only out-of-order
+        // samples pay for a search
+        let position = self
+            .samples
+            .iter()
+            .rposition(|s| s.timestamp() <= sample.timestamp())
+            .map(|p| p + 1)
+            .unwrap_or(0);
+
+        self.samples.insert(position, sample);
+        self.apply_policy(policy);
+    }
+
+    pub fn apply_policy(&mut self, policy: &TelemetryRetentionPolicy) {
+        while self.samples.len() > policy.max_raw_samples as usize {
+            self.samples.pop_front();
+        }
+
+        while self.hourly.len() > policy.max_hourly_aggregates as usize {
+            self.hourly.pop_front();
+        }
+    }
+
+    /// Returns the raw samples received within `[start, end]`, in seconds
+    pub fn samples_in_range(&self, start: u32, end: u32) -> Vec<T> {
+        self.samples
+            .iter()
+            .filter(|s| s.timestamp() >= start && s.timestamp() <= end)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the hourly aggregates overlapping `[start, end]`, in seconds
+    pub fn aggregates_in_range(&self, start: u32, end: u32) -> Vec<TelemetryAggregate> {
+        self.hourly
+            .iter()
+            .filter(|a| {
+                a.bucket_start.saturating_add(AGGREGATE_BUCKET_SECS) > start
+                    && a.bucket_start <= end
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn add_to_aggregate(&mut self, sample: &T) {
+        let bucket_start = sample.timestamp() - sample.timestamp() % AGGREGATE_BUCKET_SECS;
+
+        match self
+            .hourly
+            .iter()
+            .rposition(|a| a.bucket_start <= bucket_start)
+        {
+            Some(index) if self.hourly[index].bucket_start == bucket_start => {
+                self.hourly[index].add(sample);
+            }
+            Some(index) => {
+                let mut aggregate = TelemetryAggregate::new(bucket_start);
+                aggregate.add(sample);
+                self.hourly.insert(index + 1, aggregate);
+            }
+            None => {
+                let mut aggregate = TelemetryAggregate::new(bucket_start);
+                aggregate.add(sample);
+                self.hourly.push_front(aggregate);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TelemetrySeriesKind {
+    DeviceMetrics,
+    EnvironmentMetrics,
+    AirQualityMetrics,
+    PowerMetrics,
+    Paxcount,
+    Position,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", tag = "kind", content = "samples")]
+pub enum TelemetrySamples {
+    DeviceMetrics(Vec<MeshNodeDeviceMetrics>),
+    EnvironmentMetrics(Vec<MeshNodeEnvironmentMetrics>),
+    AirQualityMetrics(Vec<MeshNodeAirQualityMetrics>),
+    PowerMetrics(Vec<MeshNodePowerMetrics>),
+    Paxcount(Vec<MeshNodePaxcountMetrics>),
+    Position(Vec<MeshNodePositionMetrics>),
+}
+
+/// Historical telemetry for a node, returned by range queries
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeTelemetryRange {
+    pub node_num: u32,
+    pub samples: TelemetrySamples,
+    pub aggregates: Vec<TelemetryAggregate>,
+}
+
+impl MeshNode {
+    /// Copies the node without its telemetry history, which isn't serialized
+    /// and can be large, for use in event payloads
+    pub fn clone_without_history(&self) -> Self {
+        Self {
+            node_num: self.node_num,
+            last_heard: self.last_heard.clone(),
+            user: self.user.clone(),
+            device_metrics: self.device_metrics.clone(),
+            environment_metrics: self.environment_metrics.clone(),
+            air_quality_metrics: self.air_quality_metrics.clone(),
+            power_metrics: self.power_metrics.clone(),
+            paxcount_metrics: self.paxcount_metrics.clone(),
+            position_metrics: self.position_metrics.clone(),
+            ..Self::new(self.node_num)
+        }
+    }
+
+    pub fn record_device_metrics(
+        &mut self,
+        sample: MeshNodeDeviceMetrics,
+        policy: &TelemetryRetentionPolicy,
+    ) {
+        self.device_metrics.push(sample.clone());
+        truncate_recent(&mut self.device_metrics, policy);
+        self.device_metrics_history.push(sample, policy);
+    }
+
+    pub fn record_environment_metrics(
+        &mut self,
+        sample: MeshNodeEnvironmentMetrics,
+        policy: &TelemetryRetentionPolicy,
+    ) {
+        self.environment_metrics.push(sample.clone());
+        truncate_recent(&mut self.environment_metrics, policy);
+        self.environment_metrics_history.push(sample, policy);
+    }
+
+    pub fn record_air_quality_metrics(
+        &mut self,
+        sample: MeshNodeAirQualityMetrics,
+        policy: &TelemetryRetentionPolicy,
+    ) {
+        self.air_quality_metrics.push(sample.clone());
+        truncate_recent(&mut self.air_quality_metrics, policy);
+        self.air_quality_metrics_history.push(sample, policy);
+    }
+
+    pub fn record_power_metrics(
+        &mut self,
+        sample: MeshNodePowerMetrics,
+        policy: &TelemetryRetentionPolicy,
+    ) {
+        self.power_metrics.push(sample.clone());
+        truncate_recent(&mut self.power_metrics, policy);
+        self.power_metrics_history.push(sample, policy);
+    }
+
+    pub fn record_paxcount(
+        &mut self,
+        sample: MeshNodePaxcountMetrics,
+        policy: &TelemetryRetentionPolicy,
+    ) {
+        self.paxcount_metrics.push(sample.clone());
+        truncate_recent(&mut self.paxcount_metrics, policy);
+        self.paxcount_history.push(sample, policy);
+    }
+
+    pub fn record_position(
+        &mut self,
+        position: NormalizedPosition,
+        snr: f32,
+        policy: &TelemetryRetentionPolicy,
+    ) {
+        self.position_metrics.push(position.clone());
+        truncate_recent(&mut self.position_metrics, policy);
+        self.position_history.push(
+            MeshNodePositionMetrics {
+                metrics: position,
+                timestamp: get_current_time_u32(),
+                snr,
+            },
+            policy,
+        );
+    }
+
+    pub fn telemetry_in_range(
+        &self,
+        kind: TelemetrySeriesKind,
+        start: u32,
+        end: u32,
+    ) -> NodeTelemetryRange {
+        let (samples, aggregates) = match kind {
+            TelemetrySeriesKind::DeviceMetrics => (
+                TelemetrySamples::DeviceMetrics(
+                    self.device_metrics_history.samples_in_range(start, end),
+                ),
+                self.device_metrics_history.aggregates_in_range(start, end),
+            ),
+            TelemetrySeriesKind::EnvironmentMetrics => (
+                TelemetrySamples::EnvironmentMetrics(
+                    self.environment_metrics_history
+                        .samples_in_range(start, end),
+                ),
+                self.environment_metrics_history
+                    .aggregates_in_range(start, end),
+            ),
+            TelemetrySeriesKind::AirQualityMetrics => (
+                TelemetrySamples::AirQualityMetrics(
+                    self.air_quality_metrics_history
+                        .samples_in_range(start, end),
+                ),
+                self.air_quality_metrics_history
+                    .aggregates_in_range(start, end),
+            ),
+            TelemetrySeriesKind::PowerMetrics => (
+                TelemetrySamples::PowerMetrics(
+                    self.power_metrics_history.samples_in_range(start, end),
+                ),
+                self.power_metrics_history.aggregates_in_range(start, end),
+            ),
+            TelemetrySeriesKind::Paxcount => (
+                TelemetrySamples::Paxcount(self.paxcount_history.samples_in_range(start, end)),
+                self.paxcount_history.aggregates_in_range(start, end),
+            ),
+            TelemetrySeriesKind::Position => (
+                TelemetrySamples::Position(self.position_history.samples_in_range(start, end)),
+                self.position_history.aggregates_in_range(start, end),
+            ),
+        };
+
+        NodeTelemetryRange {
+            node_num: self.node_num,
+            samples,
+            aggregates,
+        }
+    }
+
+    pub fn apply_retention_policy(&mut self, policy: &TelemetryRetentionPolicy) {
+        self.device_metrics_history.apply_policy(policy);
+        self.environment_metrics_history.apply_policy(policy);
+        self.air_quality_metrics_history.apply_policy(policy);
+        self.power_metrics_history.apply_policy(policy);
+        self.paxcount_history.apply_policy(policy);
+        self.position_history.apply_policy(policy);
+
+        truncate_recent(&mut self.device_metrics, policy);
+        truncate_recent(&mut self.environment_metrics, policy);
+        truncate_recent(&mut self.air_quality_metrics, policy);
+        truncate_recent(&mut self.power_metrics, policy);
+        truncate_recent(&mut self.paxcount_metrics, policy);
+        truncate_recent(&mut self.position_metrics, policy);
+    }
+}
+
+/// Drops the oldest entries of a recent-sample list sent to the UI
+fn truncate_recent<T>(recent: &mut Vec<T>, policy: &TelemetryRetentionPolicy) {
+    let limit = policy.recent_samples as usize;
+
+    if recent.len() > limit {
+        recent.drain(..recent.len() - limit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    struct TestSample(u32, f64);
+
+    impl TelemetrySample for TestSample {
+        fn timestamp(&self) -> u32 {
+            self.0
+        }
+
+        fn numeric_fields(&self) -> Vec<(&'static str, f64)> {
+            vec![("value", self.1)]
+        }
+    }
+
+    fn policy(max_raw_samples: u32, max_hourly_aggregates: u32) -> TelemetryRetentionPolicy {
+        TelemetryRetentionPolicy {
+            max_raw_samples,
+            max_hourly_aggregates,
+            recent_samples: 1,
+        }
+    }
+
+    #[test]
+    fn raw_samples_are_bounded() {
+        let policy = policy(3, 10);
+        let mut series = TelemetrySeries::default();
+
+        for i in 0..5 {
+            series.push(TestSample(i * 60, i as f64), &policy);
+        }
+
+        let samples = series.samples_in_range(0, u32::MAX);
+
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].0, 120);
+    }
+
+    #[test]
+    fn samples_aggregated_per_hour() {
+        let policy = policy(100, 10);
+        let mut series = TelemetrySeries::default();
+
+        series.push(TestSample(0, 1.0), &policy);
+        series.push(TestSample(1800, 3.0), &policy);
+        series.push(TestSample(3600, 10.0), &policy);
+
+        let aggregates = series.aggregates_in_range(0, u32::MAX);
+        let first = aggregates[0].fields.get("value").unwrap();
+
+        assert_eq!(aggregates.len(), 2);
+        assert_eq!(aggregates[0].sample_count, 2);
+        assert_eq!(first.min, 1.0);
+        assert_eq!(first.max, 3.0);
+        assert_eq!(first.mean, 2.0);
+    }
+
+    #[test]
+    fn out_of_order_samples_kept_sorted() {
+        let policy = policy(100, 10);
+        let mut series = TelemetrySeries::default();
+
+        series.push(TestSample(7200, 1.0), &policy);
+        series.push(TestSample(0, 1.0), &policy);
+
+        let timestamps: Vec<u32> = series
+            .samples_in_range(0, u32::MAX)
+            .iter()
+            .map(|s| s.0)
+            .collect();
+        let buckets: Vec<u32> = series
+            .aggregates_in_range(0, u32::MAX)
+            .iter()
+            .map(|a| a.bucket_start)
+            .collect();
+
+        assert_eq!(timestamps, vec![0, 7200]);
+        assert_eq!(buckets, vec![0, 7200]);
+    }
+}
@@ -0,0 +1,380 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use super::telemetry::{TelemetrySample, TelemetrySeries, TelemetrySeriesKind};
+use super::{MeshDevice, MeshNode};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryExportError {
+    #[error("Failed to write export file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Export directory {0} does not exist")]
+    MissingDirectory(String),
+    #[error("Export file {0} already exists")]
+    FileExists(String),
+    #[error("This build does not support Parquet export")]
+    ParquetUnsupported,
+    #[cfg(feature = "parquet-export")]
+    #[error("Failed to write Parquet file: {0}")]
+    Parquet(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TelemetryExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl TelemetryExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            TelemetryExportFormat::Csv => "csv",
+            TelemetryExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryExportOptions {
+    pub directory: String,
+    pub format: TelemetryExportFormat,
+    pub kinds: Vec<TelemetrySeriesKind>,
+    pub node_nums: Option<Vec<u32>>, // all nodes when unset
+    pub start: Option<u32>,          // secs, inclusive
+    pub end: Option<u32>,            // secs, inclusive
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedTelemetryFile {
+    pub kind: TelemetrySeriesKind,
+    pub path: String,
+    pub rows: u32,
+}
+
+/// Samples from one telemetry series across many nodes, with one row per
+/// sample and one column per numeric field.
+struct TelemetryTable {
+    fields: Vec<&'static str>,
+    rows: Vec<TelemetryRow>,
+}
+
+struct TelemetryRow {
+    node_num: u32,
+    timestamp: u32,
+    values: Vec<f64>,
+}
+
+impl TelemetryTable {
+    fn new() -> Self {
+        Self {
+            fields: vec![],
+            rows: vec![],
+        }
+    }
+
+    fn extend_from_series<T: TelemetrySample + Clone>(
+        &mut self,
+        node_num: u32,
+        series: &TelemetrySeries<T>,
+        start: u32,
+        end: u32,
+    ) {
+        for sample in series.samples_in_range(start, end) {
+            let fields = sample.numeric_fields();
+
+            if self.fields.is_empty() {
+                self.fields = fields.iter().map(|(name, _)| *name).collect();
+            }
+
+            self.rows.push(TelemetryRow {
+                node_num,
+                timestamp: sample.timestamp(),
+                values: fields.into_iter().map(|(_, value)| value).collect(),
+            });
+        }
+    }
+}
+
+fn format_node_id(node_num: u32) -> String {
+    format!("!{:08x}", node_num)
+}
+
+fn format_timestamp(timestamp: u32) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+fn series_file_name(kind: TelemetrySeriesKind) -> &'static str {
+    match kind {
+        TelemetrySeriesKind::DeviceMetrics => "device_metrics",
+        TelemetrySeriesKind::EnvironmentMetrics => "environment_metrics",
+        TelemetrySeriesKind::AirQualityMetrics => "air_quality_metrics",
+        TelemetrySeriesKind::PowerMetrics => "power_metrics",
+        TelemetrySeriesKind::Paxcount => "paxcounts",
+        TelemetrySeriesKind::Position => "positions",
+    }
+}
+
+/// Creates a new export file, refusing to overwrite an earlier export
+fn create_export_file(path: &Path) -> Result<File, TelemetryExportError> {
+    File::options()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => {
+                TelemetryExportError::FileExists(path.to_string_lossy().to_string())
+            }
+            _ => TelemetryExportError::Io(e),
+        })
+}
+
+fn build_table(
+    nodes: &[&MeshNode],
+    kind: TelemetrySeriesKind,
+    start: u32,
+    end: u32,
+) -> TelemetryTable {
+    let mut table = TelemetryTable::new();
+
+    for node in nodes {
+        let num = node.node_num;
+
+        match kind {
+            TelemetrySeriesKind::DeviceMetrics => {
+                table.extend_from_series(num, &node.device_metrics_history, start, end)
+            }
+            TelemetrySeriesKind::EnvironmentMetrics => {
+                table.extend_from_series(num, &node.environment_metrics_history, start, end)
+            }
+            TelemetrySeriesKind::AirQualityMetrics => {
+                table.extend_from_series(num, &node.air_quality_metrics_history, start, end)
+            }
+            TelemetrySeriesKind::PowerMetrics => {
+                table.extend_from_series(num, &node.power_metrics_history, start, end)
+            }
+            TelemetrySeriesKind::Paxcount => {
+                table.extend_from_series(num, &node.paxcount_history, start, end)
+            }
+            TelemetrySeriesKind::Position => {
+                table.extend_from_series(num, &node.position_history, start, end)
+            }
+        }
+    }
+
+    table.rows.sort_by_key(|row| (row.timestamp, row.node_num));
+
+    table
+}
+
+fn write_csv(path: &Path, table: &TelemetryTable) -> Result<(), TelemetryExportError> {
+    let mut writer = BufWriter::new(create_export_file(path)?);
+
+    let mut header = vec!["nodeId", "nodeNum", "timestamp"];
+    header.extend(table.fields.iter());
+    writeln!(writer, "{}", header.join(","))?;
+
+    for row in table.rows.iter() {
+        write!(
+            writer,
+            "{},{},{}",
+            format_node_id(row.node_num),
+            row.node_num,
+            format_timestamp(row.timestamp)
+        )?;
+
+        for value in row.values.iter() {
+            // Leave non-finite values empty so they're read as missing
+            if value.is_finite() {
+                write!(writer, ",{}", value)?;
+            } else {
+                write!(writer, ",")?;
+            }
+        }
+
+        writeln!(writer)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(feature = "parquet-export")]
+fn write_parquet(path: &Path, table: &TelemetryTable) -> Result<(), TelemetryExportError> {
+    use std::sync::Arc;
+
+    use arrow_array::{
+        ArrayRef, Float64Array, RecordBatch, StringArray, TimestampSecondArray, UInt32Array,
+    };
+    use arrow_schema::{DataType, Field, Schema, TimeUnit};
+    use parquet::arrow::ArrowWriter;
+
+    let to_error = |e: &dyn std::fmt::Display| TelemetryExportError::Parquet(e.to_string());
+
+    let mut schema_fields = vec![
+        Field::new("nodeId", DataType::Utf8, false),
+        Field::new("nodeNum", DataType::UInt32, false),
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Second, Some("UTC".into())),
+            false,
+        ),
+    ];
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            table.rows.iter().map(|row| format_node_id(row.node_num)),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            table.rows.iter().map(|row| row.node_num),
+        )),
+        Arc::new(
+            TimestampSecondArray::from_iter_values(
+                table.rows.iter().map(|row| row.timestamp as i64),
+            )
+            .with_timezone("UTC"),
+        ),
+    ];
+
+    for (index, name) in table.fields.iter().enumerate() {
+        schema_fields.push(Field::new(*name, DataType::Float64, true));
+        columns.push(Arc::new(Float64Array::from_iter(table.rows.iter().map(
+            |row| Some(row.values[index]).filter(|value| value.is_finite()),
+        ))));
+    }
+
+    let schema = Arc::new(Schema::new(schema_fields));
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| to_error(&e))?;
+
+    let mut writer =
+        ArrowWriter::try_new(create_export_file(path)?, schema, None).map_err(|e| to_error(&e))?;
+
+    writer.write(&batch).map_err(|e| to_error(&e))?;
+    writer.close().map_err(|e| to_error(&e))?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet-export"))]
+fn write_parquet(_path: &Path, _table: &TelemetryTable) -> Result<(), TelemetryExportError> {
+    Err(TelemetryExportError::ParquetUnsupported)
+}
+
+/// Writes one file per requested telemetry series into the export
+/// directory, containing samples from all selected nodes. File names
+/// carry the time of the export, and existing files are never replaced.
+pub fn export_telemetry(
+    device: &MeshDevice,
+    options: &TelemetryExportOptions,
+) -> Result<Vec<ExportedTelemetryFile>, TelemetryExportError> {
+    let directory = PathBuf::from(&options.directory);
+
+    if !directory.is_dir() {
+        return Err(TelemetryExportError::MissingDirectory(
+            options.directory.clone(),
+        ));
+    }
+
+    if cfg!(not(feature = "parquet-export")) && options.format == TelemetryExportFormat::Parquet {
+        return Err(TelemetryExportError::ParquetUnsupported);
+    }
+
+    let mut nodes: Vec<&MeshNode> = device
+        .nodes
+        .values()
+        .filter(|node| match &options.node_nums {
+            Some(nums) => nums.contains(&node.node_num),
+            None => true,
+        })
+        .collect();
+
+    nodes.sort_by_key(|node| node.node_num);
+
+    let start = options.start.unwrap_or(0);
+    let end = options.end.unwrap_or(u32::MAX);
+
+    let exported_at = Utc::now().format("%Y%m%dT%H%M%SZ");
+    let mut files = vec![];
+
+    for kind in options.kinds.iter() {
+        let table = build_table(&nodes, *kind, start, end);
+        let path = directory.join(format!(
+            "{}_{}.{}",
+            series_file_name(*kind),
+            exported_at,
+            options.format.extension()
+        ));
+
+        match options.format {
+            TelemetryExportFormat::Csv => write_csv(&path, &table)?,
+            TelemetryExportFormat::Parquet => write_parquet(&path, &table)?,
+        }
+
+        files.push(ExportedTelemetryFile {
+            kind: *kind,
+            path: path.to_string_lossy().to_string(),
+            rows: table.rows.len() as u32,
+        });
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::helpers::generate_rand_id;
+
+    #[test]
+    fn csv_has_one_row_per_sample() {
+        let table = TelemetryTable {
+            fields: vec!["voltage", "snr"],
+            rows: vec![
+                TelemetryRow {
+                    node_num: 0xaabbccdd,
+                    timestamp: 0,
+                    values: vec![3.7, 5.5],
+                },
+                TelemetryRow {
+                    node_num: 1,
+                    timestamp: 60,
+                    values: vec![f64::NAN, -2.0],
+                },
+            ],
+        };
+
+        let path = std::env::temp_dir().join(format!(
+            "telemetry-export-{}.csv",
+            generate_rand_id::<u32>()
+        ));
+        write_csv(&path, &table).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+
+        // A second export to the same file leaves the first one intact
+        let overwrite = write_csv(&path, &TelemetryTable::new());
+        assert!(matches!(
+            overwrite,
+            Err(TelemetryExportError::FileExists(_))
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            contents,
+            "nodeId,nodeNum,timestamp,voltage,snr\n\
+             !aabbccdd,2864434397,1970-01-01T00:00:00Z,3.7,5.5\n\
+             !00000001,1,1970-01-01T00:01:00Z,,-2\n"
+        );
+    }
+}
@@ -6,12 +6,16 @@ use crate::graph::ds::{edge::GraphEdge, graph::MeshGraph, node::GraphNode};
 
 pub const DEFAULT_NODE_TIMEOUT_DURATION: Duration = Duration::from_secs(15 * 60);
 
+/// An edge upserted into the graph, along with its endpoints
+pub type UpdatedEdge = (GraphNode, GraphNode, GraphEdge);
+
 impl MeshGraph {
+    /// Returns the upserted reporting node and all edges updated from its neighbors
     pub fn update_from_neighbor_info(
         &mut self,
         packet: MeshPacket,
         neighbor_info: protobufs::NeighborInfo,
-    ) {
+    ) -> (GraphNode, Vec<UpdatedEdge>) {
         log::info!(
             "Updating graph from neighbor info packet from node {}",
             packet.from
@@ -29,6 +33,8 @@ impl MeshGraph {
 
         self.upsert_node(own_node.clone());
 
+        let mut updated_edges = vec![];
+
         // Update neighbor nodes, don't insert as this isn't how neighbor info works
         for neighbor in neighbor_info.neighbors {
             log::info!("Adding neighbor node {} to graph", neighbor.node_id);
@@ -40,15 +46,17 @@ impl MeshGraph {
                 }
             };
 
-            self.upsert_edge(
-                own_node.clone(),
-                remote_node,
-                GraphEdge::from_neighbor(own_node.node_num, neighbor),
-            );
+            let edge = GraphEdge::from_neighbor(own_node.node_num, neighbor);
+
+            self.upsert_edge(own_node, remote_node, edge.clone());
+            updated_edges.push((own_node, remote_node, edge));
         }
+
+        (own_node, updated_edges)
     }
 
-    pub fn update_from_node_info(&mut self, node_info: protobufs::NodeInfo) {
+    /// Returns the upserted node, or `None` if the node has no position
+    pub fn update_from_node_info(&mut self, node_info: protobufs::NodeInfo) -> Option<GraphNode> {
         log::info!(
             "Updating graph from node info packet from node {}",
             node_info.num
@@ -59,7 +67,7 @@ impl MeshGraph {
                 "Node info packet from node {} has no position, not adding to graph",
                 node_info.num
             );
-            return;
+            return None;
         }
 
         let own_node = match self.get_node(node_info.num) {
@@ -74,10 +82,14 @@ impl MeshGraph {
             },
         };
 
-        self.upsert_node(own_node);
+        Some(self.upsert_node(own_node))
     }
 
-    pub fn update_from_position(&mut self, packet: MeshPacket, _position: protobufs::Position) {
+    pub fn update_from_position(
+        &mut self,
+        packet: MeshPacket,
+        _position: protobufs::Position,
+    ) -> GraphNode {
         log::info!(
             "Updating graph from position packet from node {}",
             packet.from
@@ -95,6 +107,6 @@ impl MeshGraph {
             },
         };
 
-        self.upsert_node(own_node);
+        self.upsert_node(own_node)
     }
 }
@@ -21,6 +21,10 @@ pub struct GraphEdge {
 }
 
 impl GraphEdge {
+    pub fn snr(&self) -> f64 {
+        self.snr
+    }
+
     pub fn from_neighbor(to_node_id: u32, neighbor: Neighbor) -> Self {
         let timeout_secs: u64 = if neighbor.node_broadcast_interval_secs == 0 {
             trace!(
@@ -92,10 +92,15 @@ impl MeshGraph {
     pub fn remove_edge(&mut self, from: GraphNode, to: GraphNode) -> Option<edge::GraphEdge> {
         self.graph.remove_edge(from, to)
     }
+
+    pub fn edges(&self) -> impl Iterator<Item = (GraphNode, GraphNode, &edge::GraphEdge)> {
+        self.graph.all_edges()
+    }
 }
 
 impl MeshGraph {
-    pub fn clean(&mut self) {
+    /// Removes all nodes that have timed out, returning their node numbers
+    pub fn clean(&mut self) -> Vec<u32> {
         let now = chrono::Utc::now().naive_utc();
 
         // Edges will be removed if either the source or target node is removed
@@ -113,9 +118,11 @@ impl MeshGraph {
             }
         }
 
-        for node_num in nodes_to_remove {
-            self.remove_node(node_num);
+        for node_num in nodes_to_remove.iter() {
+            self.remove_node(*node_num);
             log::debug!("Node {} removed from graph", node_num);
         }
+
+        nodes_to_remove
     }
 }
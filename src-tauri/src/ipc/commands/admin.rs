@@ -0,0 +1,332 @@
+use crate::device::admin::encode_admin_message;
+use crate::device::helpers::{get_admin_channel_index, get_current_time_u32};
+use crate::ipc::CommandError;
+use crate::state;
+use crate::state::DeviceKey;
+
+use log::{debug, trace};
+use meshtastic::packet::PacketDestination;
+use meshtastic::protobufs;
+use meshtastic::types::{EncodedMeshPacketData, MeshChannel, NodeId};
+
+/// Sends an admin message to a remote node over the mesh, attaching the
+/// node's session passkey when one has been received recently. Messages that
+/// don't want a response change the node, so they require a passkey.
+async fn send_remote_admin_message(
+    device_key: &DeviceKey,
+    mesh_devices: &state::mesh_devices::MeshDevicesState,
+    radio_connections: &state::radio_connections::RadioConnectionsState,
+    node_num: u32,
+    payload_variant: protobufs::admin_message::PayloadVariant,
+    want_response: bool,
+) -> Result<(), CommandError> {
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(device_key)
+        .ok_or("Device not connected")?;
+
+    let mut connections_guard = radio_connections.inner.lock().await;
+    let connection = connections_guard
+        .get_mut(device_key)
+        .ok_or("Radio connection not initialized")?;
+
+    if node_num == packet_api.device.my_node_info.my_node_num {
+        return Err("Remote admin messages cannot target the connected radio".into());
+    }
+
+    let message = protobufs::AdminMessage {
+        payload_variant: Some(payload_variant),
+    };
+
+    let session_passkey = packet_api
+        .device
+        .remote_nodes
+        .get(&node_num)
+        .and_then(|n| n.active_session_passkey(get_current_time_u32()));
+
+    // Firmware only sends a passkey in response to a request, and rejects
+    // changes made without one
+    if !want_response && session_passkey.is_none() {
+        return Err(format!(
+            "No session passkey from node {}, request its configuration first",
+            node_num
+        )
+        .into());
+    }
+
+    let payload = encode_admin_message(&message, session_passkey);
+    let channel = get_admin_channel_index(&packet_api.device);
+
+    trace!(
+        "Sending admin message to remote node {} on channel {}: {:?}",
+        node_num,
+        channel,
+        message
+    );
+
+    connection
+        .send_mesh_packet(
+            packet_api,
+            EncodedMeshPacketData::new(payload),
+            protobufs::PortNum::AdminApp,
+            PacketDestination::Node(NodeId::new(node_num)),
+            MeshChannel::new(channel).map_err(|e| e.to_string())?,
+            true,
+            want_response,
+            false,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn request_remote_config(
+    device_key: DeviceKey,
+    node_num: u32,
+    config_type: protobufs::admin_message::ConfigType,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called request_remote_config command");
+    trace!("Called on node {} with type {:?}", node_num, config_type);
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::GetConfigRequest(config_type as i32),
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn request_remote_module_config(
+    device_key: DeviceKey,
+    node_num: u32,
+    module_config_type: protobufs::admin_message::ModuleConfigType,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called request_remote_module_config command");
+    trace!(
+        "Called on node {} with type {:?}",
+        node_num,
+        module_config_type
+    );
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::GetModuleConfigRequest(module_config_type as i32),
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn request_remote_channel(
+    device_key: DeviceKey,
+    node_num: u32,
+    channel_index: u32,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called request_remote_channel command");
+    trace!("Called on node {} with channel {}", node_num, channel_index);
+
+    // Firmware expects the channel index offset by one, since protobufs
+    // can't distinguish a zero value from an unset field
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::GetChannelRequest(channel_index + 1),
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn request_remote_owner(
+    device_key: DeviceKey,
+    node_num: u32,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called request_remote_owner command");
+    trace!("Called on node {}", node_num);
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::GetOwnerRequest(true),
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn request_remote_metadata(
+    device_key: DeviceKey,
+    node_num: u32,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called request_remote_metadata command");
+    trace!("Called on node {}", node_num);
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::GetDeviceMetadataRequest(true),
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_remote_config(
+    device_key: DeviceKey,
+    node_num: u32,
+    config: protobufs::Config,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called set_remote_config command");
+    trace!("Called on node {} with config {:?}", node_num, config);
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::SetConfig(config),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_remote_module_config(
+    device_key: DeviceKey,
+    node_num: u32,
+    module_config: protobufs::ModuleConfig,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called set_remote_module_config command");
+    trace!(
+        "Called on node {} with module config {:?}",
+        node_num,
+        module_config
+    );
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::SetModuleConfig(module_config),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_remote_channel(
+    device_key: DeviceKey,
+    node_num: u32,
+    channel: protobufs::Channel,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called set_remote_channel command");
+    trace!("Called on node {} with channel {:?}", node_num, channel);
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::SetChannel(channel),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_remote_owner(
+    device_key: DeviceKey,
+    node_num: u32,
+    user: protobufs::User,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called set_remote_owner command");
+    trace!("Called on node {} with user {:?}", node_num, user);
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::SetOwner(user),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reboot_remote_node(
+    device_key: DeviceKey,
+    node_num: u32,
+    delay_secs: Option<i32>,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called reboot_remote_node command");
+    trace!("Called on node {} with delay {:?}", node_num, delay_secs);
+
+    send_remote_admin_message(
+        &device_key,
+        &mesh_devices,
+        &radio_connections,
+        node_num,
+        protobufs::admin_message::PayloadVariant::RebootSeconds(delay_secs.unwrap_or(5)),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
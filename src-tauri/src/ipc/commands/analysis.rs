@@ -0,0 +1,121 @@
+use crate::analysis::calibration::{self, PropagationCalibration};
+use crate::analysis::coverage::{self, CoverageOptions, CoverageReport};
+use crate::analysis::geo::node_positions;
+use crate::analysis::propagation::LinkBudget;
+use crate::analysis::relay::{self, RelayPlacementOptions, RelayPlacementReport};
+use crate::ipc::CommandError;
+use crate::state;
+use crate::state::DeviceKey;
+
+use log::{debug, info, trace};
+
+#[tauri::command]
+pub async fn recommend_relay_placement(
+    device_key: DeviceKey,
+    options: Option<RelayPlacementOptions>,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    mesh_graph: tauri::State<'_, state::graph::GraphState>,
+    analysis: tauri::State<'_, state::analysis::AnalysisState>,
+) -> Result<RelayPlacementReport, CommandError> {
+    debug!("Called recommend_relay_placement command");
+    trace!("Called with options {:?}", options);
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let model = analysis.inner.lock().await.propagation_model(&device_key);
+    let graph = mesh_graph.inner.lock().map_err(|e| e.to_string())?;
+
+    Ok(relay::recommend_relay_placement(
+        &graph,
+        &node_positions(&packet_api.device),
+        &LinkBudget::from_device(&packet_api.device),
+        &model,
+        &options.unwrap_or_default(),
+    ))
+}
+
+#[tauri::command]
+pub async fn estimate_coverage(
+    device_key: DeviceKey,
+    options: Option<CoverageOptions>,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    analysis: tauri::State<'_, state::analysis::AnalysisState>,
+) -> Result<CoverageReport, CommandError> {
+    debug!("Called estimate_coverage command");
+    trace!("Called with options {:?}", options);
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let model = analysis.inner.lock().await.propagation_model(&device_key);
+
+    let report = coverage::estimate_coverage(
+        &node_positions(&packet_api.device),
+        &LinkBudget::from_device(&packet_api.device),
+        &model,
+        &options.unwrap_or_default(),
+    )
+    .ok_or("No nodes have reported a position")?;
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn calibrate_propagation_model(
+    device_key: DeviceKey,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    mesh_graph: tauri::State<'_, state::graph::GraphState>,
+    analysis: tauri::State<'_, state::analysis::AnalysisState>,
+) -> Result<PropagationCalibration, CommandError> {
+    debug!("Called calibrate_propagation_model command");
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let budget = LinkBudget::from_device(&packet_api.device);
+
+    let samples = {
+        let graph = mesh_graph.inner.lock().map_err(|e| e.to_string())?;
+        let positions = node_positions(&packet_api.device);
+
+        calibration::collect_samples(&packet_api.device, &graph, &positions, &budget)
+    };
+
+    let calibration = calibration::fit_propagation_model(samples, &budget)?;
+
+    info!(
+        "Calibrated propagation model from {} links: exponent {:.2}, fade margin {:.1} dB, R² {:.2}",
+        calibration.sample_count,
+        calibration.model.path_loss_exponent,
+        calibration.model.fade_margin_db,
+        calibration.r_squared
+    );
+
+    analysis
+        .inner
+        .lock()
+        .await
+        .calibrations
+        .insert(device_key, calibration.clone());
+
+    Ok(calibration)
+}
+
+#[tauri::command]
+pub async fn reset_propagation_model(
+    device_key: DeviceKey,
+    analysis: tauri::State<'_, state::analysis::AnalysisState>,
+) -> Result<(), CommandError> {
+    debug!("Called reset_propagation_model command");
+
+    analysis.inner.lock().await.calibrations.remove(&device_key);
+
+    Ok(())
+}
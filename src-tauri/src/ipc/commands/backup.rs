@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+use crate::device::backup::{diff_config_backups, ConfigFieldDiff, DeviceConfigBackup};
+use crate::ipc::helpers::apply_device_bulk_config;
+use crate::ipc::CommandError;
+use crate::ipc::DeviceBulkConfig;
+use crate::state;
+use crate::state::DeviceKey;
+
+use log::{debug, trace};
+
+#[tauri::command]
+pub async fn export_device_config(
+    device_key: DeviceKey,
+    file_path: String,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<DeviceConfigBackup, CommandError> {
+    debug!("Called export_device_config command");
+    trace!("Called with file path {}", file_path);
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let backup = DeviceConfigBackup::from_device(&packet_api.device);
+
+    backup
+        .write_to_file(&PathBuf::from(file_path))
+        .map_err(|e| e.to_string())?;
+
+    Ok(backup)
+}
+
+#[tauri::command]
+pub async fn diff_device_config(
+    device_key: DeviceKey,
+    file_path: String,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<Vec<ConfigFieldDiff>, CommandError> {
+    debug!("Called diff_device_config command");
+    trace!("Called with file path {}", file_path);
+
+    let incoming_backup =
+        DeviceConfigBackup::read_from_file(&PathBuf::from(file_path)).map_err(|e| e.to_string())?;
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let current_backup = DeviceConfigBackup::from_device(&packet_api.device);
+
+    let diffs =
+        diff_config_backups(&current_backup, &incoming_backup).map_err(|e| e.to_string())?;
+
+    Ok(diffs)
+}
+
+#[tauri::command]
+pub async fn import_device_config(
+    device_key: DeviceKey,
+    file_path: String,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called import_device_config command");
+    trace!("Called with file path {}", file_path);
+
+    let backup =
+        DeviceConfigBackup::read_from_file(&PathBuf::from(file_path)).map_err(|e| e.to_string())?;
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    packet_api
+        .device
+        .compatibility
+        .require_module_config(&backup.module_config)?;
+
+    let mut connections_guard = radio_connections.inner.lock().await;
+    let connection = connections_guard
+        .get_mut(&device_key)
+        .ok_or("Radio connection not initialized")?;
+
+    apply_device_bulk_config(
+        connection,
+        packet_api,
+        backup.owner,
+        DeviceBulkConfig {
+            radio: Some(backup.config),
+            module: Some(backup.module_config),
+            channels: Some(backup.channels),
+        },
+    )
+    .await?;
+
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::device::channel_url::{
+    build_channel_set, decode_channel_url, encode_channel_url, preview_channel_import,
+    render_channel_url_qr_code, ChannelImportPreview, ChannelUrl,
+};
+use crate::ipc::helpers::apply_device_bulk_config;
+use crate::ipc::CommandError;
+use crate::ipc::DeviceBulkConfig;
+use crate::state;
+use crate::state::DeviceKey;
+
+use log::{debug, trace};
+use meshtastic::protobufs;
+
+#[tauri::command]
+pub async fn export_channel_url(
+    device_key: DeviceKey,
+    include_lora_config: bool,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<ChannelUrl, CommandError> {
+    debug!("Called export_channel_url command");
+    trace!("Called with include_lora_config {}", include_lora_config);
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let channel_set = build_channel_set(&packet_api.device, include_lora_config);
+    let url = encode_channel_url(&channel_set);
+    let qr_code_svg = render_channel_url_qr_code(&url).map_err(|e| e.to_string())?;
+
+    Ok(ChannelUrl { url, qr_code_svg })
+}
+
+#[tauri::command]
+pub async fn preview_channel_url(
+    device_key: DeviceKey,
+    url: String,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<ChannelImportPreview, CommandError> {
+    debug!("Called preview_channel_url command");
+    trace!("Called with url {}", url);
+
+    let parsed_url = decode_channel_url(&url).map_err(|e| e.to_string())?;
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let preview =
+        preview_channel_import(&packet_api.device, parsed_url).map_err(|e| e.to_string())?;
+
+    Ok(preview)
+}
+
+#[tauri::command]
+pub async fn import_channel_url(
+    device_key: DeviceKey,
+    url: String,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<ChannelImportPreview, CommandError> {
+    debug!("Called import_channel_url command");
+    trace!("Called with url {}", url);
+
+    let parsed_url = decode_channel_url(&url).map_err(|e| e.to_string())?;
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    let mut connections_guard = radio_connections.inner.lock().await;
+    let connection = connections_guard
+        .get_mut(&device_key)
+        .ok_or("Radio connection not initialized")?;
+
+    let preview =
+        preview_channel_import(&packet_api.device, parsed_url).map_err(|e| e.to_string())?;
+
+    // Only rewrite the LoRa config when the URL carries a different one,
+    // since changing it causes the radio to reboot
+    let radio = if preview.lora_config_changed {
+        Some(protobufs::LocalConfig {
+            lora: preview.lora_config.clone(),
+            ..Default::default()
+        })
+    } else {
+        None
+    };
+
+    apply_device_bulk_config(
+        connection,
+        packet_api,
+        None,
+        DeviceBulkConfig {
+            radio,
+            module: None,
+            channels: Some(preview.channels.clone()),
+        },
+    )
+    .await?;
+
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
+
+    Ok(preview)
+}
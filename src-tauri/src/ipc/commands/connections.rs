@@ -2,12 +2,16 @@ use crate::device;
 use crate::device::SerialDeviceStatus;
 use crate::ipc::helpers::spawn_configuration_timeout_handler;
 use crate::ipc::helpers::spawn_decoded_handler;
+use crate::ipc::helpers::spawn_outbox_handler;
 use crate::ipc::CommandError;
+use crate::packet_api::device_logs::get_device_log_path;
+use crate::packet_api::outbox::get_outbox_store_path;
+use crate::packet_api::transfers::get_transfer_store_path;
 use crate::packet_api::MeshPacketApi;
 use crate::state;
 use crate::state::DeviceKey;
 
-use log::debug;
+use log::{debug, warn};
 use meshtastic::api::{StreamApi, StreamHandle};
 use meshtastic::utils::stream::build_serial_stream;
 use meshtastic::utils::stream::build_tcp_stream;
@@ -66,8 +70,39 @@ where
         device_key.clone(),
         device,
         mesh_graph.inner.clone(),
+        app_handle
+            .state::<state::dispatcher::EventDispatcherState>()
+            .inner
+            .clone(),
     );
 
+    // Restore messages left unsent and transfers left unfinished the last
+    // time this device was connected
+
+    match app_handle.path_resolver().app_data_dir() {
+        Some(app_data_dir) => {
+            let store_path = get_outbox_store_path(&app_data_dir, &device_key);
+
+            if let Err(e) = packet_api.outbox.load(store_path) {
+                warn!("Failed to restore outbox: {}", e);
+            }
+
+            let store_path = get_transfer_store_path(&app_data_dir, &device_key);
+
+            if let Err(e) = packet_api.transfers.load(store_path) {
+                warn!("Failed to restore file transfers: {}", e);
+            }
+        }
+        None => warn!("App data directory unavailable, outbox won't be persisted"),
+    }
+
+    match app_handle.path_resolver().app_log_dir() {
+        Some(app_log_dir) => packet_api
+            .device_logs
+            .set_path(get_device_log_path(&app_log_dir, &device_key)),
+        None => warn!("App log directory unavailable, device logs won't be persisted"),
+    }
+
     let stream_api = StreamApi::new();
 
     // Connect to device via stream API
@@ -89,6 +124,7 @@ where
     // Persist connection in Tauri state
 
     let handle = app_handle.clone();
+    let outbox_notifier = packet_api.outbox.notifier();
     let mesh_devices_arc = mesh_devices.inner.clone();
     let radio_connections_arc = radio_connections.inner.clone();
 
@@ -116,7 +152,20 @@ where
 
     // Spawn decoded packet handler to route decoded packets
 
-    spawn_decoded_handler(decoded_listener, mesh_devices_arc, device_key);
+    spawn_decoded_handler(
+        decoded_listener,
+        mesh_devices_arc.clone(),
+        device_key.clone(),
+    );
+
+    // Spawn outbox handler to write queued messages as the radio has room
+
+    spawn_outbox_handler(
+        outbox_notifier,
+        mesh_devices_arc,
+        radio_connections_arc,
+        device_key,
+    );
 
     Ok(())
 }
@@ -239,6 +288,8 @@ pub async fn drop_all_device_connections(
     debug!("Called drop_all_device_connections command");
 
     {
+        // Devices are always locked before connections
+        let mut state_devices = mesh_devices.inner.lock().await;
         let mut connections_guard = radio_connections.inner.lock().await;
 
         // Disconnect from all open connections and empty HashMap
@@ -249,8 +300,6 @@ pub async fn drop_all_device_connections(
 
         // Set all state devices as disconnected and empty HashMap
 
-        let mut state_devices = mesh_devices.inner.lock().await;
-
         for (_port_name, packet_api) in state_devices.iter_mut() {
             packet_api
                 .device
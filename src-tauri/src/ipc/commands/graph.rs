@@ -1,10 +1,11 @@
 use std::time::Duration;
 
 use log::{debug, error, info};
+use tauri::Manager;
 
 use crate::{
     graph::ds::graph::MeshGraph,
-    ipc::{events::dispatch_updated_graph, CommandError},
+    ipc::{CommandError, DeltaPayload},
     state,
 };
 
@@ -30,6 +31,10 @@ pub async fn initialize_timeout_handler(
     debug!("Called initialize_timeout_handler command");
 
     let mesh_graph_arc = mesh_graph_state.inner.clone();
+    let dispatcher = app_handle
+        .state::<state::dispatcher::EventDispatcherState>()
+        .inner
+        .clone();
 
     let mut mesh_graph_handle = mesh_graph_state.inner.lock().map_err(|e| e.to_string())?;
 
@@ -44,8 +49,6 @@ pub async fn initialize_timeout_handler(
             DEFAULT_GRAPH_CLEAN_SECONDS
         );
 
-        let app_handle = app_handle;
-
         loop {
             tokio::time::sleep(Duration::from_secs(DEFAULT_GRAPH_CLEAN_SECONDS)).await;
 
@@ -60,10 +63,11 @@ pub async fn initialize_timeout_handler(
                     }
                 };
 
-                mesh_graph_handle.clean();
+                let removed_nodes = mesh_graph_handle.clean();
 
-                dispatch_updated_graph(&app_handle, mesh_graph_handle.clone())
-                    .expect("Error dispatching updated graph event");
+                for node_num in removed_nodes {
+                    dispatcher.enqueue(None, DeltaPayload::NodeRemoved(node_num));
+                }
             }
 
             debug!(
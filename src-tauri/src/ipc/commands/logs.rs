@@ -0,0 +1,40 @@
+use crate::ipc::CommandError;
+use crate::packet_api::device_logs::{DeviceLogEntry, DeviceLogFilter};
+use crate::state;
+use crate::state::DeviceKey;
+
+use log::{debug, trace};
+
+#[tauri::command]
+pub async fn get_device_logs(
+    device_key: DeviceKey,
+    filter: Option<DeviceLogFilter>,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<Vec<DeviceLogEntry>, CommandError> {
+    debug!("Called get_device_logs command");
+    trace!("Called with filter {:?}", filter);
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    Ok(packet_api.device_logs.query(&filter.unwrap_or_default()))
+}
+
+#[tauri::command]
+pub async fn clear_device_logs(
+    device_key: DeviceKey,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<(), CommandError> {
+    debug!("Called clear_device_logs command");
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    packet_api.device_logs.clear();
+
+    Ok(())
+}
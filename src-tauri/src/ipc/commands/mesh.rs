@@ -1,46 +1,62 @@
 use crate::device::NormalizedWaypoint;
 use crate::ipc::events;
 use crate::ipc::CommandError;
+use crate::ipc::ResyncSnapshot;
+use crate::packet_api::outbox::{MessagePriority, OutboxPolicy, OutboxStatus};
+use crate::packet_api::unishox2;
 use crate::state::{self, DeviceKey};
 
 use log::{debug, trace};
-use meshtastic::packet::PacketDestination;
+use meshtastic::protobufs;
 use meshtastic::types::MeshChannel;
+use meshtastic::Message;
+use std::time::Duration;
 
 #[tauri::command]
 pub async fn send_text(
     device_key: DeviceKey,
     text: String,
     channel: u32,
-    app_handle: tauri::AppHandle,
+    priority: Option<MessagePriority>,
+    compress: Option<bool>,
     mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
-    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
 ) -> Result<(), CommandError> {
     debug!("Called send_text command",);
-    trace!("Called with text {} on channel {}", text, channel);
+    trace!(
+        "Called with text {} on channel {} with priority {:?} and compress {:?}",
+        text,
+        channel,
+        priority,
+        compress
+    );
 
     let mut devices_guard = mesh_devices.inner.lock().await;
     let packet_api = devices_guard
         .get_mut(&device_key)
         .ok_or("Device not connected")?;
 
-    let mut connections_guard = radio_connections.inner.lock().await;
-    let connection = connections_guard
-        .get_mut(&device_key)
-        .ok_or("Radio connection not initialized")?;
-
-    connection
-        .send_text(
-            packet_api,
-            text.clone(),
-            PacketDestination::Broadcast,
-            true,
-            MeshChannel::new(channel).map_err(|e| e.to_string())?,
-        )
-        .await
+    // Reject out of range channels before the message is queued
+    MeshChannel::new(channel).map_err(|e| e.to_string())?;
+
+    // Only send compressed when it actually saves space
+    let (portnum, payload) = match compress
+        .unwrap_or(false)
+        .then(|| unishox2::compress(&text))
+        .flatten()
+    {
+        Some(compressed) if compressed.len() < text.len() => {
+            (protobufs::PortNum::TextMessageCompressedApp, compressed)
+        }
+        _ => (protobufs::PortNum::TextMessageApp, text.into_bytes()),
+    };
+
+    packet_api
+        .queue_channel_message(channel, portnum, payload, priority.unwrap_or_default())
         .map_err(|e| e.to_string())?;
 
-    events::dispatch_updated_device(&app_handle, &packet_api.device).map_err(|e| e.to_string())?;
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
 
     Ok(())
 }
@@ -50,9 +66,8 @@ pub async fn send_waypoint(
     device_key: DeviceKey,
     waypoint: NormalizedWaypoint,
     channel: u32,
-    app_handle: tauri::AppHandle,
+    priority: Option<MessagePriority>,
     mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
-    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
 ) -> Result<(), CommandError> {
     debug!("Called send_waypoint command");
     trace!("Called on channel {} with waypoint {:?}", channel, waypoint);
@@ -62,23 +77,86 @@ pub async fn send_waypoint(
         .get_mut(&device_key)
         .ok_or("Device not connected")?;
 
-    let mut connections_guard = radio_connections.inner.lock().await;
-    let connection = connections_guard
-        .get_mut(&device_key)
-        .ok_or("Radio connection not initialized")?;
-
-    connection
-        .send_waypoint(
-            packet_api,
-            waypoint.into(),
-            PacketDestination::Broadcast,
-            true,
-            MeshChannel::new(channel).map_err(|e| e.to_string())?,
+    // Reject out of range channels before the message is queued
+    MeshChannel::new(channel).map_err(|e| e.to_string())?;
+
+    let waypoint: protobufs::Waypoint = waypoint.into();
+
+    packet_api
+        .queue_channel_message(
+            channel,
+            protobufs::PortNum::WaypointApp,
+            waypoint.encode_to_vec(),
+            priority.unwrap_or_default(),
         )
-        .await
         .map_err(|e| e.to_string())?;
 
-    events::dispatch_updated_device(&app_handle, &packet_api.device).map_err(|e| e.to_string())?;
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_outbox_status(
+    device_key: DeviceKey,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<OutboxStatus, CommandError> {
+    debug!("Called get_outbox_status command");
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    Ok(packet_api.outbox.status())
+}
+
+#[tauri::command]
+pub async fn set_outbox_policy(
+    device_key: DeviceKey,
+    policy: OutboxPolicy,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<OutboxStatus, CommandError> {
+    debug!("Called set_outbox_policy command");
+    trace!("Called with policy {:?}", policy);
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    packet_api
+        .outbox
+        .set_policy(policy)
+        .map_err(|e| e.to_string())?;
+
+    Ok(packet_api.outbox.status())
+}
+
+#[tauri::command]
+pub async fn cancel_queued_message(
+    device_key: DeviceKey,
+    message_id: u32,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<(), CommandError> {
+    debug!("Called cancel_queued_message command");
+    trace!("Called with message id {}", message_id);
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    let change = packet_api
+        .outbox
+        .cancel(message_id)
+        .ok_or("Message is no longer queued")?;
+
+    packet_api
+        .apply_outbound_state_change(change)
+        .map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -87,7 +165,6 @@ pub async fn send_waypoint(
 pub async fn delete_waypoint(
     device_key: DeviceKey,
     waypoint_id: u32,
-    app_handle: tauri::AppHandle,
     mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
 ) -> Result<(), CommandError> {
     debug!("Called delete_waypoint command");
@@ -101,7 +178,58 @@ pub async fn delete_waypoint(
         let _removed_waypoint = packet_api.device.waypoints.remove(&waypoint_id);
     }
 
-    events::dispatch_updated_device(&app_handle, &packet_api.device).map_err(|e| e.to_string())?;
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn resync_state(
+    device_key: DeviceKey,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    mesh_graph: tauri::State<'_, state::graph::GraphState>,
+    event_dispatcher: tauri::State<'_, state::dispatcher::EventDispatcherState>,
+) -> Result<ResyncSnapshot, CommandError> {
+    debug!("Called resync_state command");
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let graph_guard = mesh_graph.inner.lock().map_err(|e| e.to_string())?;
+
+    // Deltas are only queued while holding these locks, so flushing here
+    // means every delta up to the returned sequence is in the snapshot
+    event_dispatcher
+        .inner
+        .flush_all()
+        .map_err(|e| e.to_string())?;
+
+    Ok(ResyncSnapshot {
+        sequence: events::current_delta_sequence(),
+        device: packet_api.device.clone_without_history(),
+        graph: graph_guard.clone(),
+    })
+}
+
+#[tauri::command]
+pub async fn set_event_flush_interval(
+    interval_ms: u32,
+    event_dispatcher: tauri::State<'_, state::dispatcher::EventDispatcherState>,
+) -> Result<(), CommandError> {
+    debug!("Called set_event_flush_interval command");
+    trace!("Called with interval_ms {}", interval_ms);
+
+    if interval_ms == 0 {
+        return Err("Flush interval must be greater than zero".into());
+    }
+
+    event_dispatcher
+        .inner
+        .set_flush_interval(Duration::from_millis(interval_ms as u64));
 
     Ok(())
 }
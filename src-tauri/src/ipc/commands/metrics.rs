@@ -0,0 +1,64 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use crate::ipc::CommandError;
+use crate::metrics::server::{start_metrics_server, DEFAULT_METRICS_PORT};
+use crate::state;
+
+use log::{debug, info, trace};
+
+#[tauri::command]
+pub async fn start_metrics_exporter(
+    port: Option<u16>,
+    listen_on_all_interfaces: bool,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    mesh_graph: tauri::State<'_, state::graph::GraphState>,
+    metrics_server: tauri::State<'_, state::metrics_server::MetricsServerState>,
+) -> Result<String, CommandError> {
+    debug!("Called start_metrics_exporter command");
+    trace!(
+        "Called with port {:?}, listen_on_all_interfaces {}",
+        port,
+        listen_on_all_interfaces
+    );
+
+    let mut server_guard = metrics_server.inner.lock().await;
+
+    if let Some(server) = server_guard.as_ref() {
+        info!("Metrics exporter already running on {}", server.address);
+        return Ok(server.address.to_string());
+    }
+
+    // Only expose metrics to other machines when explicitly requested
+    let ip = if listen_on_all_interfaces {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    };
+
+    let server = start_metrics_server(
+        SocketAddr::new(ip, port.unwrap_or(DEFAULT_METRICS_PORT)),
+        mesh_devices.inner.clone(),
+        mesh_graph.inner.clone(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let address = server.address.to_string();
+    *server_guard = Some(server);
+
+    Ok(address)
+}
+
+#[tauri::command]
+pub async fn stop_metrics_exporter(
+    metrics_server: tauri::State<'_, state::metrics_server::MetricsServerState>,
+) -> Result<(), CommandError> {
+    debug!("Called stop_metrics_exporter command");
+
+    if let Some(server) = metrics_server.inner.lock().await.take() {
+        info!("Stopping metrics exporter on {}", server.address);
+        server.handle.abort();
+    }
+
+    Ok(())
+}
@@ -1,4 +1,14 @@
+pub mod admin;
+pub mod analysis;
+pub mod backup;
+pub mod channels;
 pub mod connections;
 pub mod graph;
+pub mod logs;
 pub mod mesh;
+pub mod metrics;
+pub mod provisioning;
 pub mod radio;
+pub mod stats;
+pub mod telemetry;
+pub mod transfers;
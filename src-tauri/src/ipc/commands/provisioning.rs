@@ -0,0 +1,64 @@
+use crate::ipc::CommandError;
+use crate::provisioning::{
+    list_usb_serial_ports, spawn_provisioning_watcher, ProvisioningProfile, ProvisioningReport,
+};
+use crate::state;
+
+use log::{debug, info, trace};
+
+#[tauri::command]
+pub async fn start_provisioning(
+    profile: ProvisioningProfile,
+    app_handle: tauri::AppHandle,
+    provisioning: tauri::State<'_, state::provisioning::ProvisioningState>,
+) -> Result<(), CommandError> {
+    debug!("Called start_provisioning command");
+    trace!("Called with profile {:?}", profile);
+
+    let mut provisioning_guard = provisioning.inner.lock().await;
+
+    if provisioning_guard.watcher_handle.is_some() {
+        return Err("Provisioning already in progress".into());
+    }
+
+    // Radios attached before provisioning starts are left untouched
+    let ignored_ports = list_usb_serial_ports()?;
+
+    provisioning_guard.reports.clear();
+    provisioning_guard.watcher_handle = Some(spawn_provisioning_watcher(
+        app_handle,
+        profile,
+        ignored_ports,
+        provisioning.inner.clone(),
+    ));
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_provisioning(
+    provisioning: tauri::State<'_, state::provisioning::ProvisioningState>,
+) -> Result<(), CommandError> {
+    debug!("Called stop_provisioning command");
+
+    let mut provisioning_guard = provisioning.inner.lock().await;
+
+    // Radios already being provisioned are allowed to finish
+    if let Some(handle) = provisioning_guard.watcher_handle.take() {
+        info!("Stopping provisioning watcher");
+        handle.abort();
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_provisioning_reports(
+    provisioning: tauri::State<'_, state::provisioning::ProvisioningState>,
+) -> Result<Vec<ProvisioningReport>, CommandError> {
+    debug!("Called get_provisioning_reports command");
+
+    let provisioning_guard = provisioning.inner.lock().await;
+
+    Ok(provisioning_guard.reports.clone())
+}
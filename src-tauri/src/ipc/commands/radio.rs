@@ -1,4 +1,5 @@
-use crate::ipc::events;
+use crate::device::compatibility::DeviceFeature;
+use crate::ipc::helpers::apply_device_bulk_config;
 use crate::ipc::CommandError;
 use crate::ipc::DeviceBulkConfig;
 use crate::state;
@@ -134,7 +135,6 @@ pub async fn commit_configuration_transaction(
 #[tauri::command]
 pub async fn update_device_config_bulk(
     device_key: DeviceKey,
-    app_handle: tauri::AppHandle,
     config: DeviceBulkConfig,
     mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
     radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
@@ -146,43 +146,84 @@ pub async fn update_device_config_bulk(
         .get_mut(&device_key)
         .ok_or("Device not connected")?;
 
+    if let Some(module_config) = config.module.as_ref() {
+        packet_api
+            .device
+            .compatibility
+            .require_module_config(module_config)?;
+    }
+
     let mut connections_guard = radio_connections.inner.lock().await;
     let connection = connections_guard
         .get_mut(&device_key)
         .ok_or("Radio connection not initialized")?;
 
-    connection
-        .start_config_transaction()
-        .await
-        .map_err(|e| e.to_string())?;
+    apply_device_bulk_config(connection, packet_api, None, config).await?;
 
-    if let Some(radio_config) = config.radio {
-        connection
-            .set_local_config(packet_api, radio_config)
-            .await
-            .map_err(|e| e.to_string())?;
-    }
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
 
-    if let Some(module_config) = config.module {
-        connection
-            .set_local_module_config(packet_api, module_config)
-            .await
-            .map_err(|e| e.to_string())?;
-    }
+    Ok(())
+}
 
-    if let Some(channel_config) = config.channels {
-        connection
-            .set_message_channel_config(packet_api, channel_config)
-            .await
-            .map_err(|e| e.to_string())?;
-    }
+/// Sets how often the device broadcasts neighbor info, which the network
+/// graph is built from
+#[tauri::command]
+pub async fn update_neighbor_info_config(
+    device_key: DeviceKey,
+    enabled: bool,
+    update_interval: u32,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+    radio_connections: tauri::State<'_, state::radio_connections::RadioConnectionsState>,
+) -> Result<(), CommandError> {
+    debug!("Called update_neighbor_info_config command");
+    trace!(
+        "Called with enabled {} and interval {}s",
+        enabled,
+        update_interval
+    );
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    packet_api
+        .device
+        .compatibility
+        .require(DeviceFeature::NeighborInfo)?;
+
+    let mut connections_guard = radio_connections.inner.lock().await;
+    let connection = connections_guard
+        .get_mut(&device_key)
+        .ok_or("Radio connection not initialized")?;
+
+    let module_config = protobufs::ModuleConfig {
+        payload_variant: Some(protobufs::module_config::PayloadVariant::NeighborInfo(
+            protobufs::module_config::NeighborInfoConfig {
+                enabled,
+                update_interval,
+                ..packet_api
+                    .device
+                    .module_config
+                    .neighbor_info
+                    .clone()
+                    .unwrap_or_default()
+            },
+        )),
+    };
 
     connection
-        .commit_config_transaction()
+        .update_module_config(packet_api, module_config.clone())
         .await
         .map_err(|e| e.to_string())?;
 
-    events::dispatch_updated_device(&app_handle, &packet_api.device).map_err(|e| e.to_string())?;
+    packet_api.device.set_module_config(module_config);
+
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
 
     Ok(())
 }
@@ -0,0 +1,57 @@
+use crate::ipc::CommandError;
+use crate::packet_api::stats::{PacketStats, RawPacketLogEntry, RAW_PACKET_LOG_CAPACITY};
+use crate::state;
+use crate::state::DeviceKey;
+
+use log::{debug, trace};
+
+#[tauri::command]
+pub async fn get_packet_stats(
+    device_key: DeviceKey,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<PacketStats, CommandError> {
+    debug!("Called get_packet_stats command");
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    Ok(packet_api.packet_log.stats.clone())
+}
+
+#[tauri::command]
+pub async fn get_raw_packet_log(
+    device_key: DeviceKey,
+    limit: Option<u32>,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<Vec<RawPacketLogEntry>, CommandError> {
+    debug!("Called get_raw_packet_log command");
+    trace!("Called with limit {:?}", limit);
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let limit = limit.map(|l| l as usize).unwrap_or(RAW_PACKET_LOG_CAPACITY);
+
+    Ok(packet_api.packet_log.recent_packets(limit))
+}
+
+#[tauri::command]
+pub async fn reset_packet_stats(
+    device_key: DeviceKey,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<(), CommandError> {
+    debug!("Called reset_packet_stats command");
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    packet_api.packet_log.clear();
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use crate::device::alerts::TelemetryAlertRule;
+use crate::device::telemetry::{NodeTelemetryRange, TelemetryRetentionPolicy, TelemetrySeriesKind};
+use crate::device::telemetry_export::{
+    export_telemetry, ExportedTelemetryFile, TelemetryExportOptions,
+};
+use crate::ipc::CommandError;
+use crate::state;
+use crate::state::DeviceKey;
+
+use log::{debug, trace};
+
+#[tauri::command]
+pub async fn get_node_telemetry(
+    device_key: DeviceKey,
+    node_num: u32,
+    kind: TelemetrySeriesKind,
+    start: u32,
+    end: u32,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<NodeTelemetryRange, CommandError> {
+    debug!("Called get_node_telemetry command");
+    trace!(
+        "Called with node {}, kind {:?}, range {} to {}",
+        node_num,
+        kind,
+        start,
+        end
+    );
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let node = packet_api
+        .device
+        .nodes
+        .get(&node_num)
+        .ok_or("Node not found")?;
+
+    Ok(node.telemetry_in_range(kind, start, end))
+}
+
+#[tauri::command]
+pub async fn set_telemetry_retention(
+    device_key: DeviceKey,
+    policy: TelemetryRetentionPolicy,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<(), CommandError> {
+    debug!("Called set_telemetry_retention command");
+    trace!("Called with policy {:?}", policy);
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    packet_api.device.set_telemetry_retention(policy);
+
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_telemetry_alert_rules(
+    device_key: DeviceKey,
+    rules: Vec<TelemetryAlertRule>,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<(), CommandError> {
+    debug!("Called set_telemetry_alert_rules command");
+    trace!("Called with rules {:?}", rules);
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    packet_api
+        .device
+        .telemetry_alerts
+        .set_rules(rules)
+        .map_err(|e| e.to_string())?;
+
+    packet_api
+        .dispatcher
+        .device_updated(&device_key, &packet_api.device);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn export_node_telemetry(
+    device_key: DeviceKey,
+    options: TelemetryExportOptions,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<Vec<ExportedTelemetryFile>, CommandError> {
+    debug!("Called export_node_telemetry command");
+    trace!("Called with options {:?}", options);
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    let files = export_telemetry(&packet_api.device, &options).map_err(|e| e.to_string())?;
+
+    Ok(files)
+}
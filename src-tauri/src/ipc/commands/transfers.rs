@@ -0,0 +1,116 @@
+use crate::ipc::CommandError;
+use crate::packet_api::transfers::FileTransferStatus;
+use crate::state;
+use crate::state::DeviceKey;
+
+use log::{debug, trace};
+use meshtastic::types::MeshChannel;
+
+#[tauri::command]
+pub async fn offer_file_transfer(
+    device_key: DeviceKey,
+    to: u32,
+    channel: u32,
+    file_name: String,
+    data: Vec<u8>,
+    compress: Option<bool>,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<FileTransferStatus, CommandError> {
+    debug!("Called offer_file_transfer command");
+    trace!(
+        "Called with \"{}\" ({} bytes) to {} on channel {}",
+        file_name,
+        data.len(),
+        to,
+        channel
+    );
+
+    // Reject out of range channels before the transfer is offered
+    MeshChannel::new(channel).map_err(|e| e.to_string())?;
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    let (transfer_id, offer) = packet_api
+        .transfers
+        .offer(to, channel, &file_name, &data, compress.unwrap_or(true))
+        .map_err(|e| e.to_string())?;
+
+    packet_api.send_transfer_frames(vec![offer]);
+    packet_api.emit_transfer_update(transfer_id);
+
+    Ok(packet_api
+        .transfers
+        .status(transfer_id)
+        .ok_or("Transfer not found")?)
+}
+
+#[tauri::command]
+pub async fn accept_file_transfer(
+    device_key: DeviceKey,
+    transfer_id: u32,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<FileTransferStatus, CommandError> {
+    debug!("Called accept_file_transfer command");
+    trace!("Called with transfer id {}", transfer_id);
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    let accept = packet_api
+        .transfers
+        .accept(transfer_id)
+        .map_err(|e| e.to_string())?;
+
+    packet_api.send_transfer_frames(vec![accept]);
+    packet_api.emit_transfer_update(transfer_id);
+
+    Ok(packet_api
+        .transfers
+        .status(transfer_id)
+        .ok_or("Transfer not found")?)
+}
+
+#[tauri::command]
+pub async fn cancel_file_transfer(
+    device_key: DeviceKey,
+    transfer_id: u32,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<(), CommandError> {
+    debug!("Called cancel_file_transfer command");
+    trace!("Called with transfer id {}", transfer_id);
+
+    let mut devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get_mut(&device_key)
+        .ok_or("Device not connected")?;
+
+    let cancel = packet_api
+        .transfers
+        .cancel(transfer_id)
+        .map_err(|e| e.to_string())?;
+
+    packet_api.send_transfer_frames(vec![cancel]);
+    packet_api.emit_transfer_update(transfer_id);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_file_transfers(
+    device_key: DeviceKey,
+    mesh_devices: tauri::State<'_, state::mesh_devices::MeshDevicesState>,
+) -> Result<Vec<FileTransferStatus>, CommandError> {
+    debug!("Called get_file_transfers command");
+
+    let devices_guard = mesh_devices.inner.lock().await;
+    let packet_api = devices_guard
+        .get(&device_key)
+        .ok_or("Device not connected")?;
+
+    Ok(packet_api.transfers.list())
+}
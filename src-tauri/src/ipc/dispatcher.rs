@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{debug, error, trace};
+use tauri::async_runtime::JoinHandle;
+
+use crate::device::MeshDevice;
+use crate::state::DeviceKey;
+
+use super::{events, DeltaPayload};
+
+pub const DEFAULT_FLUSH_INTERVAL_MS: u32 = 250;
+
+/// Identifies the entity a delta describes. Only the most recent pending
+/// delta for each entity is emitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CoalesceKey {
+    Node(u32),
+    GraphNode(u32),
+    Edge(u32, u32),
+    Outbox,
+    Fragment(u32, u32),
+    Transfer(u32),
+    Unique,
+}
+
+impl From<&DeltaPayload> for CoalesceKey {
+    fn from(delta: &DeltaPayload) -> Self {
+        match delta {
+            DeltaPayload::NodeUpdated(node) => CoalesceKey::Node(node.node_num),
+            DeltaPayload::GraphNodeUpdated(node) => CoalesceKey::GraphNode(node.node_num),
+            DeltaPayload::NodeRemoved(node_num) => CoalesceKey::GraphNode(*node_num),
+            DeltaPayload::EdgeUpdated(payload) => {
+                CoalesceKey::Edge(payload.source.node_num, payload.target.node_num)
+            }
+            DeltaPayload::OutboxUpdated(_) => CoalesceKey::Outbox,
+            DeltaPayload::FragmentProgress(progress) => {
+                CoalesceKey::Fragment(progress.from, progress.message_id)
+            }
+            DeltaPayload::FileTransferUpdated(status) => CoalesceKey::Transfer(status.transfer_id),
+            DeltaPayload::MessageAdded(_)
+            | DeltaPayload::MessageStateChanged(_)
+            | DeltaPayload::WaypointAdded(_)
+            | DeltaPayload::DetectionEvent(_) => CoalesceKey::Unique,
+        }
+    }
+}
+
+#[derive(Default)]
+struct PendingBatch {
+    device: Option<MeshDevice>, // full device update, supersedes earlier deltas
+    deltas: Vec<(CoalesceKey, DeltaPayload)>,
+}
+
+impl PendingBatch {
+    fn is_empty(&self) -> bool {
+        self.device.is_none() && self.deltas.is_empty()
+    }
+
+    fn push(&mut self, delta: DeltaPayload) {
+        let key = CoalesceKey::from(&delta);
+
+        // Move replaced deltas to the end so they stay ordered relative to
+        // deltas for other entities
+        if key != CoalesceKey::Unique {
+            self.deltas.retain(|(k, _)| *k != key);
+        }
+
+        self.deltas.push((key, delta));
+    }
+}
+
+/// Sits between packet handlers and `ipc::events`, coalescing updates per
+/// device and for the shared graph into at most one emission per flush
+/// interval. High-priority updates can bypass the interval and flush
+/// immediately.
+pub struct EventDispatcher<R: tauri::Runtime = tauri::Wry> {
+    app_handle: tauri::AppHandle<R>,
+    pending: Arc<Mutex<HashMap<Option<DeviceKey>, PendingBatch>>>,
+    flush_interval_ms: Arc<AtomicU32>,
+}
+
+impl<R: tauri::Runtime> Clone for EventDispatcher<R> {
+    fn clone(&self) -> Self {
+        Self {
+            app_handle: self.app_handle.clone(),
+            pending: self.pending.clone(),
+            flush_interval_ms: self.flush_interval_ms.clone(),
+        }
+    }
+}
+
+impl<R: tauri::Runtime> EventDispatcher<R> {
+    pub fn new(app_handle: tauri::AppHandle<R>) -> Self {
+        Self {
+            app_handle,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            flush_interval_ms: Arc::new(AtomicU32::new(DEFAULT_FLUSH_INTERVAL_MS)),
+        }
+    }
+
+    pub fn flush_interval(&self) -> Duration {
+        Duration::from_millis(self.flush_interval_ms.load(Ordering::Relaxed) as u64)
+    }
+
+    pub fn set_flush_interval(&self, interval: Duration) {
+        debug!("Setting event flush interval to {:?}", interval);
+
+        let interval_ms = interval.as_millis().clamp(1, u32::MAX as u128) as u32;
+        self.flush_interval_ms.store(interval_ms, Ordering::Relaxed);
+    }
+
+    /// Queues a delta to be emitted on the next flush
+    pub fn enqueue(&self, device_key: Option<&DeviceKey>, delta: DeltaPayload) {
+        let mut pending = match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("Error getting pending events handle: {}", e);
+                return;
+            }
+        };
+
+        trace!("Queueing delta for device {:?}", device_key);
+
+        pending.entry(device_key.cloned()).or_default().push(delta);
+    }
+
+    /// Queues a delta and immediately flushes all pending updates for the device
+    pub fn enqueue_immediate(
+        &self,
+        device_key: Option<&DeviceKey>,
+        delta: DeltaPayload,
+    ) -> tauri::Result<()> {
+        self.enqueue(device_key, delta);
+        self.flush(device_key)
+    }
+
+    /// Queues a full device update, dropping any pending deltas for the
+    /// device since they're already reflected in the full update. Full
+    /// updates are only emitted from here, so a queued update can't be
+    /// flushed after a newer one.
+    pub fn device_updated(&self, device_key: &DeviceKey, device: &MeshDevice) {
+        let mut pending = match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("Error getting pending events handle: {}", e);
+                return;
+            }
+        };
+
+        trace!("Queueing full update for device {}", device_key);
+
+        let batch = pending.entry(Some(device_key.clone())).or_default();
+        batch.device = Some(device.clone_without_history());
+        batch.deltas.clear();
+    }
+
+    /// Emits all pending updates for a device, or for the graph if `None`
+    pub fn flush(&self, device_key: Option<&DeviceKey>) -> tauri::Result<()> {
+        let mut pending = match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("Error getting pending events handle: {}", e);
+                return Ok(());
+            }
+        };
+
+        // Emit while holding the lock so sequence numbers stay in emission order
+        match pending.remove(&device_key.cloned()) {
+            Some(batch) => self.emit_batch(device_key.cloned(), batch),
+            None => Ok(()),
+        }
+    }
+
+    pub fn flush_all(&self) -> tauri::Result<()> {
+        let mut pending = match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("Error getting pending events handle: {}", e);
+                return Ok(());
+            }
+        };
+
+        for (device_key, batch) in pending.drain() {
+            self.emit_batch(device_key, batch)?;
+        }
+
+        Ok(())
+    }
+
+    /// Spawns a task that flushes all pending updates once per flush interval
+    pub fn spawn_flush_task(&self) -> JoinHandle<()> {
+        let dispatcher = self.clone();
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(dispatcher.flush_interval()).await;
+
+                if let Err(e) = dispatcher.flush_all() {
+                    error!("Error flushing pending events: {}", e);
+                }
+            }
+        })
+    }
+
+    fn emit_batch(&self, device_key: Option<DeviceKey>, batch: PendingBatch) -> tauri::Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(device) = batch.device {
+            events::dispatch_updated_device(&self.app_handle, &device)?;
+        }
+
+        if !batch.deltas.is_empty() {
+            let deltas = batch.deltas.into_iter().map(|(_, delta)| delta).collect();
+            events::dispatch_delta_batch(&self.app_handle, device_key, deltas)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_updates_coalesced() {
+        let mut batch = PendingBatch::default();
+
+        batch.push(DeltaPayload::NodeRemoved(1));
+        batch.push(DeltaPayload::NodeRemoved(2));
+        batch.push(DeltaPayload::NodeRemoved(1));
+
+        let keys: Vec<CoalesceKey> = batch.deltas.iter().map(|(k, _)| *k).collect();
+
+        assert_eq!(
+            keys,
+            vec![CoalesceKey::GraphNode(2), CoalesceKey::GraphNode(1)]
+        );
+    }
+}
@@ -1,8 +1,24 @@
-use crate::{device, graph::ds::graph::MeshGraph};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::{
+    device::{self, alerts::TelemetryAlert},
+    packet_api::device_logs::DeviceLogEntry,
+    provisioning::ProvisioningReport,
+    state::DeviceKey,
+};
 use log::{debug, trace};
 use tauri::Manager;
 
-use super::ConfigurationStatus;
+use super::{
+    ConfigurationStatus, DeltaBatch, DeltaEvent, DeltaPayload, DeviceLogEvent, TelemetryAlertEvent,
+};
+
+static DELTA_EVENT_SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+/// Returns the sequence number of the most recently dispatched delta event
+pub fn current_delta_sequence() -> u32 {
+    DELTA_EVENT_SEQUENCE.load(Ordering::SeqCst)
+}
 
 pub fn dispatch_updated_device<R: tauri::Runtime>(
     handle: &tauri::AppHandle<R>,
@@ -43,13 +59,73 @@ pub fn dispatch_rebooting_event<R: tauri::Runtime>(
     Ok(())
 }
 
-pub fn dispatch_updated_graph<R: tauri::Runtime>(
+pub fn dispatch_provisioning_report<R: tauri::Runtime>(
+    handle: &tauri::AppHandle<R>,
+    report: &ProvisioningReport,
+) -> tauri::Result<()> {
+    debug!("Dispatching provisioning report");
+
+    handle.emit_all("provisioning_report", report)?;
+
+    Ok(())
+}
+
+pub fn dispatch_telemetry_alert<R: tauri::Runtime>(
+    handle: &tauri::AppHandle<R>,
+    device_key: &DeviceKey,
+    alert: &TelemetryAlert,
+) -> tauri::Result<()> {
+    debug!("Dispatching telemetry alert");
+
+    handle.emit_all(
+        "telemetry_alert",
+        TelemetryAlertEvent {
+            device_key: device_key.clone(),
+            alert: alert.clone(),
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Emits a log record from the radio as soon as it's received, for live tailing
+pub fn dispatch_device_log<R: tauri::Runtime>(
     handle: &tauri::AppHandle<R>,
-    graph: MeshGraph,
+    device_key: &DeviceKey,
+    entry: &DeviceLogEntry,
 ) -> tauri::Result<()> {
-    debug!("Dispatching updated graph");
+    trace!("Dispatching device log record");
+
+    handle.emit_all(
+        "device_log",
+        DeviceLogEvent {
+            device_key: device_key.clone(),
+            entry: entry.clone(),
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Assigns sequence numbers to a batch of deltas and emits them as a single
+/// `delta_batch` event. Callers must serialize calls to keep sequence numbers
+/// in emission order.
+pub fn dispatch_delta_batch<R: tauri::Runtime>(
+    handle: &tauri::AppHandle<R>,
+    device_key: Option<DeviceKey>,
+    deltas: Vec<DeltaPayload>,
+) -> tauri::Result<()> {
+    debug!("Dispatching batch of {} delta events", deltas.len());
+
+    let events = deltas
+        .into_iter()
+        .map(|delta| DeltaEvent {
+            sequence: DELTA_EVENT_SEQUENCE.fetch_add(1, Ordering::SeqCst) + 1,
+            delta,
+        })
+        .collect();
 
-    handle.emit_all("graph_update", graph)?;
+    handle.emit_all("delta_batch", DeltaBatch { device_key, events })?;
 
     Ok(())
 }
@@ -1,15 +1,112 @@
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use log::{trace, warn};
+use meshtastic::api::ConnectedStreamApi;
 use meshtastic::packet::PacketRouter;
 use meshtastic::protobufs;
 use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::Notify;
 
 use crate::device::SerialDeviceStatus;
 use crate::ipc::events::dispatch_configuration_status;
-use crate::ipc::ConfigurationStatus;
+use crate::ipc::{CommandError, ConfigurationStatus, DeviceBulkConfig};
+use crate::packet_api::handlers::DeviceUpdateError;
+use crate::packet_api::outbox::get_duty_cycle_usage;
 use crate::state::{self, DeviceKey};
 
+/// How often the outbox is checked for packets the radio never reported on
+const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Applies owner, radio, module and channel configuration to a device within
+/// a single configuration transaction, so the device only reboots once.
+pub async fn apply_device_bulk_config<P>(
+    connection: &mut ConnectedStreamApi,
+    packet_router: &mut P,
+    owner: Option<protobufs::User>,
+    config: DeviceBulkConfig,
+) -> Result<(), CommandError>
+where
+    P: PacketRouter<(), DeviceUpdateError>,
+{
+    connection
+        .start_config_transaction()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(user) = owner {
+        connection
+            .update_user(packet_router, user)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(radio_config) = config.radio {
+        connection
+            .set_local_config(packet_router, radio_config)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(mut module_config) = config.module {
+        // `ConnectedStreamApi::set_local_module_config` in meshtastic 0.1.6
+        // (src/connections/stream_api.rs) only sends the audio, canned
+        // message, external notification, MQTT, range test, remote hardware,
+        // serial, store and forward and telemetry modules and skips the rest.
+        // Newer modules are taken out and sent individually, so each module
+        // is only sent once.
+        let newer_modules = [
+            module_config
+                .neighbor_info
+                .take()
+                .map(protobufs::module_config::PayloadVariant::NeighborInfo),
+            module_config
+                .ambient_lighting
+                .take()
+                .map(protobufs::module_config::PayloadVariant::AmbientLighting),
+            module_config
+                .detection_sensor
+                .take()
+                .map(protobufs::module_config::PayloadVariant::DetectionSensor),
+            module_config
+                .paxcounter
+                .take()
+                .map(protobufs::module_config::PayloadVariant::Paxcounter),
+        ];
+
+        connection
+            .set_local_module_config(packet_router, module_config)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for payload_variant in newer_modules.into_iter().flatten() {
+            connection
+                .update_module_config(
+                    packet_router,
+                    protobufs::ModuleConfig {
+                        payload_variant: Some(payload_variant),
+                    },
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    if let Some(channel_config) = config.channels {
+        connection
+            .set_message_channel_config(packet_router, channel_config)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    connection
+        .commit_config_transaction()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 pub fn spawn_configuration_timeout_handler(
     handle: tauri::AppHandle,
     connected_devices_inner: state::mesh_devices::MeshDevicesStateInner,
@@ -88,6 +185,7 @@ pub fn spawn_decoded_handler(
             match packet_api.handle_packet_from_radio(packet) {
                 Ok(result) => result,
                 Err(err) => {
+                    packet_api.metrics.record_update_error(&err);
                     warn!("{}", err);
                     continue;
                 }
@@ -95,3 +193,141 @@ pub fn spawn_decoded_handler(
         }
     });
 }
+
+/// Writes packets held in a device's outbox to the radio as TX queue slots
+/// free up. Exits once the device or its connection is removed.
+pub fn spawn_outbox_handler(
+    outbox_notifier: Arc<Notify>,
+    connected_devices_arc: state::mesh_devices::MeshDevicesStateInner,
+    radio_connections_arc: state::radio_connections::RadioConnectionsStateInner,
+    device_key: DeviceKey,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // Wake on new messages and queue status updates, or periodically
+            // to retry packets the radio never reported on
+            let _ = tokio::time::timeout(OUTBOX_POLL_INTERVAL, outbox_notifier.notified()).await;
+
+            // The device lock is released while writing to disk and to the
+            // radio, and is always taken before the connection lock
+            let (packets, now, pending_writes, log_write) = {
+                let mut devices_guard = connected_devices_arc.lock().await;
+                let packet_api = match devices_guard.get_mut(&device_key) {
+                    Some(d) => d,
+                    None => {
+                        trace!("Device \"{}\" disconnected, stopping outbox", device_key);
+                        return;
+                    }
+                };
+
+                let now = Instant::now();
+
+                for change in packet_api.outbox.expire(now) {
+                    if let Err(err) = packet_api.apply_outbound_state_change(change) {
+                        warn!("{}", err);
+                    }
+                }
+
+                packet_api.handle_undelivered_transfer_packets();
+                packet_api.transfers.prune(now);
+                packet_api.expire_fragments(now);
+
+                let mut pending_writes = vec![];
+
+                match packet_api.outbox.persist() {
+                    Ok(write) => pending_writes.extend(write),
+                    Err(e) => warn!("Error persisting outbox: {}", e),
+                }
+
+                match packet_api.transfers.persist() {
+                    Ok(write) => pending_writes.extend(write),
+                    Err(e) => warn!("Error persisting file transfers: {}", e),
+                }
+
+                let log_write = packet_api.device_logs.take_pending_write();
+
+                let mut packets = vec![];
+
+                // Hold messages until the device has finished configuring
+                if packet_api.device.status == SerialDeviceStatus::Connected {
+                    let duty_cycle =
+                        get_duty_cycle_usage(&packet_api.device, packet_api.outbox.policy());
+
+                    while let Some(packet) = packet_api.outbox.next_ready(now, duty_cycle) {
+                        packets.push(packet);
+                    }
+                }
+
+                (packets, now, pending_writes, log_write)
+            };
+
+            for write in pending_writes {
+                if let Err(e) = write.write() {
+                    warn!("Error writing {:?}: {}", write.path(), e);
+                }
+            }
+
+            if let Some(write) = log_write {
+                if let Err(e) = write.write() {
+                    warn!("Error writing device log {:?}: {}", write.path(), e);
+                }
+            }
+
+            if packets.is_empty() {
+                continue;
+            }
+
+            let mut written = vec![];
+
+            {
+                let mut connections_guard = radio_connections_arc.lock().await;
+                let connection = match connections_guard.get_mut(&device_key) {
+                    Some(c) => c,
+                    None => {
+                        trace!(
+                            "Radio connection \"{}\" closed, stopping outbox",
+                            device_key
+                        );
+                        return;
+                    }
+                };
+
+                for packet in packets {
+                    let packet_id = packet.id;
+
+                    trace!("Writing outbound packet {} to radio", packet_id);
+
+                    // Failed writes stay in flight and are retried once they expire
+                    if let Err(e) = connection
+                        .send_to_radio_packet(Some(protobufs::to_radio::PayloadVariant::Packet(
+                            packet,
+                        )))
+                        .await
+                    {
+                        warn!("Error writing outbound packet {}: {}", packet_id, e);
+                        break;
+                    }
+
+                    written.push(packet_id);
+                }
+            }
+
+            let mut devices_guard = connected_devices_arc.lock().await;
+            let packet_api = match devices_guard.get_mut(&device_key) {
+                Some(d) => d,
+                None => {
+                    trace!("Device \"{}\" disconnected, stopping outbox", device_key);
+                    return;
+                }
+            };
+
+            for packet_id in written {
+                if let Some(change) = packet_api.outbox.mark_written(packet_id, now) {
+                    if let Err(err) = packet_api.apply_outbound_state_change(change) {
+                        warn!("{}", err);
+                    }
+                }
+            }
+        }
+    });
+}
@@ -1,3 +1,13 @@
+use crate::device::alerts::TelemetryAlert;
+use crate::device::{
+    ChannelMessageState, ChannelMessageWithState, DetectionEvent, MeshDevice, MeshNode,
+    NormalizedWaypoint,
+};
+use crate::graph::ds::{edge::GraphEdge, graph::MeshGraph, node::GraphNode};
+use crate::packet_api::device_logs::DeviceLogEntry;
+use crate::packet_api::fragments::FragmentProgress;
+use crate::packet_api::outbox::OutboxStatus;
+use crate::packet_api::transfers::FileTransferStatus;
 use crate::state::DeviceKey;
 use meshtastic::protobufs;
 use meshtastic::ts::specta::{self, Type};
@@ -5,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub mod commands;
+pub mod dispatcher;
 pub mod events;
 pub mod helpers;
 
@@ -54,7 +65,89 @@ pub struct ConfigurationStatus {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct DeviceBulkConfig {
-    radio: Option<protobufs::LocalConfig>,
-    module: Option<protobufs::LocalModuleConfig>,
-    channels: Option<Vec<protobufs::Channel>>,
+    pub radio: Option<protobufs::LocalConfig>,
+    pub module: Option<protobufs::LocalModuleConfig>,
+    pub channels: Option<Vec<protobufs::Channel>>,
+}
+
+/// A fine-grained update to device or graph state, emitted in place of
+/// re-sending the full `MeshDevice` or `MeshGraph`
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case", tag = "event", content = "payload")]
+pub enum DeltaPayload {
+    NodeUpdated(MeshNode),
+    MessageAdded(MessageAddedPayload),
+    MessageStateChanged(MessageStateChangedPayload),
+    WaypointAdded(NormalizedWaypoint),
+    GraphNodeUpdated(GraphNode),
+    EdgeUpdated(EdgeUpdatedPayload),
+    NodeRemoved(u32), // node removed from the graph after timing out
+    OutboxUpdated(OutboxStatus),
+    FragmentProgress(FragmentProgress), // chunks received of a fragmented message
+    FileTransferUpdated(FileTransferStatus),
+    DetectionEvent(DetectionEvent),
+}
+
+/// The sequence number increases by one for every delta event, so the UI
+/// can detect a missed event and request a full resync.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaEvent {
+    pub sequence: u32,
+    pub delta: DeltaPayload,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaBatch {
+    pub device_key: Option<DeviceKey>, // None for events on the shared graph
+    pub events: Vec<DeltaEvent>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryAlertEvent {
+    pub device_key: DeviceKey,
+    pub alert: TelemetryAlert,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLogEvent {
+    pub device_key: DeviceKey,
+    pub entry: DeviceLogEntry,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageAddedPayload {
+    pub channel: u32,
+    pub message: ChannelMessageWithState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageStateChangedPayload {
+    pub channel: u32,
+    pub message_id: u32,
+    pub state: ChannelMessageState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeUpdatedPayload {
+    pub source: GraphNode,
+    pub target: GraphNode,
+    pub edge: GraphEdge,
+}
+
+/// Full device and graph state returned when the UI detects a missed delta
+/// event. Deltas with a sequence number at or below `sequence` are already
+/// reflected in the snapshot.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResyncSnapshot {
+    pub sequence: u32,
+    pub device: MeshDevice,
+    pub graph: MeshGraph,
 }
@@ -3,11 +3,14 @@
     windows_subsystem = "windows"
 )]
 
+mod analysis;
 mod cli;
 mod device;
 mod graph;
 mod ipc;
+mod metrics;
 mod packet_api;
+mod provisioning;
 mod state;
 
 use log::{info, LevelFilter};
@@ -53,6 +56,11 @@ fn main() {
                 state::radio_connections::RadioConnectionsState::new();
             let mut inital_autoconnect_state = state::autoconnect::AutoConnectState::new();
             let initial_graph_state = state::graph::GraphState::new();
+            let initial_provisioning_state = state::provisioning::ProvisioningState::new();
+            let initial_metrics_server_state = state::metrics_server::MetricsServerState::new();
+            let initial_analysis_state = state::analysis::AnalysisState::new();
+            let initial_dispatcher_state =
+                state::dispatcher::EventDispatcherState::new(app.app_handle());
 
             match cli::handle_cli_matches(app, &mut inital_autoconnect_state) {
                 Ok(_) => {}
@@ -63,6 +71,12 @@ fn main() {
             app.app_handle().manage(initial_radio_connections_state);
             app.app_handle().manage(inital_autoconnect_state); // Needs to be set after being mutated by CLI parser
             app.app_handle().manage(initial_graph_state);
+            app.app_handle().manage(initial_provisioning_state);
+            app.app_handle().manage(initial_metrics_server_state);
+            app.app_handle().manage(initial_analysis_state);
+
+            initial_dispatcher_state.inner.spawn_flush_task();
+            app.app_handle().manage(initial_dispatcher_state);
 
             Ok(())
         })
@@ -75,15 +89,59 @@ fn main() {
             ipc::commands::connections::drop_all_device_connections,
             ipc::commands::mesh::send_text,
             ipc::commands::mesh::send_waypoint,
+            ipc::commands::mesh::get_outbox_status,
+            ipc::commands::mesh::set_outbox_policy,
+            ipc::commands::mesh::cancel_queued_message,
+            ipc::commands::transfers::offer_file_transfer,
+            ipc::commands::transfers::accept_file_transfer,
+            ipc::commands::transfers::cancel_file_transfer,
+            ipc::commands::transfers::get_file_transfers,
             ipc::commands::mesh::delete_waypoint,
+            ipc::commands::mesh::resync_state,
+            ipc::commands::mesh::set_event_flush_interval,
             ipc::commands::radio::update_device_config,
             ipc::commands::radio::update_device_user,
             ipc::commands::radio::start_configuration_transaction,
             ipc::commands::radio::commit_configuration_transaction,
             ipc::commands::radio::update_device_config_bulk,
+            ipc::commands::radio::update_neighbor_info_config,
             ipc::commands::graph::get_graph_state,
             ipc::commands::graph::initialize_timeout_handler,
             ipc::commands::graph::stop_timeout_handler,
+            ipc::commands::admin::request_remote_config,
+            ipc::commands::admin::request_remote_module_config,
+            ipc::commands::admin::request_remote_channel,
+            ipc::commands::admin::request_remote_owner,
+            ipc::commands::admin::request_remote_metadata,
+            ipc::commands::admin::set_remote_config,
+            ipc::commands::admin::set_remote_module_config,
+            ipc::commands::admin::set_remote_channel,
+            ipc::commands::admin::set_remote_owner,
+            ipc::commands::admin::reboot_remote_node,
+            ipc::commands::backup::export_device_config,
+            ipc::commands::backup::diff_device_config,
+            ipc::commands::backup::import_device_config,
+            ipc::commands::channels::export_channel_url,
+            ipc::commands::channels::preview_channel_url,
+            ipc::commands::channels::import_channel_url,
+            ipc::commands::provisioning::start_provisioning,
+            ipc::commands::provisioning::stop_provisioning,
+            ipc::commands::provisioning::get_provisioning_reports,
+            ipc::commands::telemetry::get_node_telemetry,
+            ipc::commands::telemetry::set_telemetry_retention,
+            ipc::commands::telemetry::set_telemetry_alert_rules,
+            ipc::commands::telemetry::export_node_telemetry,
+            ipc::commands::metrics::start_metrics_exporter,
+            ipc::commands::metrics::stop_metrics_exporter,
+            ipc::commands::logs::get_device_logs,
+            ipc::commands::logs::clear_device_logs,
+            ipc::commands::analysis::recommend_relay_placement,
+            ipc::commands::analysis::estimate_coverage,
+            ipc::commands::analysis::calibrate_propagation_model,
+            ipc::commands::analysis::reset_propagation_model,
+            ipc::commands::stats::get_packet_stats,
+            ipc::commands::stats::get_raw_packet_log,
+            ipc::commands::stats::reset_packet_stats,
         ])
         .run(tauri::generate_context!())
         .expect("Error while running tauri application");
@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::device::telemetry::TelemetrySample;
+use crate::device::MeshDevice;
+use crate::graph::ds::graph::MeshGraph;
+use crate::packet_api::handlers::DeviceUpdateError;
+use crate::packet_api::stats::PacketStats;
+use crate::state::DeviceKey;
+
+pub mod server;
+
+/// Counters for packets handled by a single device's packet router
+#[derive(Clone, Debug, Default)]
+pub struct PacketMetrics {
+    pub update_errors: BTreeMap<&'static str, u64>, // keyed by `DeviceUpdateError` variant
+    pub message_acks: u64,
+    pub message_naks: BTreeMap<String, u64>, // keyed by routing error
+}
+
+impl PacketMetrics {
+    pub fn record_update_error(&mut self, error: &DeviceUpdateError) {
+        *self.update_errors.entry(error.variant_name()).or_default() += 1;
+    }
+
+    pub fn record_ack(&mut self) {
+        self.message_acks += 1;
+    }
+
+    pub fn record_nak(&mut self, reason: &str) {
+        *self.message_naks.entry(reason.to_string()).or_default() += 1;
+    }
+}
+
+/// A device and its packet counters, as exposed to the exporter
+pub struct DeviceMetricsSource<'a> {
+    pub device_key: &'a DeviceKey,
+    pub device: &'a MeshDevice,
+    pub packet_metrics: &'a PacketMetrics,
+    pub packet_stats: &'a PacketStats,
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+struct MetricFamily {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    samples: Vec<(Labels, f64)>,
+}
+
+impl MetricFamily {
+    fn gauge(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: "gauge",
+            samples: vec![],
+        }
+    }
+
+    fn counter(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: "counter",
+            samples: vec![],
+        }
+    }
+
+    fn push(&mut self, labels: Labels, value: f64) {
+        if value.is_finite() {
+            self.samples.push((labels, value));
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", self.name, self.kind);
+
+        for (labels, value) in self.samples.iter() {
+            let labels = labels
+                .iter()
+                .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+                .collect::<Vec<String>>()
+                .join(",");
+
+            let _ = writeln!(out, "{}{{{}}} {}", self.name, labels, value);
+        }
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn node_id(node_num: u32) -> String {
+    format!("!{:08x}", node_num)
+}
+
+/// Renders mesh health in the Prometheus text exposition format
+pub fn render_metrics(devices: &[DeviceMetricsSource], graph: &MeshGraph, now: u32) -> String {
+    let mut battery = MetricFamily::gauge(
+        "meshtastic_node_battery_level",
+        "Battery level from the most recent device metrics, in percent",
+    );
+    let mut voltage = MetricFamily::gauge(
+        "meshtastic_node_voltage",
+        "Battery voltage from the most recent device metrics, in volts",
+    );
+    let mut channel_utilization = MetricFamily::gauge(
+        "meshtastic_node_channel_utilization",
+        "Channel utilization from the most recent device metrics, in percent",
+    );
+    let mut air_util_tx = MetricFamily::gauge(
+        "meshtastic_node_air_util_tx",
+        "Transmit airtime from the most recent device metrics, in percent",
+    );
+    let mut pax_wifi = MetricFamily::gauge(
+        "meshtastic_node_pax_wifi",
+        "WiFi devices seen in the most recent paxcounter report",
+    );
+    let mut pax_ble = MetricFamily::gauge(
+        "meshtastic_node_pax_ble",
+        "BLE devices seen in the most recent paxcounter report",
+    );
+    let mut last_heard_age = MetricFamily::gauge(
+        "meshtastic_node_last_heard_age_seconds",
+        "Seconds since a packet was last received from the node",
+    );
+    let mut node_snr = MetricFamily::gauge(
+        "meshtastic_node_snr",
+        "SNR of the last packet received from the node, in dB",
+    );
+    let mut packets = MetricFamily::counter(
+        "meshtastic_packets_total",
+        "Mesh packets received by the client, by port and outcome",
+    );
+    let mut update_errors = MetricFamily::counter(
+        "meshtastic_packet_errors_total",
+        "Packets that failed to update device state, by error kind",
+    );
+    let mut acks = MetricFamily::counter(
+        "meshtastic_message_acks_total",
+        "Sent messages acknowledged by the mesh",
+    );
+    let mut naks = MetricFamily::counter(
+        "meshtastic_message_naks_total",
+        "Sent messages that failed, by routing error",
+    );
+    let mut edge_snr = MetricFamily::gauge(
+        "meshtastic_edge_snr",
+        "SNR between neighboring nodes reported in neighbor info, in dB",
+    );
+
+    for source in devices {
+        let device_label = || ("device", source.device_key.clone());
+
+        let mut nodes: Vec<_> = source.device.nodes.values().collect();
+        nodes.sort_by_key(|node| node.node_num);
+
+        for node in nodes {
+            let labels = || -> Labels {
+                vec![
+                    device_label(),
+                    ("node", node_id(node.node_num)),
+                    ("node_num", node.node_num.to_string()),
+                ]
+            };
+
+            if let Some(metrics) = node.device_metrics.last() {
+                for (field, value) in metrics.numeric_fields() {
+                    let family = match field {
+                        "batteryLevel" => &mut battery,
+                        "voltage" => &mut voltage,
+                        "channelUtilization" => &mut channel_utilization,
+                        "airUtilTx" => &mut air_util_tx,
+                        _ => continue,
+                    };
+
+                    family.push(labels(), value);
+                }
+            }
+
+            if let Some(paxcount) = node.paxcount_metrics.last() {
+                for (field, value) in paxcount.numeric_fields() {
+                    let family = match field {
+                        "wifi" => &mut pax_wifi,
+                        "ble" => &mut pax_ble,
+                        _ => continue,
+                    };
+
+                    family.push(labels(), value);
+                }
+            }
+
+            if let Some(last_heard) = node.last_heard.as_ref() {
+                last_heard_age.push(labels(), now.saturating_sub(last_heard.timestamp) as f64);
+                node_snr.push(labels(), last_heard.snr as f64);
+            }
+        }
+
+        let mut portnums: Vec<_> = source.packet_stats.by_portnum.iter().collect();
+        portnums.sort_by_key(|(portnum, _)| *portnum);
+
+        for (portnum, counts) in portnums {
+            for (outcome, count) in [
+                ("handled", counts.handled),
+                ("unsupported", counts.unsupported),
+                ("decode_failure", counts.decode_failure),
+                ("failed", counts.failed),
+            ] {
+                packets.push(
+                    vec![
+                        device_label(),
+                        ("portnum", portnum.clone()),
+                        ("outcome", outcome.to_string()),
+                    ],
+                    count as f64,
+                );
+            }
+        }
+
+        let metrics = source.packet_metrics;
+
+        for (kind, count) in metrics.update_errors.iter() {
+            update_errors.push(
+                vec![device_label(), ("kind", kind.to_string())],
+                *count as f64,
+            );
+        }
+
+        acks.push(vec![device_label()], metrics.message_acks as f64);
+
+        for (reason, count) in metrics.message_naks.iter() {
+            naks.push(
+                vec![device_label(), ("reason", reason.clone())],
+                *count as f64,
+            );
+        }
+    }
+
+    for (from, to, edge) in graph.edges() {
+        edge_snr.push(
+            vec![
+                ("from", node_id(from.node_num)),
+                ("to", node_id(to.node_num)),
+            ],
+            edge.snr(),
+        );
+    }
+
+    let mut out = String::new();
+
+    for family in [
+        battery,
+        voltage,
+        channel_utilization,
+        air_util_tx,
+        pax_wifi,
+        pax_ble,
+        last_heard_age,
+        node_snr,
+        packets,
+        update_errors,
+        acks,
+        naks,
+        edge_snr,
+    ] {
+        family.render(&mut out);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_rendered_with_labels() {
+        let device_key: DeviceKey = "/dev/tty\"USB0\"".into();
+        let device = MeshDevice::new();
+
+        let mut packet_metrics = PacketMetrics::default();
+        packet_metrics.record_nak("MAX_RETRANSMIT");
+
+        let mut packet_stats = PacketStats::default();
+        packet_stats
+            .by_portnum
+            .entry("TEXT_MESSAGE_APP".into())
+            .or_default()
+            .handled = 2;
+
+        let rendered = render_metrics(
+            &[DeviceMetricsSource {
+                device_key: &device_key,
+                device: &device,
+                packet_metrics: &packet_metrics,
+                packet_stats: &packet_stats,
+            }],
+            &MeshGraph::new(),
+            0,
+        );
+
+        assert!(rendered.contains("# TYPE meshtastic_packets_total counter\n"));
+        assert!(rendered.contains(
+            "meshtastic_packets_total{device=\"/dev/tty\\\"USB0\\\"\",portnum=\"TEXT_MESSAGE_APP\",outcome=\"handled\"} 2\n"
+        ));
+        assert!(rendered.contains(
+            "meshtastic_message_naks_total{device=\"/dev/tty\\\"USB0\\\"\",reason=\"MAX_RETRANSMIT\"} 1\n"
+        ));
+    }
+}
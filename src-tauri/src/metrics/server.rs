@@ -0,0 +1,126 @@
+use std::net::SocketAddr;
+
+use log::{debug, error, info, warn};
+use tauri::async_runtime::{self, JoinHandle};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::device::helpers::get_current_time_u32;
+use crate::state::graph::GraphStateInner;
+use crate::state::mesh_devices::MeshDevicesStateInner;
+
+use super::{render_metrics, DeviceMetricsSource};
+
+pub const DEFAULT_METRICS_PORT: u16 = 9464;
+
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub struct MetricsServer {
+    pub address: SocketAddr,
+    pub handle: JoinHandle<()>,
+}
+
+/// Binds a listener and serves the `/metrics` endpoint until the returned
+/// handle is aborted
+pub async fn start_metrics_server(
+    address: SocketAddr,
+    mesh_devices: MeshDevicesStateInner,
+    mesh_graph: GraphStateInner,
+) -> std::io::Result<MetricsServer> {
+    let listener = TcpListener::bind(address).await?;
+    let address = listener.local_addr()?;
+
+    info!("Serving metrics on http://{}/metrics", address);
+
+    let handle = async_runtime::spawn(async move {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    error!("Error accepting metrics connection: {}", e);
+                    continue;
+                }
+            };
+
+            debug!("Accepted metrics connection from {}", peer);
+
+            let mesh_devices = mesh_devices.clone();
+            let mesh_graph = mesh_graph.clone();
+
+            async_runtime::spawn(async move {
+                if let Err(e) = handle_connection(stream, mesh_devices, mesh_graph).await {
+                    warn!("Error handling metrics request from {}: {}", peer, e);
+                }
+            });
+        }
+    });
+
+    Ok(MetricsServer { address, handle })
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    mesh_devices: MeshDevicesStateInner,
+    mesh_graph: GraphStateInner,
+) -> std::io::Result<()> {
+    let mut buffer = [0u8; 1024];
+    let read = stream.read(&mut buffer).await?;
+    let request = String::from_utf8_lossy(&buffer[..read]);
+
+    // Only the request line matters, so headers and bodies are ignored
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let target = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => target.split('?').next(),
+        _ => None,
+    };
+
+    let (status, body) = match target {
+        Some("/metrics") => ("200 OK", collect_metrics(mesh_devices, mesh_graph).await),
+        Some(_) => ("404 Not Found", "Not found\n".to_string()),
+        None => ("405 Method Not Allowed", "Method not allowed\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        METRICS_CONTENT_TYPE,
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+async fn collect_metrics(
+    mesh_devices: MeshDevicesStateInner,
+    mesh_graph: GraphStateInner,
+) -> String {
+    let devices_guard = mesh_devices.lock().await;
+
+    let sources: Vec<DeviceMetricsSource> = devices_guard
+        .iter()
+        .map(|(device_key, packet_api)| DeviceMetricsSource {
+            device_key,
+            device: &packet_api.device,
+            packet_metrics: &packet_api.metrics,
+            packet_stats: &packet_api.packet_log.stats,
+        })
+        .collect();
+
+    let graph_guard = match mesh_graph.lock() {
+        Ok(guard) => guard,
+        Err(e) => {
+            error!("Error getting graph handle: {}", e);
+            return String::new();
+        }
+    };
+
+    render_metrics(&sources, &graph_guard, get_current_time_u32())
+}
@@ -0,0 +1,331 @@
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, SecondsFormat};
+use log::debug;
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::device::helpers::get_current_time_u32;
+use crate::ipc::events;
+use crate::state::DeviceKey;
+
+use super::handlers::DeviceUpdateError;
+use super::MeshPacketApi;
+
+/// Number of log records kept in memory before the oldest are dropped
+pub const DEVICE_LOG_CAPACITY: usize = 2000;
+
+/// Size at which the log file is rotated, keeping a single previous file
+const MAX_LOG_FILE_LEN: u64 = 5 * 1024 * 1024;
+
+pub fn get_device_log_path(app_log_dir: &Path, device_key: &DeviceKey) -> PathBuf {
+    let file_name: String = device_key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    app_log_dir
+        .join("devices")
+        .join(format!("{}.log", file_name))
+}
+
+/// Ordered from least to most severe
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceLogLevel {
+    Unset,
+    Trace,
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl From<protobufs::log_record::Level> for DeviceLogLevel {
+    fn from(level: protobufs::log_record::Level) -> Self {
+        match level {
+            protobufs::log_record::Level::Unset => DeviceLogLevel::Unset,
+            protobufs::log_record::Level::Trace => DeviceLogLevel::Trace,
+            protobufs::log_record::Level::Debug => DeviceLogLevel::Debug,
+            protobufs::log_record::Level::Info => DeviceLogLevel::Info,
+            protobufs::log_record::Level::Warning => DeviceLogLevel::Warning,
+            protobufs::log_record::Level::Error => DeviceLogLevel::Error,
+            protobufs::log_record::Level::Critical => DeviceLogLevel::Critical,
+        }
+    }
+}
+
+impl DeviceLogLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DeviceLogLevel::Unset => "UNSET",
+            DeviceLogLevel::Trace => "TRACE",
+            DeviceLogLevel::Debug => "DEBUG",
+            DeviceLogLevel::Info => "INFO",
+            DeviceLogLevel::Warning => "WARN",
+            DeviceLogLevel::Error => "ERROR",
+            DeviceLogLevel::Critical => "CRIT",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLogEntry {
+    pub time: u32, // secs, from the device clock when it's set
+    pub level: DeviceLogLevel,
+    pub source: String, // firmware module that logged the record
+    pub message: String,
+}
+
+impl DeviceLogEntry {
+    fn to_line(&self) -> String {
+        let time = DateTime::from_timestamp(self.time as i64, 0)
+            .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_default();
+
+        format!(
+            "{} {} [{}] {}\n",
+            time,
+            self.level.as_str(),
+            self.source,
+            self.message.trim_end()
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLogFilter {
+    pub min_level: Option<DeviceLogLevel>,
+    pub text: Option<String>, // case-insensitive match on message or source
+    pub limit: Option<u32>,   // most recent matching records
+}
+
+impl DeviceLogFilter {
+    fn matches(&self, entry: &DeviceLogEntry, text: Option<&str>) -> bool {
+        if self.min_level.is_some_and(|level| entry.level < level) {
+            return false;
+        }
+
+        match text {
+            Some(text) => {
+                entry.message.to_lowercase().contains(text)
+                    || entry.source.to_lowercase().contains(text)
+            }
+            None => true,
+        }
+    }
+}
+
+/// Log lines waiting to be appended to a device's log file, written
+/// once the device lock has been released
+#[derive(Debug)]
+pub struct PendingLogWrite {
+    path: PathBuf,
+    contents: String,
+}
+
+impl PendingLogWrite {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends to the log file, first rotating it if it has grown too large
+    pub fn write(&self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        if std::fs::metadata(&self.path).is_ok_and(|m| m.len() >= MAX_LOG_FILE_LEN) {
+            std::fs::rename(&self.path, self.path.with_extension("log.1"))?;
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(self.contents.as_bytes())
+    }
+}
+
+/// Rolling buffer of the radio's debug log, mirrored to a file on disk
+#[derive(Debug, Default)]
+pub struct DeviceLogBuffer {
+    entries: VecDeque<DeviceLogEntry>,
+    path: Option<PathBuf>,
+    unwritten: String, // lines not yet appended to the log file
+}
+
+impl DeviceLogBuffer {
+    pub fn set_path(&mut self, path: PathBuf) {
+        debug!("Writing device logs to {:?}", path);
+
+        self.path = Some(path);
+        self.unwritten.clear();
+    }
+
+    pub fn record(&mut self, record: protobufs::LogRecord) -> DeviceLogEntry {
+        let entry = DeviceLogEntry {
+            time: match record.time {
+                0 => get_current_time_u32(),
+                time => time,
+            },
+            level: record.level().into(),
+            source: record.source,
+            message: record.message,
+        };
+
+        if self.path.is_some() {
+            self.unwritten.push_str(&entry.to_line());
+        }
+
+        self.entries.push_back(entry.clone());
+
+        while self.entries.len() > DEVICE_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+
+        entry
+    }
+
+    pub fn query(&self, filter: &DeviceLogFilter) -> Vec<DeviceLogEntry> {
+        let text = filter
+            .text
+            .as_ref()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty());
+        let limit = filter
+            .limit
+            .map(|l| l as usize)
+            .unwrap_or(DEVICE_LOG_CAPACITY);
+
+        let mut entries: Vec<DeviceLogEntry> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|e| filter.matches(e, text.as_deref()))
+            .take(limit)
+            .cloned()
+            .collect();
+
+        entries.reverse();
+        entries
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Takes the lines recorded since the last call, to be appended to
+    /// the log file without holding the device lock
+    pub fn take_pending_write(&mut self) -> Option<PendingLogWrite> {
+        if self.unwritten.is_empty() {
+            return None;
+        }
+
+        Some(PendingLogWrite {
+            path: self.path.clone()?,
+            contents: std::mem::take(&mut self.unwritten),
+        })
+    }
+}
+
+impl<R: tauri::Runtime> MeshPacketApi<R> {
+    pub fn handle_log_record(
+        &mut self,
+        record: protobufs::LogRecord,
+    ) -> Result<(), DeviceUpdateError> {
+        let entry = self.device_logs.record(record);
+
+        events::dispatch_device_log(&self.app_handle, &self.device_key, &entry)
+            .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::helpers::generate_rand_id;
+
+    fn record(
+        level: protobufs::log_record::Level,
+        source: &str,
+        message: &str,
+    ) -> protobufs::LogRecord {
+        protobufs::LogRecord {
+            message: message.into(),
+            time: 1_700_000_000,
+            source: source.into(),
+            level: level as i32,
+        }
+    }
+
+    #[test]
+    fn logs_filtered_by_level_and_text() {
+        let mut logs = DeviceLogBuffer::default();
+
+        logs.record(record(
+            protobufs::log_record::Level::Debug,
+            "Router",
+            "Rx packet",
+        ));
+        logs.record(record(
+            protobufs::log_record::Level::Warning,
+            "GPS",
+            "No fix",
+        ));
+        logs.record(record(
+            protobufs::log_record::Level::Error,
+            "Router",
+            "TX failed",
+        ));
+
+        let warnings = logs.query(&DeviceLogFilter {
+            min_level: Some(DeviceLogLevel::Warning),
+            ..Default::default()
+        });
+        let router = logs.query(&DeviceLogFilter {
+            text: Some("router".into()),
+            limit: Some(1),
+            ..Default::default()
+        });
+
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(router.len(), 1);
+        assert_eq!(router[0].message, "TX failed");
+    }
+
+    #[test]
+    fn log_lines_written_in_batches() {
+        let path = std::env::temp_dir()
+            .join(format!("device-logs-{}", generate_rand_id::<u32>()))
+            .join("radio.log");
+        let mut logs = DeviceLogBuffer::default();
+
+        logs.record(record(protobufs::log_record::Level::Info, "Router", "a"));
+        assert!(logs.take_pending_write().is_none());
+
+        logs.set_path(path.clone());
+        logs.record(record(protobufs::log_record::Level::Info, "Router", "b"));
+        logs.record(record(protobufs::log_record::Level::Info, "GPS", "c"));
+
+        logs.take_pending_write().unwrap().write().unwrap();
+        assert!(logs.take_pending_write().is_none());
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(
+            contents,
+            "2023-11-14T22:13:20Z INFO [Router] b\n2023-11-14T22:13:20Z INFO [GPS] c\n"
+        );
+    }
+}
@@ -0,0 +1,763 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use log::{debug, trace, warn};
+use meshtastic::packet::PacketRouter;
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::device::helpers::generate_rand_id;
+use crate::ipc::DeltaPayload;
+
+use super::handlers::DeviceUpdateError;
+use super::outbox::{MessagePriority, Outbox, BROADCAST_NODE_NUM};
+use super::MeshPacketApi;
+
+/// First byte of fragmentation frames sent on the private app portnum
+pub const FRAGMENT_PROTOCOL_ID: u8 = 0x01;
+
+/// Largest payload firmware will send in a single packet
+pub const MAX_PAYLOAD_LEN: usize = 233;
+
+const CHUNK_KIND: u8 = 0x00;
+const RETRANSMIT_REQUEST_KIND: u8 = 0x01;
+
+// protocol, kind, message id, portnum, index, total
+const CHUNK_HEADER_LEN: usize = 1 + 1 + 4 + 2 + 1 + 1;
+
+// protocol, kind, message id
+const RETRANSMIT_REQUEST_HEADER_LEN: usize = 1 + 1 + 4;
+
+/// Most chunk indices a single retransmit request can carry
+pub const MAX_RETRANSMIT_INDICES: usize = MAX_PAYLOAD_LEN - RETRANSMIT_REQUEST_HEADER_LEN;
+
+/// Only user content is reassembled, since the reassembled packet is routed
+/// as if the sender had sent it directly. Routing and admin packets would
+/// otherwise be forgeable by any node on the channel.
+const REASSEMBLED_PORTNUMS: [protobufs::PortNum; 3] = [
+    protobufs::PortNum::TextMessageApp,
+    protobufs::PortNum::TextMessageCompressedApp,
+    protobufs::PortNum::WaypointApp,
+];
+
+/// Partial messages kept for a single sender before its oldest is dropped
+pub const MAX_PARTIAL_MESSAGES_PER_SENDER: usize = 4;
+
+/// Partial messages kept across all senders before the oldest is dropped
+pub const MAX_PARTIAL_MESSAGES: usize = 32;
+
+/// Bytes of the original payload carried by each chunk
+pub const CHUNK_DATA_LEN: usize = MAX_PAYLOAD_LEN - CHUNK_HEADER_LEN;
+
+/// Time without new chunks after which missing chunks are requested again
+pub const RETRANSMIT_REQUEST_DELAY: Duration = Duration::from_secs(30);
+
+pub const MAX_RETRANSMIT_REQUESTS: u32 = 3;
+
+/// Time a partial message is kept, and a sent message is kept available for
+/// retransmission
+pub const REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum FragmentError {
+    #[error("Fragment frame truncated")]
+    Truncated,
+    #[error("Unknown fragment frame kind {0}")]
+    UnknownKind(u8),
+    #[error("Chunk index {index} out of range for {total} chunks")]
+    InvalidIndex { index: u8, total: u8 },
+    #[error("Payload of {0} bytes is too large to fragment")]
+    TooLarge(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FragmentChunk {
+    pub message_id: u32,
+    pub portnum: u16, // portnum of the reassembled payload
+    pub index: u8,
+    pub total: u8,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FragmentFrame {
+    Chunk(FragmentChunk),
+    RetransmitRequest { message_id: u32, missing: Vec<u8> },
+}
+
+impl FragmentFrame {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![FRAGMENT_PROTOCOL_ID];
+
+        match self {
+            FragmentFrame::Chunk(chunk) => {
+                bytes.push(CHUNK_KIND);
+                bytes.extend_from_slice(&chunk.message_id.to_le_bytes());
+                bytes.extend_from_slice(&chunk.portnum.to_le_bytes());
+                bytes.push(chunk.index);
+                bytes.push(chunk.total);
+                bytes.extend_from_slice(&chunk.data);
+            }
+            FragmentFrame::RetransmitRequest {
+                message_id,
+                missing,
+            } => {
+                bytes.push(RETRANSMIT_REQUEST_KIND);
+                bytes.extend_from_slice(&message_id.to_le_bytes());
+                bytes.extend_from_slice(missing);
+            }
+        }
+
+        bytes
+    }
+
+    /// Decodes a frame, including the leading protocol byte
+    pub fn decode(bytes: &[u8]) -> Result<Self, FragmentError> {
+        if bytes.len() < RETRANSMIT_REQUEST_HEADER_LEN {
+            return Err(FragmentError::Truncated);
+        }
+
+        let message_id = u32::from_le_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]);
+
+        match bytes[1] {
+            CHUNK_KIND => {
+                if bytes.len() < CHUNK_HEADER_LEN {
+                    return Err(FragmentError::Truncated);
+                }
+
+                let index = bytes[8];
+                let total = bytes[9];
+
+                if index >= total {
+                    return Err(FragmentError::InvalidIndex { index, total });
+                }
+
+                Ok(FragmentFrame::Chunk(FragmentChunk {
+                    message_id,
+                    portnum: u16::from_le_bytes([bytes[6], bytes[7]]),
+                    index,
+                    total,
+                    data: bytes[CHUNK_HEADER_LEN..].to_vec(),
+                }))
+            }
+            RETRANSMIT_REQUEST_KIND => Ok(FragmentFrame::RetransmitRequest {
+                message_id,
+                missing: bytes[RETRANSMIT_REQUEST_HEADER_LEN..].to_vec(),
+            }),
+            kind => Err(FragmentError::UnknownKind(kind)),
+        }
+    }
+}
+
+/// Splits a payload into encoded chunk frames
+pub fn split_payload(
+    message_id: u32,
+    portnum: protobufs::PortNum,
+    payload: &[u8],
+) -> Result<Vec<Vec<u8>>, FragmentError> {
+    let chunks: Vec<&[u8]> = payload.chunks(CHUNK_DATA_LEN).collect();
+    let total = u8::try_from(chunks.len()).map_err(|_| FragmentError::TooLarge(payload.len()))?;
+
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| {
+            FragmentFrame::Chunk(FragmentChunk {
+                message_id,
+                portnum: portnum as u16,
+                index: index as u8,
+                total,
+                data: data.to_vec(),
+            })
+            .encode()
+        })
+        .collect())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FragmentProgress {
+    pub from: u32,
+    pub channel: u32,
+    pub message_id: u32,
+    pub received: u32,
+    pub total: u32,
+    pub expired: bool, // the message was dropped before all chunks arrived
+}
+
+#[derive(Clone, Debug)]
+struct PartialMessage {
+    packet: protobufs::MeshPacket, // most recent chunk, used as a template for the full packet
+    portnum: u16,
+    total: u8,
+    chunks: BTreeMap<u8, Vec<u8>>,
+    last_received: Instant,
+    retransmit_requests: u32,
+}
+
+impl PartialMessage {
+    fn progress(&self, message_id: u32, expired: bool) -> FragmentProgress {
+        FragmentProgress {
+            from: self.packet.from,
+            channel: self.packet.channel,
+            message_id,
+            received: self.chunks.len() as u32,
+            total: self.total as u32,
+            expired,
+        }
+    }
+
+    /// Missing chunk indices, limited to what fits in a single request
+    fn missing(&self) -> Vec<u8> {
+        (0..self.total)
+            .filter(|i| !self.chunks.contains_key(i))
+            .take(MAX_RETRANSMIT_INDICES)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SentMessage {
+    channel: u32,
+    chunks: Vec<Vec<u8>>,
+    sent_at: Instant,
+}
+
+#[derive(Clone, Debug)]
+pub enum ReassemblyUpdate {
+    Progress(FragmentProgress),
+    Complete(protobufs::MeshPacket, FragmentProgress),
+    Duplicate, // chunk of a message that was already reassembled
+    Rejected,  // chunk of a payload that isn't reassembled
+}
+
+#[derive(Clone, Debug)]
+pub enum ReassemblyExpiry {
+    RequestRetransmit {
+        to: u32,
+        channel: u32,
+        message_id: u32,
+        missing: Vec<u8>,
+    },
+    Expired(FragmentProgress),
+}
+
+/// Reassembles fragmented messages from other nodes, and keeps messages
+/// fragmented by this client so missing chunks can be resent on request
+#[derive(Debug, Default)]
+pub struct FragmentState {
+    partial: HashMap<(u32, u32), PartialMessage>, // keyed by sender and message id
+    completed: HashMap<(u32, u32), Instant>,
+    sent: HashMap<u32, SentMessage>,
+}
+
+impl FragmentState {
+    pub fn record_sent(&mut self, message_id: u32, channel: u32, chunks: Vec<Vec<u8>>) {
+        self.sent.insert(
+            message_id,
+            SentMessage {
+                channel,
+                chunks,
+                sent_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Returns the channel and encoded chunks requested by another node
+    pub fn requested_chunks(&self, message_id: u32, missing: &[u8]) -> Option<(u32, Vec<Vec<u8>>)> {
+        let message = self.sent.get(&message_id)?;
+
+        let chunks = missing
+            .iter()
+            .filter_map(|i| message.chunks.get(*i as usize))
+            .cloned()
+            .collect();
+
+        Some((message.channel, chunks))
+    }
+
+    /// Drops the oldest partial messages so one more from `from` fits
+    fn make_room(&mut self, from: u32) {
+        let oldest = |partial: &HashMap<(u32, u32), PartialMessage>, from: Option<u32>| {
+            partial
+                .iter()
+                .filter(|((sender, _), _)| match from {
+                    Some(from) => *sender == from,
+                    None => true,
+                })
+                .min_by_key(|(_, p)| p.last_received)
+                .map(|(key, _)| *key)
+        };
+
+        let from_sender = self
+            .partial
+            .keys()
+            .filter(|(sender, _)| *sender == from)
+            .count();
+
+        if from_sender >= MAX_PARTIAL_MESSAGES_PER_SENDER {
+            if let Some(key) = oldest(&self.partial, Some(from)) {
+                debug!(
+                    "Dropping partial message {} from {} to make room",
+                    key.1, key.0
+                );
+                self.partial.remove(&key);
+            }
+        }
+
+        if self.partial.len() >= MAX_PARTIAL_MESSAGES {
+            if let Some(key) = oldest(&self.partial, None) {
+                debug!(
+                    "Dropping partial message {} from {} to make room",
+                    key.1, key.0
+                );
+                self.partial.remove(&key);
+            }
+        }
+    }
+
+    pub fn receive_chunk(
+        &mut self,
+        packet: &protobufs::MeshPacket,
+        chunk: FragmentChunk,
+        now: Instant,
+    ) -> ReassemblyUpdate {
+        let key = (packet.from, chunk.message_id);
+
+        let allowed = protobufs::PortNum::from_i32(chunk.portnum as i32)
+            .is_some_and(|portnum| REASSEMBLED_PORTNUMS.contains(&portnum));
+
+        if !allowed {
+            warn!(
+                "Dropping chunk of message {} from {} with disallowed portnum {}",
+                chunk.message_id, packet.from, chunk.portnum
+            );
+            return ReassemblyUpdate::Rejected;
+        }
+
+        if self.completed.contains_key(&key) {
+            return ReassemblyUpdate::Duplicate;
+        }
+
+        if !self.partial.contains_key(&key) {
+            self.make_room(packet.from);
+        }
+
+        let partial = self.partial.entry(key).or_insert_with(|| PartialMessage {
+            packet: packet.clone(),
+            portnum: chunk.portnum,
+            total: chunk.total,
+            chunks: BTreeMap::new(),
+            last_received: now,
+            retransmit_requests: 0,
+        });
+
+        // A different chunk count means the id was reused for a new message
+        if partial.total != chunk.total || partial.portnum != chunk.portnum {
+            partial.total = chunk.total;
+            partial.portnum = chunk.portnum;
+            partial.chunks.clear();
+        }
+
+        trace!(
+            "Received chunk {} of {} for message {} from {}",
+            chunk.index + 1,
+            chunk.total,
+            chunk.message_id,
+            packet.from
+        );
+
+        partial.packet = packet.clone();
+        partial.last_received = now;
+        partial.chunks.insert(chunk.index, chunk.data);
+
+        if partial.chunks.len() < partial.total as usize {
+            return ReassemblyUpdate::Progress(partial.progress(chunk.message_id, false));
+        }
+
+        let progress = partial.progress(chunk.message_id, false);
+        let partial = self.partial.remove(&key).expect("Partial message exists");
+        self.completed.insert(key, now);
+
+        debug!(
+            "Reassembled message {} from {} out of {} chunks",
+            chunk.message_id, packet.from, partial.total
+        );
+
+        let payload = partial.chunks.into_values().flatten().collect();
+
+        let reassembled = protobufs::MeshPacket {
+            id: chunk.message_id,
+            payload_variant: Some(protobufs::mesh_packet::PayloadVariant::Decoded(
+                protobufs::Data {
+                    portnum: partial.portnum as i32,
+                    payload,
+                    ..Default::default()
+                },
+            )),
+            ..partial.packet
+        };
+
+        ReassemblyUpdate::Complete(reassembled, progress)
+    }
+
+    /// Requests missing chunks of stalled messages, and drops messages that
+    /// can no longer be completed
+    pub fn expire(&mut self, now: Instant) -> Vec<ReassemblyExpiry> {
+        let is_expired = |t: Instant| now.saturating_duration_since(t) > REASSEMBLY_TIMEOUT;
+
+        self.completed.retain(|_, t| !is_expired(*t));
+        self.sent.retain(|_, m| !is_expired(m.sent_at));
+
+        let mut expiries = vec![];
+
+        self.partial.retain(|(from, message_id), partial| {
+            if is_expired(partial.last_received) {
+                debug!("Dropping incomplete message {} from {}", message_id, from);
+                expiries.push(ReassemblyExpiry::Expired(
+                    partial.progress(*message_id, true),
+                ));
+                return false;
+            }
+
+            let stalled =
+                now.saturating_duration_since(partial.last_received) > RETRANSMIT_REQUEST_DELAY;
+
+            if stalled && partial.retransmit_requests < MAX_RETRANSMIT_REQUESTS {
+                partial.retransmit_requests += 1;
+                partial.last_received = now;
+
+                expiries.push(ReassemblyExpiry::RequestRetransmit {
+                    to: *from,
+                    channel: partial.packet.channel,
+                    message_id: *message_id,
+                    missing: partial.missing(),
+                });
+            }
+
+            true
+        });
+
+        expiries
+    }
+}
+
+fn private_packet(from: u32, to: u32, channel: u32, payload: Vec<u8>) -> protobufs::MeshPacket {
+    protobufs::MeshPacket {
+        id: generate_rand_id(),
+        from,
+        to,
+        channel,
+        want_ack: true,
+        payload_variant: Some(protobufs::mesh_packet::PayloadVariant::Decoded(
+            protobufs::Data {
+                portnum: protobufs::PortNum::PrivateApp as i32,
+                payload,
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    }
+}
+
+/// Queues requests for the missing chunks of stalled messages, returning the
+/// progress of messages that timed out. Requests aren't channel messages, so
+/// they're queued as transient packets that are never persisted or shown.
+fn queue_retransmit_requests(
+    fragments: &mut FragmentState,
+    outbox: &mut Outbox,
+    from: u32,
+    now: Instant,
+) -> Vec<FragmentProgress> {
+    let mut expired = vec![];
+
+    for expiry in fragments.expire(now) {
+        match expiry {
+            ReassemblyExpiry::RequestRetransmit {
+                to,
+                channel,
+                message_id,
+                missing,
+            } => {
+                debug!(
+                    "Requesting {} missing chunks of message {} from {}",
+                    missing.len(),
+                    message_id,
+                    to
+                );
+
+                let request = FragmentFrame::RetransmitRequest {
+                    message_id,
+                    missing,
+                };
+                let packet = private_packet(from, to, channel, request.encode());
+
+                outbox.enqueue_transient(packet, MessagePriority::Routine);
+            }
+            ReassemblyExpiry::Expired(progress) => expired.push(progress),
+        }
+    }
+
+    expired
+}
+
+impl<R: tauri::Runtime> MeshPacketApi<R> {
+    /// Builds a packet from the connected node that isn't shown in the
+    /// device's messages
+    pub fn build_private_packet(
+        &self,
+        to: u32,
+        channel: u32,
+        payload: Vec<u8>,
+    ) -> protobufs::MeshPacket {
+        private_packet(self.device.my_node_info.my_node_num, to, channel, payload)
+    }
+
+    /// Queues the chunks of a payload too large for a single packet, tracked
+    /// in the outbox under the id of the full message
+    pub fn queue_fragmented_message(
+        &mut self,
+        packet: &protobufs::MeshPacket,
+        chunks: Vec<Vec<u8>>,
+        priority: MessagePriority,
+    ) {
+        debug!(
+            "Fragmenting message {} into {} chunks",
+            packet.id,
+            chunks.len()
+        );
+
+        let packets = chunks
+            .iter()
+            .map(|chunk| self.build_private_packet(packet.to, packet.channel, chunk.clone()))
+            .collect();
+
+        self.fragments
+            .record_sent(packet.id, packet.channel, chunks);
+        self.outbox.enqueue_fragments(packet.id, packets, priority);
+    }
+
+    /// Requests missing chunks and reports dropped messages
+    pub fn expire_fragments(&mut self, now: Instant) {
+        let expired = queue_retransmit_requests(
+            &mut self.fragments,
+            &mut self.outbox,
+            self.device.my_node_info.my_node_num,
+            now,
+        );
+
+        for progress in expired {
+            self.dispatcher.enqueue(
+                Some(&self.device_key),
+                DeltaPayload::FragmentProgress(progress),
+            );
+        }
+    }
+
+    pub fn handle_fragment_frame(
+        &mut self,
+        packet: protobufs::MeshPacket,
+        frame: FragmentFrame,
+    ) -> Result<(), DeviceUpdateError> {
+        match frame {
+            FragmentFrame::Chunk(chunk) => {
+                match self.fragments.receive_chunk(&packet, chunk, Instant::now()) {
+                    ReassemblyUpdate::Progress(progress) => {
+                        self.dispatcher.enqueue(
+                            Some(&self.device_key),
+                            DeltaPayload::FragmentProgress(progress),
+                        );
+                    }
+                    ReassemblyUpdate::Complete(reassembled, progress) => {
+                        // Route the full message as if it had arrived in one packet
+                        self.handle_mesh_packet(reassembled)?;
+
+                        self.dispatcher.enqueue(
+                            Some(&self.device_key),
+                            DeltaPayload::FragmentProgress(progress),
+                        );
+                    }
+                    ReassemblyUpdate::Duplicate | ReassemblyUpdate::Rejected => {}
+                }
+            }
+            FragmentFrame::RetransmitRequest {
+                message_id,
+                missing,
+            } => {
+                let (channel, chunks) = match self.fragments.requested_chunks(message_id, &missing)
+                {
+                    Some(requested) => requested,
+                    None => {
+                        trace!(
+                            "Ignoring retransmit request for unknown message {}",
+                            message_id
+                        );
+                        return Ok(());
+                    }
+                };
+
+                debug!(
+                    "Resending {} chunks of message {} requested by {}",
+                    chunks.len(),
+                    message_id,
+                    packet.from
+                );
+
+                let packets: Vec<_> = chunks
+                    .into_iter()
+                    .map(|chunk| self.build_private_packet(BROADCAST_NODE_NUM, channel, chunk))
+                    .collect();
+
+                // Resent chunks get their own ids so they don't change the
+                // state of the original message
+                for packet in packets {
+                    self.outbox
+                        .enqueue_transient(packet, MessagePriority::Routine);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_packet(from: u32, frame: &[u8]) -> (protobufs::MeshPacket, FragmentChunk) {
+        let chunk = match FragmentFrame::decode(frame).unwrap() {
+            FragmentFrame::Chunk(chunk) => chunk,
+            _ => panic!("Expected chunk"),
+        };
+
+        let packet = protobufs::MeshPacket {
+            from,
+            channel: 1,
+            ..Default::default()
+        };
+
+        (packet, chunk)
+    }
+
+    #[test]
+    fn out_of_order_chunks_reassembled() {
+        let payload: Vec<u8> = (0..500).map(|i| (i % 251) as u8).collect();
+        let frames = split_payload(7, protobufs::PortNum::TextMessageApp, &payload).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|f| f.len() <= MAX_PAYLOAD_LEN));
+
+        let mut state = FragmentState::default();
+        let now = Instant::now();
+
+        for index in [2, 0] {
+            let (packet, chunk) = chunk_packet(42, &frames[index]);
+            assert!(matches!(
+                state.receive_chunk(&packet, chunk, now),
+                ReassemblyUpdate::Progress(_)
+            ));
+        }
+
+        let (packet, chunk) = chunk_packet(42, &frames[1]);
+        let (reassembled, progress) = match state.receive_chunk(&packet, chunk, now) {
+            ReassemblyUpdate::Complete(packet, progress) => (packet, progress),
+            update => panic!("Expected complete message, got {:?}", update),
+        };
+
+        assert_eq!((reassembled.id, reassembled.from), (7, 42));
+        assert_eq!((progress.received, progress.total), (3, 3));
+        match reassembled.payload_variant {
+            Some(protobufs::mesh_packet::PayloadVariant::Decoded(data)) => {
+                assert_eq!(data.portnum(), protobufs::PortNum::TextMessageApp);
+                assert_eq!(data.payload, payload);
+            }
+            _ => panic!("Expected decoded payload"),
+        }
+
+        let (packet, chunk) = chunk_packet(42, &frames[1]);
+        assert!(matches!(
+            state.receive_chunk(&packet, chunk, now),
+            ReassemblyUpdate::Duplicate
+        ));
+    }
+
+    #[test]
+    fn stalled_message_requests_missing_chunks() {
+        let frames = split_payload(7, protobufs::PortNum::TextMessageApp, &[0; 500]).unwrap();
+        let mut state = FragmentState::default();
+        let now = Instant::now();
+
+        let (packet, chunk) = chunk_packet(42, &frames[1]);
+        state.receive_chunk(&packet, chunk, now);
+
+        assert!(state.expire(now).is_empty());
+
+        let expiries = state.expire(now + RETRANSMIT_REQUEST_DELAY + Duration::from_secs(1));
+        assert!(matches!(
+            expiries.as_slice(),
+            [ReassemblyExpiry::RequestRetransmit { to: 42, missing, .. }] if missing == &vec![0, 2]
+        ));
+
+        let expiries = state.expire(now + REASSEMBLY_TIMEOUT * 2);
+        assert!(matches!(
+            expiries.as_slice(),
+            [ReassemblyExpiry::Expired(progress)] if progress.expired && progress.received == 1
+        ));
+    }
+
+    #[test]
+    fn retransmit_requests_not_persisted() {
+        let frames = split_payload(7, protobufs::PortNum::TextMessageApp, &[0; 500]).unwrap();
+        let mut state = FragmentState::default();
+        let now = Instant::now();
+
+        let (packet, chunk) = chunk_packet(42, &frames[1]);
+        state.receive_chunk(&packet, chunk, now);
+
+        let path = std::env::temp_dir().join(format!("outbox-{}.json", generate_rand_id::<u32>()));
+        let mut outbox = Outbox::default();
+        outbox.load(path.clone()).unwrap();
+
+        let later = now + RETRANSMIT_REQUEST_DELAY + Duration::from_secs(1);
+        assert!(queue_retransmit_requests(&mut state, &mut outbox, 1, later).is_empty());
+        assert_eq!(outbox.status().queued, 1);
+
+        outbox.persist().unwrap().unwrap().write().unwrap();
+
+        let mut restored = Outbox::default();
+        restored.load(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored.status().queued, 0);
+    }
+
+    #[test]
+    fn control_portnums_not_reassembled() {
+        let frames = split_payload(7, protobufs::PortNum::RoutingApp, &[0; 500]).unwrap();
+        let mut state = FragmentState::default();
+
+        let (packet, chunk) = chunk_packet(42, &frames[0]);
+        assert!(matches!(
+            state.receive_chunk(&packet, chunk, Instant::now()),
+            ReassemblyUpdate::Rejected
+        ));
+        assert!(state.partial.is_empty());
+    }
+
+    #[test]
+    fn partial_messages_bounded_per_sender() {
+        let mut state = FragmentState::default();
+        let now = Instant::now();
+
+        for message_id in 0..(MAX_PARTIAL_MESSAGES_PER_SENDER as u32 + 2) {
+            let frames =
+                split_payload(message_id, protobufs::PortNum::TextMessageApp, &[0; 500]).unwrap();
+            let (packet, chunk) = chunk_packet(42, &frames[0]);
+            state.receive_chunk(&packet, chunk, now + Duration::from_secs(message_id as u64));
+        }
+
+        assert_eq!(state.partial.len(), MAX_PARTIAL_MESSAGES_PER_SENDER);
+        assert!(!state.partial.contains_key(&(42, 0)));
+    }
+}
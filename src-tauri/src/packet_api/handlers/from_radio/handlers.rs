@@ -1,9 +1,11 @@
+use std::time::Instant;
+
 use log::debug;
 use meshtastic::protobufs;
 
 use crate::{
     device::{helpers::get_current_time_u32, MeshChannel, SerialDeviceStatus},
-    ipc::{events, ConfigurationStatus},
+    ipc::{events, ConfigurationStatus, DeltaPayload},
     packet_api::{handlers::DeviceUpdateError, MeshPacketApi},
 };
 
@@ -18,8 +20,9 @@ pub fn handle_channel_packet<R: tauri::Runtime>(
         messages: vec![],
     });
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    packet_api
+        .dispatcher
+        .device_updated(&packet_api.device_key, &packet_api.device);
 
     Ok(())
 }
@@ -31,8 +34,9 @@ pub fn handle_config_packet<R: tauri::Runtime>(
 ) -> Result<(), DeviceUpdateError> {
     packet_api.device.set_config(config);
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    packet_api
+        .dispatcher
+        .device_updated(&packet_api.device_key, &packet_api.device);
 
     Ok(())
 }
@@ -44,8 +48,22 @@ pub fn handle_module_config_packet<R: tauri::Runtime>(
 ) -> Result<(), DeviceUpdateError> {
     packet_api.device.set_module_config(module_config);
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    packet_api
+        .dispatcher
+        .device_updated(&packet_api.device_key, &packet_api.device);
+
+    Ok(())
+}
+
+pub fn handle_metadata_packet<R: tauri::Runtime>(
+    packet_api: &mut MeshPacketApi<R>,
+    metadata: protobufs::DeviceMetadata,
+) -> Result<(), DeviceUpdateError> {
+    packet_api.device.set_metadata(metadata);
+
+    packet_api
+        .dispatcher
+        .device_updated(&packet_api.device_key, &packet_api.device);
 
     Ok(())
 }
@@ -55,7 +73,15 @@ pub fn handle_config_complete_packet<R: tauri::Runtime>(
 ) -> Result<(), DeviceUpdateError> {
     packet_api.device.set_status(SerialDeviceStatus::Configured);
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
+    // Flush immediately so the UI has the full device before it's told
+    // configuration succeeded
+    packet_api
+        .dispatcher
+        .device_updated(&packet_api.device_key, &packet_api.device);
+
+    packet_api
+        .dispatcher
+        .flush(Some(&packet_api.device_key))
         .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
 
     if packet_api.device.status == SerialDeviceStatus::Configured {
@@ -77,6 +103,11 @@ pub fn handle_config_complete_packet<R: tauri::Runtime>(
         packet_api.device.set_status(SerialDeviceStatus::Connected);
     }
 
+    // Channels are known once configuration completes, so messages left
+    // unsent from a previous connection can be shown and sent again
+    packet_api.restore_outbox_messages()?;
+    packet_api.resume_file_transfers();
+
     Ok(())
 }
 
@@ -87,8 +118,9 @@ pub fn handle_my_node_info_packet<R: tauri::Runtime>(
 ) -> Result<(), DeviceUpdateError> {
     packet_api.device.set_my_node_info(my_node_info);
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    packet_api
+        .dispatcher
+        .device_updated(&packet_api.device_key, &packet_api.device);
 
     Ok(())
 }
@@ -103,13 +135,41 @@ pub fn handle_node_info_packet<R: tauri::Runtime>(
         .get_locked_graph()
         .map_err(|e| DeviceUpdateError::GeneralFailure(e.to_string()))?;
 
-    graph.update_from_node_info(node_info);
+    let node_num = node_info.num;
+    let graph_node = graph.update_from_node_info(node_info);
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    if let Some(node) = packet_api.device.nodes.get(&node_num) {
+        packet_api.dispatcher.enqueue(
+            Some(&packet_api.device_key),
+            DeltaPayload::NodeUpdated(node.clone_without_history()),
+        );
+    }
 
-    events::dispatch_updated_graph(&packet_api.app_handle, graph.clone())
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    if let Some(graph_node) = graph_node {
+        packet_api
+            .dispatcher
+            .enqueue(None, DeltaPayload::GraphNodeUpdated(graph_node));
+    }
+
+    Ok(())
+}
+
+pub fn handle_queue_status_packet<R: tauri::Runtime>(
+    packet_api: &mut MeshPacketApi<R>,
+    queue_status: protobufs::QueueStatus,
+) -> Result<(), DeviceUpdateError> {
+    match packet_api
+        .outbox
+        .handle_queue_status(queue_status, Instant::now())
+    {
+        Some(change) => packet_api.apply_outbound_state_change(change)?,
+        None => packet_api.dispatcher.enqueue(
+            Some(&packet_api.device_key),
+            DeltaPayload::OutboxUpdated(packet_api.outbox.status()),
+        ),
+    }
+
+    packet_api.handle_undelivered_transfer_packets();
 
     Ok(())
 }
@@ -1,18 +1,55 @@
+use std::time::Instant;
+
 use log::debug;
 use meshtastic::protobufs;
 use tauri::api::notification::Notification;
 
 use crate::{
     device::{
+        admin::decode_admin_message,
+        alerts::TelemetryAlert,
         helpers::{get_channel_name, get_node_user_name},
-        ChannelMessageState, NeighborInfoPacket, NormalizedWaypoint, PositionPacket,
-        TelemetryPacket, TextPacket, UserPacket, WaypointPacket,
+        ChannelMessageState, NeighborInfoPacket, NormalizedWaypoint, PaxcountPacket,
+        PositionPacket, TelemetryPacket, TextPacket, UserPacket, WaypointPacket,
+    },
+    ipc::{
+        events, DeltaPayload, EdgeUpdatedPayload, MessageAddedPayload, MessageStateChangedPayload,
+    },
+    packet_api::{
+        fragments::{FragmentFrame, FRAGMENT_PROTOCOL_ID},
+        handlers::DeviceUpdateError,
+        outbox::RoutingDisposition,
+        transfers::{TransferFrame, TRANSFER_PROTOCOL_ID},
+        unishox2, MeshPacketApi,
     },
-    ipc::events,
-    packet_api::{handlers::DeviceUpdateError, MeshPacketApi},
 };
 use meshtastic::Message;
 
+pub fn handle_admin_mesh_packet<R: tauri::Runtime>(
+    packet_api: &mut MeshPacketApi<R>,
+    packet: protobufs::MeshPacket,
+    data: protobufs::Data,
+) -> Result<(), DeviceUpdateError> {
+    // Admin messages for the connected radio are exchanged over `FromRadio`,
+    // so only responses from remote nodes are expected here
+    if packet.from == packet_api.device.my_node_info.my_node_num {
+        return Err(DeviceUpdateError::PacketNotSupported("local admin".into()));
+    }
+
+    let (message, session_passkey) = decode_admin_message(data.payload.as_slice())
+        .map_err(|e| DeviceUpdateError::DecodeFailure(e.to_string()))?;
+
+    packet_api
+        .device
+        .add_remote_admin_message(packet.from, message, session_passkey);
+
+    packet_api
+        .dispatcher
+        .device_updated(&packet_api.device_key, &packet_api.device);
+
+    Ok(())
+}
+
 pub fn handle_user_mesh_packet<R: tauri::Runtime>(
     packet_api: &mut MeshPacketApi<R>,
     packet: protobufs::MeshPacket,
@@ -21,11 +58,9 @@ pub fn handle_user_mesh_packet<R: tauri::Runtime>(
     let data = protobufs::User::decode(data.payload.as_slice())
         .map_err(|e| DeviceUpdateError::DecodeFailure(e.to_string()))?;
 
+    // The router dispatches the updated node once the packet is handled
     packet_api.device.add_user(UserPacket { packet, data });
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
-
     Ok(())
 }
 
@@ -46,17 +81,37 @@ pub fn handle_position_mesh_packet<R: tauri::Runtime>(
         .get_locked_graph()
         .map_err(|e| DeviceUpdateError::GeneralFailure(e.to_string()))?;
 
-    graph.update_from_position(packet, data);
+    let graph_node = graph.update_from_position(packet, data);
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
-
-    events::dispatch_updated_graph(&packet_api.app_handle, graph.clone())
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    packet_api
+        .dispatcher
+        .enqueue(None, DeltaPayload::GraphNodeUpdated(graph_node));
 
     Ok(())
 }
 
+pub fn handle_private_app_mesh_packet<R: tauri::Runtime>(
+    packet_api: &mut MeshPacketApi<R>,
+    packet: protobufs::MeshPacket,
+    data: protobufs::Data,
+) -> Result<(), DeviceUpdateError> {
+    match data.payload.first() {
+        Some(&FRAGMENT_PROTOCOL_ID) => {
+            let frame = FragmentFrame::decode(data.payload.as_slice())
+                .map_err(|e| DeviceUpdateError::DecodeFailure(e.to_string()))?;
+
+            packet_api.handle_fragment_frame(packet, frame)
+        }
+        Some(&TRANSFER_PROTOCOL_ID) => {
+            let frame = TransferFrame::decode(data.payload.as_slice())
+                .map_err(|e| DeviceUpdateError::DecodeFailure(e.to_string()))?;
+
+            packet_api.handle_transfer_frame(packet, frame)
+        }
+        _ => Err(DeviceUpdateError::PacketNotSupported("private app".into())),
+    }
+}
+
 pub fn handle_routing_mesh_packet<R: tauri::Runtime>(
     packet_api: &mut MeshPacketApi<R>,
     packet: protobufs::MeshPacket,
@@ -69,52 +124,76 @@ pub fn handle_routing_mesh_packet<R: tauri::Runtime>(
         match variant {
             protobufs::routing::Variant::ErrorReason(e) => {
                 if let Some(r) = protobufs::routing::Error::from_i32(e) {
-                    match r {
-                        protobufs::routing::Error::None => {
-                            packet_api.device.set_message_state(
-                                packet.channel,
-                                data.request_id,
-                                ChannelMessageState::Acknowledged,
-                            );
+                    // Messages sent through the outbox may be resent under a
+                    // new packet id rather than failed
+                    let disposition =
+                        packet_api
+                            .outbox
+                            .handle_routing_result(data.request_id, r, Instant::now());
+
+                    let message_id = match disposition {
+                        RoutingDisposition::Retrying(change) => {
+                            packet_api.metrics.record_nak(r.as_str_name());
+
+                            if let Some(change) = change {
+                                packet_api.apply_outbound_state_change(change)?;
+                            }
+
+                            return Ok(());
+                        }
+                        RoutingDisposition::Outstanding => {
+                            packet_api.metrics.record_ack();
+                            return Ok(());
                         }
+                        RoutingDisposition::Finished(message_id)
+                        | RoutingDisposition::Control(message_id) => message_id,
+                        RoutingDisposition::Untracked => data.request_id,
+                    };
+
+                    let state = match r {
+                        protobufs::routing::Error::None => ChannelMessageState::Acknowledged,
                         protobufs::routing::Error::Timeout => {
-                            packet_api.device.set_message_state(
-                                packet.channel,
-                                data.request_id,
-                                ChannelMessageState::Error("Message timed out".into()),
-                            );
+                            ChannelMessageState::Error("Message timed out".into())
                         }
                         protobufs::routing::Error::MaxRetransmit => {
-                            packet_api.device.set_message_state(
-                                packet.channel,
-                                data.request_id,
-                                ChannelMessageState::Error("Reached retransmit limit".into()),
-                            );
+                            ChannelMessageState::Error("Reached retransmit limit".into())
                         }
                         protobufs::routing::Error::GotNak => {
-                            packet_api.device.set_message_state(
-                                packet.channel,
-                                data.request_id,
-                                ChannelMessageState::Error("Received NAK".into()),
-                            );
+                            ChannelMessageState::Error("Received NAK".into())
                         }
                         protobufs::routing::Error::TooLarge => {
-                            packet_api.device.set_message_state(
-                                packet.channel,
-                                data.request_id,
-                                ChannelMessageState::Error("Message too large".into()),
-                            );
-                        }
-                        _ => {
-                            packet_api.device.set_message_state(
-                                packet.channel,
-                                data.request_id,
-                                ChannelMessageState::Error("Message failed to send".into()),
-                            );
+                            ChannelMessageState::Error("Message too large".into())
                         }
+                        _ => ChannelMessageState::Error("Message failed to send".into()),
+                    };
+
+                    match r {
+                        protobufs::routing::Error::None => packet_api.metrics.record_ack(),
+                        _ => packet_api.metrics.record_nak(r.as_str_name()),
                     }
 
-                    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
+                    // Control packets such as file chunks aren't shown as
+                    // messages, so their result only goes to their transfer
+                    if let RoutingDisposition::Control(_) = disposition {
+                        packet_api.handle_transfer_routing_result(message_id, r);
+                        return Ok(());
+                    }
+
+                    packet_api
+                        .device
+                        .set_message_state(packet.channel, message_id, state.clone());
+
+                    // Acks and failures are user-visible, so skip the flush interval
+                    packet_api
+                        .dispatcher
+                        .enqueue_immediate(
+                            Some(&packet_api.device_key),
+                            DeltaPayload::MessageStateChanged(MessageStateChangedPayload {
+                                channel: packet.channel,
+                                message_id,
+                                state,
+                            }),
+                        )
                         .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
                 }
             }
@@ -138,12 +217,98 @@ pub fn handle_telemetry_mesh_packet<R: tauri::Runtime>(
     let data = protobufs::Telemetry::decode(data.payload.as_slice())
         .map_err(|e| DeviceUpdateError::DecodeFailure(e.to_string()))?;
 
-    packet_api
+    // The router dispatches the updated node once the packet is handled
+    let alerts = packet_api
         .device
         .set_device_metrics(TelemetryPacket { packet, data });
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    dispatch_telemetry_alerts(packet_api, alerts)
+}
+
+pub fn handle_paxcounter_mesh_packet<R: tauri::Runtime>(
+    packet_api: &mut MeshPacketApi<R>,
+    packet: protobufs::MeshPacket,
+    data: protobufs::Data,
+) -> Result<(), DeviceUpdateError> {
+    let data = protobufs::Paxcount::decode(data.payload.as_slice())
+        .map_err(|e| DeviceUpdateError::DecodeFailure(e.to_string()))?;
+
+    // The router dispatches the updated node once the packet is handled
+    let alerts = packet_api
+        .device
+        .add_paxcount(PaxcountPacket { packet, data });
+
+    dispatch_telemetry_alerts(packet_api, alerts)
+}
+
+fn dispatch_telemetry_alerts<R: tauri::Runtime>(
+    packet_api: &mut MeshPacketApi<R>,
+    alerts: Vec<TelemetryAlert>,
+) -> Result<(), DeviceUpdateError> {
+    for alert in alerts {
+        let node_name = get_node_user_name(&mut packet_api.device, &alert.node_num)
+            .unwrap_or_else(|| alert.node_num.to_string());
+
+        events::dispatch_telemetry_alert(&packet_api.app_handle, &packet_api.device_key, &alert)
+            .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+
+        Notification::new(
+            packet_api
+                .app_handle
+                .config()
+                .tauri
+                .bundle
+                .identifier
+                .clone(),
+        )
+        .title(format!("Telemetry alert from {}", node_name))
+        .body(format!(
+            "{} is {} ({:?} {})",
+            alert.field, alert.value, alert.comparison, alert.threshold
+        ))
+        .notify(&packet_api.app_handle)
+        .map_err(|e| DeviceUpdateError::NotificationDispatchFailure(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+pub fn handle_detection_sensor_mesh_packet<R: tauri::Runtime>(
+    packet_api: &mut MeshPacketApi<R>,
+    packet: protobufs::MeshPacket,
+    data: protobufs::Data,
+) -> Result<(), DeviceUpdateError> {
+    let text = String::from_utf8(data.payload)
+        .map_err(|e| DeviceUpdateError::DecodeFailure(e.to_string()))?;
+
+    let event = packet_api.device.add_detection_event(&packet, text);
+
+    let from_user_name = get_node_user_name(&mut packet_api.device, &packet.from)
+        .unwrap_or_else(|| packet.from.to_string());
+
+    let location = match &event.position {
+        Some(position) => format!(" at {:.5}, {:.5}", position.latitude, position.longitude),
+        None => "".into(),
+    };
+
+    packet_api.dispatcher.enqueue(
+        Some(&packet_api.device_key),
+        DeltaPayload::DetectionEvent(event.clone()),
+    );
+
+    Notification::new(
+        packet_api
+            .app_handle
+            .config()
+            .tauri
+            .bundle
+            .identifier
+            .clone(),
+    )
+    .title(format!("Detection from {}", from_user_name))
+    .body(format!("{}{}", event.text, location))
+    .notify(&packet_api.app_handle)
+    .map_err(|e| DeviceUpdateError::NotificationDispatchFailure(e.to_string()))?;
 
     Ok(())
 }
@@ -168,8 +333,7 @@ pub fn handle_text_message_mesh_packet<R: tauri::Runtime>(
         .unwrap_or_else(|| "Unknown channel".into());
 
     // Always keep updates at bottom in case of failure during functions
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    dispatch_latest_channel_message(packet_api, packet.channel)?;
 
     if packet.from != packet_api.device.my_node_info.my_node_num {
         Notification::new(
@@ -190,6 +354,25 @@ pub fn handle_text_message_mesh_packet<R: tauri::Runtime>(
     Ok(())
 }
 
+pub fn handle_text_message_compressed_mesh_packet<R: tauri::Runtime>(
+    packet_api: &mut MeshPacketApi<R>,
+    packet: protobufs::MeshPacket,
+    data: protobufs::Data,
+) -> Result<(), DeviceUpdateError> {
+    let text = unishox2::decompress(data.payload.as_slice())
+        .map_err(|e| DeviceUpdateError::DecodeFailure(e.to_string()))?;
+
+    handle_text_message_mesh_packet(
+        packet_api,
+        packet,
+        protobufs::Data {
+            portnum: protobufs::PortNum::TextMessageApp as i32,
+            payload: text.into_bytes(),
+            ..data
+        },
+    )
+}
+
 pub fn handle_waypoint_mesh_packet<R: tauri::Runtime>(
     packet_api: &mut MeshPacketApi<R>,
     packet: protobufs::MeshPacket,
@@ -212,8 +395,12 @@ pub fn handle_waypoint_mesh_packet<R: tauri::Runtime>(
     let channel_name = get_channel_name(&mut packet_api.device, &packet.channel)
         .unwrap_or_else(|| "Unknown channel".into());
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    packet_api.dispatcher.enqueue(
+        Some(&packet_api.device_key),
+        DeltaPayload::WaypointAdded(converted_data.clone()),
+    );
+
+    dispatch_latest_channel_message(packet_api, packet.channel)?;
 
     if packet.from != packet_api.device.my_node_info.my_node_num {
         Notification::new(
@@ -254,13 +441,49 @@ pub fn handle_neighbor_info_mesh_packet<R: tauri::Runtime>(
         .get_locked_graph()
         .map_err(|e| DeviceUpdateError::GeneralFailure(e.to_string()))?;
 
-    graph.update_from_neighbor_info(packet, data);
+    let (graph_node, updated_edges) = graph.update_from_neighbor_info(packet, data);
+
+    packet_api
+        .dispatcher
+        .enqueue(None, DeltaPayload::GraphNodeUpdated(graph_node));
+
+    for (source, target, edge) in updated_edges {
+        packet_api.dispatcher.enqueue(
+            None,
+            DeltaPayload::EdgeUpdated(EdgeUpdatedPayload {
+                source,
+                target,
+                edge,
+            }),
+        );
+    }
 
-    events::dispatch_updated_device(&packet_api.app_handle, &packet_api.device)
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    Ok(())
+}
 
-    events::dispatch_updated_graph(&packet_api.app_handle, graph.clone())
-        .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+fn dispatch_latest_channel_message<R: tauri::Runtime>(
+    packet_api: &MeshPacketApi<R>,
+    channel: u32,
+) -> Result<(), DeviceUpdateError> {
+    let message = packet_api
+        .device
+        .channels
+        .get(&channel)
+        .and_then(|c| c.messages.last());
+
+    // Incoming messages skip the flush interval so they appear immediately
+    if let Some(message) = message {
+        packet_api
+            .dispatcher
+            .enqueue_immediate(
+                Some(&packet_api.device_key),
+                DeltaPayload::MessageAdded(MessageAddedPayload {
+                    channel,
+                    message: message.clone(),
+                }),
+            )
+            .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+    }
 
     Ok(())
 }
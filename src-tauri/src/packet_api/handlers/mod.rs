@@ -13,6 +13,19 @@ pub enum DeviceUpdateError {
     NotificationDispatchFailure(String),
 }
 
+impl DeviceUpdateError {
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            DeviceUpdateError::PacketNotSupported(_) => "packet_not_supported",
+            DeviceUpdateError::RadioMessageNotSupported(_) => "radio_message_not_supported",
+            DeviceUpdateError::DecodeFailure(_) => "decode_failure",
+            DeviceUpdateError::GeneralFailure(_) => "general_failure",
+            DeviceUpdateError::EventDispatchFailure(_) => "event_dispatch_failure",
+            DeviceUpdateError::NotificationDispatchFailure(_) => "notification_dispatch_failure",
+        }
+    }
+}
+
 impl fmt::Display for DeviceUpdateError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("Failed to update device: ")?;
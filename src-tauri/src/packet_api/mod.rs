@@ -2,16 +2,37 @@ use std::sync::{Arc, LockResult, Mutex};
 
 // use meshtastic::connections::stream_api::{state::Configured, StreamApi};
 
-use crate::{device::MeshDevice, graph::ds::graph::MeshGraph, state::DeviceKey};
+use crate::{
+    device::MeshDevice, graph::ds::graph::MeshGraph, ipc::dispatcher::EventDispatcher,
+    metrics::PacketMetrics, state::DeviceKey,
+};
 
+use self::{
+    device_logs::DeviceLogBuffer, fragments::FragmentState, outbox::Outbox, stats::PacketLog,
+    transfers::FileTransfers,
+};
+
+pub mod device_logs;
+pub mod fragments;
 pub mod handlers;
+pub mod outbox;
 pub mod router;
+pub mod stats;
+pub mod transfers;
+pub mod unishox2;
 
 pub struct MeshPacketApi<R: tauri::Runtime = tauri::Wry> {
     pub app_handle: tauri::AppHandle<R>,
     pub device_key: DeviceKey,
     pub device: MeshDevice,
     pub graph_arc: Arc<Mutex<MeshGraph>>,
+    pub dispatcher: EventDispatcher<R>,
+    pub metrics: PacketMetrics,
+    pub packet_log: PacketLog,
+    pub outbox: Outbox,
+    pub fragments: FragmentState,
+    pub transfers: FileTransfers,
+    pub device_logs: DeviceLogBuffer,
 }
 
 impl<R: tauri::Runtime> MeshPacketApi<R> {
@@ -20,12 +41,20 @@ impl<R: tauri::Runtime> MeshPacketApi<R> {
         device_key: DeviceKey,
         device: MeshDevice,
         graph_arc: Arc<Mutex<MeshGraph>>,
+        dispatcher: EventDispatcher<R>,
     ) -> Self {
         Self {
             app_handle,
             device_key,
             device,
             graph_arc,
+            dispatcher,
+            metrics: PacketMetrics::default(),
+            packet_log: PacketLog::default(),
+            outbox: Outbox::default(),
+            fragments: FragmentState::default(),
+            transfers: FileTransfers::default(),
+            device_logs: DeviceLogBuffer::default(),
         }
     }
 
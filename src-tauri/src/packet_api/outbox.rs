@@ -0,0 +1,1075 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{debug, trace, warn};
+use meshtastic::packet::PacketRouter;
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::device::helpers::{generate_rand_id, get_current_time_u32};
+use crate::device::telemetry::TelemetrySample;
+use crate::device::{ChannelMessageState, MeshDevice};
+use crate::ipc::{DeltaPayload, MessageStateChangedPayload};
+use crate::state::DeviceKey;
+
+use super::fragments::{split_payload, MAX_PAYLOAD_LEN};
+use super::handlers::DeviceUpdateError;
+use super::MeshPacketApi;
+
+/// Time to wait for the radio to report a packet as queued before retrying
+pub const QUEUE_STATUS_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Time after which the radio's reported free TX slots are no longer
+/// trusted. Firmware only reports its queue in response to a written packet,
+/// so a full queue would otherwise hold the outbox until something else is
+/// sent.
+pub const RADIO_FREE_STALE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time after which a sent packet the mesh never reported on is forgotten
+pub const ACK_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Share of the duty cycle limit after which only emergency messages are sent
+pub const DUTY_CYCLE_HEADROOM: f32 = 0.9;
+
+pub const BROADCAST_NODE_NUM: u32 = 0xffffffff;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OutboxError {
+    #[error("Failed to access outbox store: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to serialize outbox: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Invalid outbox policy: {0}")]
+    InvalidPolicy(String),
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum MessagePriority {
+    #[default]
+    Routine,
+    Tactical,
+    Emergency,
+}
+
+impl MessagePriority {
+    /// Priority used by the radio when ordering its own TX queue. Routine
+    /// messages are left unset so the firmware picks its default.
+    fn packet_priority(self) -> protobufs::mesh_packet::Priority {
+        match self {
+            MessagePriority::Routine => protobufs::mesh_packet::Priority::Unset,
+            MessagePriority::Tactical => protobufs::mesh_packet::Priority::Reliable,
+            MessagePriority::Emergency => protobufs::mesh_packet::Priority::Max,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxPolicy {
+    pub max_attempts: u32,
+    pub retry_spacing_secs: u32, // delay before a failed message is resent
+    pub min_send_interval_ms: u32, // minimum time between packets written to the radio
+    pub duty_cycle_limit: Option<f32>, // percent, overrides the limit of the configured region
+}
+
+impl Default for OutboxPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_spacing_secs: 30,
+            min_send_interval_ms: 500,
+            duty_cycle_limit: None,
+        }
+    }
+}
+
+impl OutboxPolicy {
+    fn validate(&self) -> Result<(), OutboxError> {
+        if self.max_attempts == 0 {
+            return Err(OutboxError::InvalidPolicy(
+                "At least one attempt is required".into(),
+            ));
+        }
+
+        if let Some(limit) = self.duty_cycle_limit {
+            if !(limit > 0.0 && limit <= 100.0) {
+                return Err(OutboxError::InvalidPolicy(
+                    "Duty cycle limit must be between 0 and 100 percent".into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Transmit airtime of the connected node compared to the limit it's held to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DutyCycleUsage {
+    pub air_util_tx: f32, // percent, over the firmware's rolling hour
+    pub limit: f32,       // percent
+}
+
+impl DutyCycleUsage {
+    fn is_limited(&self) -> bool {
+        self.air_util_tx >= self.limit * DUTY_CYCLE_HEADROOM
+    }
+}
+
+/// Duty cycle limits enforced by firmware, only EU regions are restricted
+fn region_duty_cycle_limit(region: protobufs::config::lo_ra_config::RegionCode) -> Option<f32> {
+    match region {
+        protobufs::config::lo_ra_config::RegionCode::Eu433
+        | protobufs::config::lo_ra_config::RegionCode::Eu868 => Some(10.0),
+        _ => None,
+    }
+}
+
+/// Reads the connected node's most recent airtime from its device metrics
+pub fn get_duty_cycle_usage(device: &MeshDevice, policy: &OutboxPolicy) -> Option<DutyCycleUsage> {
+    let limit = policy.duty_cycle_limit.or_else(|| {
+        device
+            .config
+            .lora
+            .as_ref()
+            .and_then(|lora| region_duty_cycle_limit(lora.region()))
+    })?;
+
+    let air_util_tx = device
+        .nodes
+        .get(&device.my_node_info.my_node_num)?
+        .device_metrics
+        .last()?
+        .numeric_fields()
+        .into_iter()
+        .find(|(field, _)| *field == "airUtilTx")?
+        .1;
+
+    Some(DutyCycleUsage {
+        air_util_tx: air_util_tx as f32,
+        limit,
+    })
+}
+
+/// Path of the file a device's outbox is persisted to
+pub fn get_outbox_store_path(app_data_dir: &Path, device_key: &DeviceKey) -> PathBuf {
+    let file_name: String = device_key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    app_data_dir
+        .join("outbox")
+        .join(format!("{}.json", file_name))
+}
+
+/// What an outbound packet carries
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum OutboundKind {
+    #[default]
+    Message, // a whole channel message
+    Fragment, // part of a fragmented channel message, not persisted
+    Control,  // not a channel message, such as a file transfer frame, not persisted
+}
+
+/// Serialized state to be written to disk once the device lock is released
+#[derive(Debug)]
+pub struct PendingWrite {
+    path: PathBuf,
+    contents: String,
+}
+
+impl PendingWrite {
+    pub fn new(path: PathBuf, contents: String) -> Self {
+        Self { path, contents }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes to a temporary file next to the destination and renames it
+    /// into place, so a crash mid-write can't leave a truncated file
+    pub fn write(&self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let temp_path = self.path.with_extension("tmp");
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(self.contents.as_bytes())?;
+        file.sync_all()?;
+
+        std::fs::rename(&temp_path, &self.path)
+    }
+}
+
+/// A message waiting to be sent to the radio, or waiting for the radio or
+/// the mesh to report on it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboundMessage {
+    pub message_id: u32, // id of the first packet sent, used to identify the message in the UI
+    pub priority: MessagePriority,
+    pub packet: protobufs::MeshPacket,
+    pub attempts: u32,
+    #[serde(skip)]
+    restored: bool, // loaded from disk, not yet shown in the device's messages
+    #[serde(skip)]
+    kind: OutboundKind,
+    #[serde(skip)]
+    not_before: Option<Instant>,
+    #[serde(skip)]
+    sent_at: Option<Instant>,
+}
+
+/// A change in the state of a channel message, to be applied to the device
+#[derive(Clone, Debug)]
+pub struct OutboundStateChange {
+    pub channel: u32,
+    pub message_id: u32,
+    pub state: ChannelMessageState,
+}
+
+impl OutboundStateChange {
+    fn new(message: &OutboundMessage, state: ChannelMessageState) -> Self {
+        Self {
+            channel: message.packet.channel,
+            message_id: message.message_id,
+            state,
+        }
+    }
+}
+
+/// What the routing handler should do with a routing result
+#[derive(Clone, Debug)]
+pub enum RoutingDisposition {
+    Untracked, // not sent through the outbox, apply the result as reported
+    Retrying(Option<OutboundStateChange>), // the message was queued to be resent
+    Finished(u32), // apply the result to the message with this id
+    Control(u32), // apply the result to whatever queued this control packet
+    Outstanding, // other packets of the message haven't been acknowledged
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxStatus {
+    pub queued: u32,             // held in the client
+    pub in_flight: u32,          // written to the radio, waiting for a queue status
+    pub awaiting_ack: u32,       // sent, waiting for the mesh to report on it
+    pub radio_free: Option<u32>, // free TX slots last reported by the radio
+    pub radio_capacity: Option<u32>,
+    pub duty_cycle_limited: bool, // only emergency messages are being sent
+    pub policy: OutboxPolicy,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedOutbox {
+    policy: OutboxPolicy,
+    messages: Vec<OutboundMessage>,
+}
+
+/// Client-side outbound queue. Messages are ordered by priority, handed to
+/// the radio only while it reports free TX slots and the duty cycle allows,
+/// and resent when the radio or the mesh reports a failure.
+#[derive(Debug, Default)]
+pub struct Outbox {
+    queued: VecDeque<OutboundMessage>,
+    in_flight: HashMap<u32, OutboundMessage>,
+    awaiting_ack: HashMap<u32, OutboundMessage>,
+    undelivered: Vec<u32>, // control packets that failed or were never acknowledged
+    radio_free: Option<u32>,
+    radio_free_reported_at: Option<Instant>,
+    radio_capacity: Option<u32>,
+    policy: OutboxPolicy,
+    last_sent: Option<Instant>,
+    duty_cycle_limited: bool,
+    store_path: Option<PathBuf>,
+    dirty: bool,
+    notify: Arc<Notify>,
+}
+
+impl Outbox {
+    /// Handle used to wake the task that sends queued packets
+    pub fn notifier(&self) -> Arc<Notify> {
+        self.notify.clone()
+    }
+
+    pub fn policy(&self) -> &OutboxPolicy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: OutboxPolicy) -> Result<(), OutboxError> {
+        policy.validate()?;
+
+        self.policy = policy;
+        self.dirty = true;
+        self.notify.notify_one();
+
+        Ok(())
+    }
+
+    pub fn status(&self) -> OutboxStatus {
+        OutboxStatus {
+            queued: self.queued.len() as u32,
+            in_flight: self.in_flight.len() as u32,
+            awaiting_ack: self.awaiting_ack.len() as u32,
+            radio_free: self.radio_free,
+            radio_capacity: self.radio_capacity,
+            duty_cycle_limited: self.duty_cycle_limited,
+            policy: self.policy.clone(),
+        }
+    }
+
+    pub fn enqueue(&mut self, packet: protobufs::MeshPacket, priority: MessagePriority) {
+        self.push(packet.id, packet, priority, OutboundKind::Message);
+    }
+
+    /// Queues the packets of a fragmented message, which is reported as sent
+    /// or acknowledged once all of its packets are. These packets aren't
+    /// persisted, since the message can't be rebuilt from them.
+    pub fn enqueue_fragments(
+        &mut self,
+        message_id: u32,
+        packets: Vec<protobufs::MeshPacket>,
+        priority: MessagePriority,
+    ) {
+        for packet in packets {
+            self.push(message_id, packet, priority, OutboundKind::Fragment);
+        }
+    }
+
+    /// Queues a packet that isn't a channel message and isn't persisted.
+    /// No state changes are reported for it, if it's never delivered it's
+    /// returned by `take_undelivered` instead.
+    pub fn enqueue_transient(&mut self, packet: protobufs::MeshPacket, priority: MessagePriority) {
+        self.push(packet.id, packet, priority, OutboundKind::Control);
+    }
+
+    /// Returns the ids of packets queued with `enqueue_transient` that ran
+    /// out of attempts or were never acknowledged
+    pub fn take_undelivered(&mut self) -> Vec<u32> {
+        std::mem::take(&mut self.undelivered)
+    }
+
+    fn push(
+        &mut self,
+        message_id: u32,
+        mut packet: protobufs::MeshPacket,
+        priority: MessagePriority,
+        kind: OutboundKind,
+    ) {
+        debug!("Queueing outbound packet {} as {:?}", packet.id, priority);
+
+        packet.set_priority(priority.packet_priority());
+
+        self.insert(
+            OutboundMessage {
+                message_id,
+                priority,
+                packet,
+                attempts: 0,
+                restored: false,
+                kind,
+                not_before: None,
+                sent_at: None,
+            },
+            false,
+        );
+    }
+
+    /// Inserts a message behind all messages of a higher or equal priority,
+    /// or ahead of its own priority when it's being retried
+    fn insert(&mut self, message: OutboundMessage, is_retry: bool) {
+        let position = self.queued.iter().position(|m| match is_retry {
+            true => m.priority <= message.priority,
+            false => m.priority < message.priority,
+        });
+
+        match position {
+            Some(index) => self.queued.insert(index, message),
+            None => self.queued.push_back(message),
+        }
+
+        self.dirty = true;
+        self.notify.notify_one();
+    }
+
+    /// Removes the packets of a message that haven't been written to the
+    /// radio yet
+    pub fn cancel(&mut self, message_id: u32) -> Option<OutboundStateChange> {
+        let message = self
+            .queued
+            .iter()
+            .find(|m| m.message_id == message_id)?
+            .clone();
+
+        self.queued.retain(|m| m.message_id != message_id);
+
+        debug!("Cancelled outbound message {}", message_id);
+        self.dirty = true;
+
+        self.state_change(&message, ChannelMessageState::Cancelled)
+    }
+
+    /// Builds the state change for a channel message. Packets that aren't
+    /// channel messages have no state, so failed ones are recorded as
+    /// undelivered instead.
+    fn state_change(
+        &mut self,
+        message: &OutboundMessage,
+        state: ChannelMessageState,
+    ) -> Option<OutboundStateChange> {
+        if message.kind != OutboundKind::Control {
+            return Some(OutboundStateChange::new(message, state));
+        }
+
+        if matches!(state, ChannelMessageState::Error(_)) {
+            self.undelivered.push(message.message_id);
+        }
+
+        None
+    }
+
+    /// Drops the remaining packets of a fragmented message that failed, so
+    /// it isn't reported as acknowledged once its other packets are. Routing
+    /// results for the dropped packets are then untracked.
+    fn drop_fragments(&mut self, message: &OutboundMessage) {
+        if message.kind != OutboundKind::Fragment {
+            return;
+        }
+
+        let message_id = message.message_id;
+        self.queued.retain(|m| m.message_id != message_id);
+        self.in_flight.retain(|_, m| m.message_id != message_id);
+        self.awaiting_ack.retain(|_, m| m.message_id != message_id);
+    }
+
+    /// Whether any packets of a message are still waiting to be sent or
+    /// acknowledged
+    fn has_outstanding(&self, message_id: u32) -> bool {
+        self.queued
+            .iter()
+            .chain(self.in_flight.values())
+            .chain(self.awaiting_ack.values())
+            .any(|m| m.message_id == message_id)
+    }
+
+    /// Removes the next packet to write to the radio, if the radio has room
+    /// for it and pacing allows. The packet is tracked until the radio
+    /// reports its status.
+    pub fn next_ready(
+        &mut self,
+        now: Instant,
+        duty_cycle: Option<DutyCycleUsage>,
+    ) -> Option<protobufs::MeshPacket> {
+        // Until the radio reports its queue, assume it has room. Once the
+        // report is stale, assume room for a single packet so the radio is
+        // prompted to report again.
+        let is_stale = self
+            .radio_free_reported_at
+            .is_some_and(|t| now.saturating_duration_since(t) > RADIO_FREE_STALE_TIMEOUT);
+
+        if let Some(free) = self.radio_free {
+            let free = if is_stale { free.max(1) } else { free };
+
+            if free <= self.in_flight.len() as u32 {
+                trace!("Radio TX queue full, holding outbound packets");
+                return None;
+            }
+        }
+
+        let min_interval = Duration::from_millis(self.policy.min_send_interval_ms as u64);
+
+        if self
+            .last_sent
+            .is_some_and(|t| now.saturating_duration_since(t) < min_interval)
+        {
+            return None;
+        }
+
+        self.duty_cycle_limited = duty_cycle.is_some_and(|usage| usage.is_limited());
+
+        let index = self.queued.iter().position(|m| {
+            !m.restored
+                && !m.not_before.is_some_and(|t| t > now)
+                && (!self.duty_cycle_limited || m.priority == MessagePriority::Emergency)
+        })?;
+
+        let mut message = self.queued.remove(index)?;
+        message.attempts += 1;
+        message.sent_at = Some(now);
+
+        let packet = message.packet.clone();
+        self.in_flight.insert(packet.id, message);
+        self.last_sent = Some(now);
+        self.dirty = true;
+
+        Some(packet)
+    }
+
+    /// Updates the radio's free slots, returning the state change for the
+    /// packet the status refers to
+    pub fn handle_queue_status(
+        &mut self,
+        status: protobufs::QueueStatus,
+        now: Instant,
+    ) -> Option<OutboundStateChange> {
+        trace!("Received queue status: {:?}", status);
+
+        self.radio_free = Some(status.free);
+        self.radio_free_reported_at = Some(now);
+        self.radio_capacity = Some(status.maxlen);
+        self.notify.notify_one();
+
+        let message = self.in_flight.remove(&status.mesh_packet_id)?;
+        self.dirty = true;
+
+        if status.res == 0 {
+            return self.await_ack(message, now);
+        }
+
+        warn!(
+            "Radio failed to queue packet {} with result {}",
+            message.packet.id, status.res
+        );
+
+        self.retry(message, now, "Radio failed to queue message")
+    }
+
+    /// Called once a packet has been written to the radio. Firmware that
+    /// hasn't reported its queue may never do so, so those packets are
+    /// considered sent as soon as they're written.
+    pub fn mark_written(&mut self, packet_id: u32, now: Instant) -> Option<OutboundStateChange> {
+        if self.radio_free.is_some() {
+            return None;
+        }
+
+        let message = self.in_flight.remove(&packet_id)?;
+        self.dirty = true;
+
+        self.await_ack(message, now)
+    }
+
+    /// Tracks a packet accepted by the radio, returning a state change once
+    /// every packet of its message has been sent
+    fn await_ack(
+        &mut self,
+        mut message: OutboundMessage,
+        now: Instant,
+    ) -> Option<OutboundStateChange> {
+        let change = self.state_change(&message, ChannelMessageState::Sent);
+        let message_id = message.message_id;
+
+        message.sent_at = Some(now);
+        self.awaiting_ack.insert(message.packet.id, message);
+
+        let is_unsent = |m: &OutboundMessage| m.message_id == message_id;
+
+        match self
+            .queued
+            .iter()
+            .chain(self.in_flight.values())
+            .any(is_unsent)
+        {
+            true => None,
+            false => change,
+        }
+    }
+
+    /// Resends messages that timed out or ran out of retransmissions on the
+    /// mesh, as long as they have attempts remaining
+    pub fn handle_routing_result(
+        &mut self,
+        packet_id: u32,
+        error: protobufs::routing::Error,
+        now: Instant,
+    ) -> RoutingDisposition {
+        let mut message = match self
+            .awaiting_ack
+            .remove(&packet_id)
+            .or_else(|| self.in_flight.remove(&packet_id))
+        {
+            Some(m) => m,
+            None => return RoutingDisposition::Untracked,
+        };
+        self.dirty = true;
+
+        if error == protobufs::routing::Error::None && self.has_outstanding(message.message_id) {
+            return RoutingDisposition::Outstanding;
+        }
+
+        let is_retryable = matches!(
+            error,
+            protobufs::routing::Error::Timeout | protobufs::routing::Error::MaxRetransmit
+        );
+
+        if !is_retryable || message.attempts >= self.policy.max_attempts {
+            if error != protobufs::routing::Error::None {
+                self.drop_fragments(&message);
+            }
+
+            return match message.kind {
+                OutboundKind::Control => RoutingDisposition::Control(message.message_id),
+                _ => RoutingDisposition::Finished(message.message_id),
+            };
+        }
+
+        debug!(
+            "Retrying message {} after {:?}, attempt {} of {}",
+            message.message_id,
+            error,
+            message.attempts + 1,
+            self.policy.max_attempts
+        );
+
+        // Nodes that heard the failed packet would drop a packet with the
+        // same id as a duplicate
+        message.packet.id = generate_rand_id();
+
+        RoutingDisposition::Retrying(self.retry(message, now, "Message failed to send"))
+    }
+
+    /// Retries packets the radio never reported on, returning state changes
+    /// for the retried packets. Sent messages the mesh never reported on are
+    /// forgotten, other sent packets are recorded as undelivered.
+    pub fn expire(&mut self, now: Instant) -> Vec<OutboundStateChange> {
+        let is_expired = |m: &OutboundMessage, timeout: Duration| {
+            m.sent_at
+                .is_some_and(|t| now.saturating_duration_since(t) > timeout)
+        };
+
+        let unacknowledged: Vec<u32> = self
+            .awaiting_ack
+            .iter()
+            .filter(|(_, m)| is_expired(m, ACK_TIMEOUT))
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in unacknowledged {
+            if let Some(message) = self.awaiting_ack.remove(&id) {
+                if message.kind == OutboundKind::Control {
+                    self.undelivered.push(message.message_id);
+                }
+            }
+        }
+
+        let expired: Vec<u32> = self
+            .in_flight
+            .iter()
+            .filter(|(_, m)| is_expired(m, QUEUE_STATUS_TIMEOUT))
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut changes = vec![];
+
+        for id in expired {
+            if let Some(message) = self.in_flight.remove(&id) {
+                changes.extend(self.retry(message, now, "Radio failed to queue message"));
+            }
+        }
+
+        changes
+    }
+
+    /// Requeues a message to be resent after the retry spacing, returning the
+    /// state change for the message. Messages out of attempts are failed.
+    fn retry(
+        &mut self,
+        mut message: OutboundMessage,
+        now: Instant,
+        reason: &str,
+    ) -> Option<OutboundStateChange> {
+        self.dirty = true;
+
+        if message.attempts >= self.policy.max_attempts {
+            self.drop_fragments(&message);
+            return self.state_change(&message, ChannelMessageState::Error(reason.into()));
+        }
+
+        message.not_before = Some(now + Duration::from_secs(self.policy.retry_spacing_secs as u64));
+        message.sent_at = None;
+
+        let change = self.state_change(&message, ChannelMessageState::Queued);
+        self.insert(message, true);
+
+        change
+    }
+
+    /// Loads messages left unsent when the device was last connected, and
+    /// persists the outbox to the same path from then on
+    pub fn load(&mut self, path: PathBuf) -> Result<(), OutboxError> {
+        if path.exists() {
+            let persisted: PersistedOutbox =
+                serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+
+            debug!(
+                "Restoring {} outbound messages from {:?}",
+                persisted.messages.len(),
+                path
+            );
+
+            self.policy = persisted.policy;
+
+            for mut message in persisted.messages {
+                message.restored = true;
+                self.queued.push_back(message);
+            }
+        }
+
+        self.store_path = Some(path);
+
+        Ok(())
+    }
+
+    /// Serializes unsent messages if the outbox changed since it was last
+    /// persisted, to be written once the device lock is released
+    pub fn persist(&mut self) -> Result<Option<PendingWrite>, OutboxError> {
+        let path = match self.store_path.as_ref() {
+            Some(p) if self.dirty => p.clone(),
+            _ => return Ok(None),
+        };
+
+        let persisted = PersistedOutbox {
+            policy: self.policy.clone(),
+            messages: self
+                .queued
+                .iter()
+                .chain(self.in_flight.values())
+                .filter(|m| m.kind == OutboundKind::Message)
+                .cloned()
+                .collect(),
+        };
+
+        let contents = serde_json::to_string(&persisted)?;
+        self.dirty = false;
+
+        Ok(Some(PendingWrite { path, contents }))
+    }
+
+    /// Returns packets loaded from disk that haven't been added to the
+    /// device's messages, and releases them to be sent
+    fn take_restored(&mut self) -> Vec<protobufs::MeshPacket> {
+        self.queued
+            .iter_mut()
+            .filter(|m| m.restored)
+            .map(|m| {
+                m.restored = false;
+                m.message_id = m.packet.id;
+                m.packet.clone()
+            })
+            .collect()
+    }
+}
+
+impl<R: tauri::Runtime> MeshPacketApi<R> {
+    /// Builds a broadcast packet from the connected node, adds it to the
+    /// device's messages and holds it in the outbox until the radio has room.
+    pub fn queue_channel_message(
+        &mut self,
+        channel: u32,
+        portnum: protobufs::PortNum,
+        payload: Vec<u8>,
+        priority: MessagePriority,
+    ) -> Result<u32, DeviceUpdateError> {
+        let message_id = generate_rand_id();
+
+        // Split payloads too large for a single packet before the message is
+        // shown, so a payload too large to fragment is rejected outright
+        let chunks = match payload.len() > MAX_PAYLOAD_LEN {
+            true => Some(
+                split_payload(message_id, portnum, &payload)
+                    .map_err(|e| DeviceUpdateError::GeneralFailure(e.to_string()))?,
+            ),
+            false => None,
+        };
+
+        let packet = protobufs::MeshPacket {
+            id: message_id,
+            from: self.device.my_node_info.my_node_num,
+            to: BROADCAST_NODE_NUM,
+            channel,
+            rx_time: get_current_time_u32(),
+            want_ack: true,
+            payload_variant: Some(protobufs::mesh_packet::PayloadVariant::Decoded(
+                protobufs::Data {
+                    portnum: portnum as i32,
+                    payload,
+                    ..Default::default()
+                },
+            )),
+            ..Default::default()
+        };
+
+        // Echo the packet through the router so it's shown alongside
+        // received messages
+        self.handle_mesh_packet(packet.clone())?;
+
+        match chunks {
+            Some(chunks) => self.queue_fragmented_message(&packet, chunks, priority),
+            None => self.outbox.enqueue(packet, priority),
+        }
+
+        self.apply_outbound_state_change(OutboundStateChange {
+            channel,
+            message_id,
+            state: ChannelMessageState::Queued,
+        })?;
+
+        Ok(message_id)
+    }
+
+    /// Adds messages restored from disk to the device's messages once its
+    /// channels are known, and releases them to be sent
+    pub fn restore_outbox_messages(&mut self) -> Result<(), DeviceUpdateError> {
+        for packet in self.outbox.take_restored() {
+            let channel = packet.channel;
+            let message_id = packet.id;
+
+            self.handle_mesh_packet(packet)?;
+
+            self.apply_outbound_state_change(OutboundStateChange {
+                channel,
+                message_id,
+                state: ChannelMessageState::Queued,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn apply_outbound_state_change(
+        &mut self,
+        change: OutboundStateChange,
+    ) -> Result<(), DeviceUpdateError> {
+        self.device
+            .set_message_state(change.channel, change.message_id, change.state.clone());
+
+        self.dispatcher.enqueue(
+            Some(&self.device_key),
+            DeltaPayload::OutboxUpdated(self.outbox.status()),
+        );
+
+        self.dispatcher
+            .enqueue_immediate(
+                Some(&self.device_key),
+                DeltaPayload::MessageStateChanged(MessageStateChangedPayload {
+                    channel: change.channel,
+                    message_id: change.message_id,
+                    state: change.state,
+                }),
+            )
+            .map_err(|e| DeviceUpdateError::EventDispatchFailure(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(id: u32) -> protobufs::MeshPacket {
+        protobufs::MeshPacket {
+            id,
+            ..Default::default()
+        }
+    }
+
+    fn queue_status(mesh_packet_id: u32, res: i32, free: u32) -> protobufs::QueueStatus {
+        protobufs::QueueStatus {
+            res,
+            free,
+            maxlen: 16,
+            mesh_packet_id,
+        }
+    }
+
+    fn unpaced_outbox() -> Outbox {
+        let mut outbox = Outbox::default();
+        outbox
+            .set_policy(OutboxPolicy {
+                retry_spacing_secs: 0,
+                min_send_interval_ms: 0,
+                ..Default::default()
+            })
+            .unwrap();
+
+        outbox
+    }
+
+    #[test]
+    fn packets_held_while_radio_queue_full() {
+        let mut outbox = unpaced_outbox();
+        let now = Instant::now();
+        outbox.handle_queue_status(queue_status(0, 0, 1), now);
+
+        outbox.enqueue(packet(1), MessagePriority::Routine);
+        outbox.enqueue(packet(2), MessagePriority::Routine);
+
+        assert_eq!(outbox.next_ready(now, None).map(|p| p.id), Some(1));
+        assert!(outbox.next_ready(now, None).is_none());
+
+        let change = outbox
+            .handle_queue_status(queue_status(1, 0, 1), now)
+            .unwrap();
+
+        assert!(matches!(change.state, ChannelMessageState::Sent));
+        assert_eq!(outbox.next_ready(now, None).map(|p| p.id), Some(2));
+    }
+
+    #[test]
+    fn stale_full_radio_queue_does_not_hold_outbox() {
+        let mut outbox = unpaced_outbox();
+        let now = Instant::now();
+        outbox.handle_queue_status(queue_status(0, 0, 0), now);
+
+        outbox.enqueue(packet(1), MessagePriority::Routine);
+        outbox.enqueue(packet(2), MessagePriority::Routine);
+
+        assert!(outbox.next_ready(now, None).is_none());
+
+        let later = now + RADIO_FREE_STALE_TIMEOUT + Duration::from_secs(1);
+        assert_eq!(outbox.next_ready(later, None).map(|p| p.id), Some(1));
+        assert!(outbox.next_ready(later, None).is_none());
+    }
+
+    #[test]
+    fn failed_packets_retried_then_marked_failed() {
+        let mut outbox = unpaced_outbox();
+        let now = Instant::now();
+        outbox.enqueue(packet(1), MessagePriority::Routine);
+
+        for _ in 0..outbox.policy().max_attempts - 1 {
+            assert_eq!(outbox.next_ready(now, None).map(|p| p.id), Some(1));
+            let change = outbox.handle_queue_status(queue_status(1, 1, 4), now);
+            assert!(matches!(change.unwrap().state, ChannelMessageState::Queued));
+        }
+
+        outbox.next_ready(now, None);
+        let change = outbox
+            .handle_queue_status(queue_status(1, 1, 4), now)
+            .unwrap();
+
+        assert!(matches!(change.state, ChannelMessageState::Error(_)));
+        assert_eq!(outbox.status().queued, 0);
+    }
+
+    #[test]
+    fn higher_priority_sent_first_and_alone_when_duty_cycle_limited() {
+        let mut outbox = unpaced_outbox();
+        let now = Instant::now();
+        let usage = DutyCycleUsage {
+            air_util_tx: 9.5,
+            limit: 10.0,
+        };
+
+        outbox.enqueue(packet(1), MessagePriority::Routine);
+        outbox.enqueue(packet(2), MessagePriority::Tactical);
+        outbox.enqueue(packet(3), MessagePriority::Emergency);
+
+        assert_eq!(outbox.next_ready(now, Some(usage)).map(|p| p.id), Some(3));
+        assert!(outbox.next_ready(now, Some(usage)).is_none());
+        assert!(outbox.status().duty_cycle_limited);
+        assert_eq!(outbox.next_ready(now, None).map(|p| p.id), Some(2));
+    }
+
+    #[test]
+    fn routing_timeout_resends_with_new_packet_id() {
+        let mut outbox = unpaced_outbox();
+        let now = Instant::now();
+        outbox.enqueue(packet(1), MessagePriority::Routine);
+
+        outbox.next_ready(now, None);
+        outbox.mark_written(1, now);
+
+        let disposition = outbox.handle_routing_result(1, protobufs::routing::Error::Timeout, now);
+        assert!(matches!(disposition, RoutingDisposition::Retrying(Some(c)) if c.message_id == 1));
+
+        let resent = outbox.next_ready(now, None).unwrap();
+        assert_ne!(resent.id, 1);
+
+        outbox.mark_written(resent.id, now);
+        let disposition =
+            outbox.handle_routing_result(resent.id, protobufs::routing::Error::None, now);
+        assert!(matches!(disposition, RoutingDisposition::Finished(1)));
+    }
+
+    #[test]
+    fn failed_fragment_fails_whole_message() {
+        let mut outbox = unpaced_outbox();
+        let now = Instant::now();
+        outbox.enqueue_fragments(
+            10,
+            vec![packet(1), packet(2), packet(3)],
+            MessagePriority::Routine,
+        );
+
+        for id in 1..=3 {
+            assert_eq!(outbox.next_ready(now, None).map(|p| p.id), Some(id));
+            outbox.mark_written(id, now);
+        }
+
+        let disposition = outbox.handle_routing_result(2, protobufs::routing::Error::NoRoute, now);
+        assert!(matches!(disposition, RoutingDisposition::Finished(10)));
+
+        // Acks for the other fragments must not mark the message acknowledged
+        for id in [1, 3] {
+            let disposition =
+                outbox.handle_routing_result(id, protobufs::routing::Error::None, now);
+            assert!(matches!(disposition, RoutingDisposition::Untracked));
+        }
+
+        let status = outbox.status();
+        assert_eq!(
+            (status.queued, status.in_flight, status.awaiting_ack),
+            (0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn unacknowledged_control_packets_are_undelivered() {
+        let mut outbox = unpaced_outbox();
+        let now = Instant::now();
+        outbox.enqueue_transient(packet(1), MessagePriority::Routine);
+
+        outbox.next_ready(now, None);
+        assert!(outbox.mark_written(1, now).is_none());
+
+        assert!(outbox.expire(now + ACK_TIMEOUT / 2).is_empty());
+        assert!(outbox.take_undelivered().is_empty());
+
+        assert!(outbox.expire(now + ACK_TIMEOUT * 2).is_empty());
+        assert_eq!(outbox.take_undelivered(), vec![1]);
+        assert_eq!(outbox.status().awaiting_ack, 0);
+    }
+
+    #[test]
+    fn persisted_messages_are_restored() {
+        let path = std::env::temp_dir().join(format!("outbox-{}.json", generate_rand_id::<u32>()));
+
+        let mut outbox = Outbox::default();
+        outbox.load(path.clone()).unwrap();
+        outbox.enqueue(packet(1), MessagePriority::Routine);
+        outbox.enqueue_transient(packet(2), MessagePriority::Routine);
+
+        outbox.persist().unwrap().unwrap().write().unwrap();
+        assert!(outbox.persist().unwrap().is_none());
+
+        let mut restored = Outbox::default();
+        restored.load(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let ids: Vec<u32> = restored.take_restored().iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+}
@@ -3,7 +3,7 @@ use meshtastic::packet::PacketRouter;
 use meshtastic::protobufs;
 use meshtastic::types::NodeId;
 
-use crate::ipc::events;
+use crate::ipc::{events, DeltaPayload};
 
 use super::handlers::{
     from_radio::handlers as from_radio_handlers, mesh_packet::handlers as mesh_packet_handlers,
@@ -39,15 +39,11 @@ impl<R: tauri::Runtime> PacketRouter<(), DeviceUpdateError> for MeshPacketApi<R>
             protobufs::from_radio::PayloadVariant::ConfigCompleteId(_) => {
                 from_radio_handlers::handle_config_complete_packet(self)?;
             }
-            protobufs::from_radio::PayloadVariant::LogRecord(_) => {
-                return Err(DeviceUpdateError::RadioMessageNotSupported(
-                    "log record".into(),
-                ));
+            protobufs::from_radio::PayloadVariant::LogRecord(record) => {
+                self.handle_log_record(record)?;
             }
-            protobufs::from_radio::PayloadVariant::Metadata(_m) => {
-                return Err(DeviceUpdateError::RadioMessageNotSupported(
-                    "metadata".into(),
-                ));
+            protobufs::from_radio::PayloadVariant::Metadata(metadata) => {
+                from_radio_handlers::handle_metadata_packet(self, metadata)?;
             }
             protobufs::from_radio::PayloadVariant::ModuleConfig(module_config) => {
                 from_radio_handlers::handle_module_config_packet(self, module_config)?;
@@ -61,10 +57,8 @@ impl<R: tauri::Runtime> PacketRouter<(), DeviceUpdateError> for MeshPacketApi<R>
             protobufs::from_radio::PayloadVariant::Packet(mesh_packet) => {
                 self.handle_mesh_packet(mesh_packet)?;
             }
-            protobufs::from_radio::PayloadVariant::QueueStatus(_) => {
-                return Err(DeviceUpdateError::RadioMessageNotSupported(
-                    "queue status".into(),
-                ));
+            protobufs::from_radio::PayloadVariant::QueueStatus(queue_status) => {
+                from_radio_handlers::handle_queue_status_packet(self, queue_status)?;
             }
             protobufs::from_radio::PayloadVariant::Rebooted(_) => {
                 debug!("Device rebooting");
@@ -94,10 +88,36 @@ impl<R: tauri::Runtime> PacketRouter<(), DeviceUpdateError> for MeshPacketApi<R>
             .ok_or("No payload variant")
             .map_err(|e| DeviceUpdateError::GeneralFailure(e.to_string()))?;
 
+        let from = packet.from;
+
+        // Record the sender before dispatching, so nodes are tracked even
+        // when their packets are unsupported or can't be decrypted
+        self.device.upsert_node_from_packet(&packet);
+
+        let result = self.route_mesh_packet(packet.clone(), variant);
+        self.packet_log.record(&packet, &result);
+
+        if let Some(node) = self.device.nodes.get(&from) {
+            self.dispatcher.enqueue(
+                Some(&self.device_key),
+                DeltaPayload::NodeUpdated(node.clone_without_history()),
+            );
+        }
+
+        result
+    }
+}
+
+impl<R: tauri::Runtime> MeshPacketApi<R> {
+    fn route_mesh_packet(
+        &mut self,
+        packet: protobufs::MeshPacket,
+        variant: protobufs::mesh_packet::PayloadVariant,
+    ) -> Result<(), DeviceUpdateError> {
         match variant {
             protobufs::mesh_packet::PayloadVariant::Decoded(data) => match data.portnum() {
                 protobufs::PortNum::AdminApp => {
-                    return Err(DeviceUpdateError::PacketNotSupported("admin".into()));
+                    mesh_packet_handlers::handle_admin_mesh_packet(self, packet, data)?;
                 }
                 protobufs::PortNum::AtakForwarder => {
                     return Err(DeviceUpdateError::PacketNotSupported(
@@ -117,7 +137,7 @@ impl<R: tauri::Runtime> PacketRouter<(), DeviceUpdateError> for MeshPacketApi<R>
                     mesh_packet_handlers::handle_position_mesh_packet(self, packet, data)?;
                 }
                 protobufs::PortNum::PrivateApp => {
-                    return Err(DeviceUpdateError::PacketNotSupported("admin".into()));
+                    mesh_packet_handlers::handle_private_app_mesh_packet(self, packet, data)?;
                 }
                 protobufs::PortNum::RangeTestApp => {
                     return Err(DeviceUpdateError::PacketNotSupported("range test".into()));
@@ -151,9 +171,9 @@ impl<R: tauri::Runtime> PacketRouter<(), DeviceUpdateError> for MeshPacketApi<R>
                     mesh_packet_handlers::handle_text_message_mesh_packet(self, packet, data)?;
                 }
                 protobufs::PortNum::TextMessageCompressedApp => {
-                    return Err(DeviceUpdateError::PacketNotSupported(
-                        "compressed text".into(),
-                    ));
+                    mesh_packet_handlers::handle_text_message_compressed_mesh_packet(
+                        self, packet, data,
+                    )?;
                 }
                 protobufs::PortNum::WaypointApp => {
                     mesh_packet_handlers::handle_waypoint_mesh_packet(self, packet, data)?;
@@ -168,9 +188,7 @@ impl<R: tauri::Runtime> PacketRouter<(), DeviceUpdateError> for MeshPacketApi<R>
                     return Err(DeviceUpdateError::PacketNotSupported("traceroute".into()));
                 }
                 protobufs::PortNum::DetectionSensorApp => {
-                    return Err(DeviceUpdateError::PacketNotSupported(
-                        "detection sensor".into(),
-                    ));
+                    mesh_packet_handlers::handle_detection_sensor_mesh_packet(self, packet, data)?;
                 }
                 protobufs::PortNum::UnknownApp => {
                     return Err(DeviceUpdateError::GeneralFailure(
@@ -183,7 +201,7 @@ impl<R: tauri::Runtime> PacketRouter<(), DeviceUpdateError> for MeshPacketApi<R>
                     ));
                 }
                 protobufs::PortNum::PaxcounterApp => {
-                    return Err(DeviceUpdateError::PacketNotSupported("paxcounter".into()));
+                    mesh_packet_handlers::handle_paxcounter_mesh_packet(self, packet, data)?;
                 }
                 protobufs::PortNum::AtakPlugin => {
                     return Err(DeviceUpdateError::PacketNotSupported("atakplugin".into()));
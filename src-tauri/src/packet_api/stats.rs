@@ -0,0 +1,214 @@
+use std::collections::{HashMap, VecDeque};
+
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::device::helpers::{get_current_time_u32, get_hops_away};
+
+use super::handlers::DeviceUpdateError;
+
+/// Number of raw packets kept for inspection before the oldest are dropped
+pub const RAW_PACKET_LOG_CAPACITY: usize = 500;
+
+pub const ENCRYPTED_PORTNUM: &str = "ENCRYPTED";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum PacketOutcome {
+    Handled,
+    Unsupported,
+    DecodeFailure,
+    Failed, // decoded, but the handler failed for another reason
+}
+
+impl From<&Result<(), DeviceUpdateError>> for PacketOutcome {
+    fn from(result: &Result<(), DeviceUpdateError>) -> Self {
+        match result {
+            Ok(()) => PacketOutcome::Handled,
+            Err(DeviceUpdateError::PacketNotSupported(_)) => PacketOutcome::Unsupported,
+            Err(DeviceUpdateError::DecodeFailure(_)) => PacketOutcome::DecodeFailure,
+            Err(_) => PacketOutcome::Failed,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketCounts {
+    pub total: u32,
+    pub handled: u32,
+    pub unsupported: u32,
+    pub decode_failure: u32,
+    pub failed: u32,
+}
+
+impl PacketCounts {
+    fn record(&mut self, outcome: PacketOutcome) {
+        self.total = self.total.saturating_add(1);
+
+        let count = match outcome {
+            PacketOutcome::Handled => &mut self.handled,
+            PacketOutcome::Unsupported => &mut self.unsupported,
+            PacketOutcome::DecodeFailure => &mut self.decode_failure,
+            PacketOutcome::Failed => &mut self.failed,
+        };
+
+        *count = count.saturating_add(1);
+    }
+}
+
+/// Counters for every mesh packet received by a device since it connected
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketStats {
+    pub since: u32, // secs
+    pub totals: PacketCounts,
+    pub by_portnum: HashMap<String, PacketCounts>,
+    pub by_sender: HashMap<u32, PacketCounts>,
+    pub by_hops_away: HashMap<u32, PacketCounts>,
+    pub unknown_hops_away: PacketCounts, // senders whose firmware doesn't report `hop_start`
+}
+
+impl Default for PacketStats {
+    fn default() -> Self {
+        Self {
+            since: get_current_time_u32(),
+            totals: PacketCounts::default(),
+            by_portnum: HashMap::new(),
+            by_sender: HashMap::new(),
+            by_hops_away: HashMap::new(),
+            unknown_hops_away: PacketCounts::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RawPacketLogEntry {
+    pub received_at: u32, // secs
+    pub portnum: String,
+    pub hops_away: Option<u32>,
+    pub outcome: PacketOutcome,
+    pub error: Option<String>,
+    pub packet: protobufs::MeshPacket,
+}
+
+/// Packet counters along with a rolling log of the most recent raw packets
+#[derive(Clone, Debug, Default)]
+pub struct PacketLog {
+    pub stats: PacketStats,
+    raw_packets: VecDeque<RawPacketLogEntry>,
+}
+
+impl PacketLog {
+    pub fn record(
+        &mut self,
+        packet: &protobufs::MeshPacket,
+        result: &Result<(), DeviceUpdateError>,
+    ) {
+        let outcome = PacketOutcome::from(result);
+        let hops_away = get_hops_away(packet);
+
+        let portnum = match &packet.payload_variant {
+            Some(protobufs::mesh_packet::PayloadVariant::Decoded(data)) => {
+                data.portnum().as_str_name().to_string()
+            }
+            _ => ENCRYPTED_PORTNUM.to_string(),
+        };
+
+        self.stats.totals.record(outcome);
+        self.stats
+            .by_portnum
+            .entry(portnum.clone())
+            .or_default()
+            .record(outcome);
+        self.stats
+            .by_sender
+            .entry(packet.from)
+            .or_default()
+            .record(outcome);
+
+        match hops_away {
+            Some(hops) => self
+                .stats
+                .by_hops_away
+                .entry(hops)
+                .or_default()
+                .record(outcome),
+            None => self.stats.unknown_hops_away.record(outcome),
+        }
+
+        if self.raw_packets.len() >= RAW_PACKET_LOG_CAPACITY {
+            self.raw_packets.pop_front();
+        }
+
+        self.raw_packets.push_back(RawPacketLogEntry {
+            received_at: get_current_time_u32(),
+            portnum,
+            hops_away,
+            outcome,
+            error: result.as_ref().err().map(|e| e.to_string()),
+            packet: packet.clone(),
+        });
+    }
+
+    /// Returns up to `limit` of the most recently received packets, newest last
+    pub fn recent_packets(&self, limit: usize) -> Vec<RawPacketLogEntry> {
+        self.raw_packets
+            .iter()
+            .skip(self.raw_packets.len().saturating_sub(limit))
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_packet(from: u32) -> protobufs::MeshPacket {
+        protobufs::MeshPacket {
+            from,
+            payload_variant: Some(protobufs::mesh_packet::PayloadVariant::Decoded(
+                protobufs::Data {
+                    portnum: protobufs::PortNum::TextMessageApp as i32,
+                    ..Default::default()
+                },
+            )),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn packets_counted_by_outcome() {
+        let mut log = PacketLog::default();
+
+        log.record(&text_packet(1), &Ok(()));
+        log.record(
+            &text_packet(1),
+            &Err(DeviceUpdateError::DecodeFailure("bad utf-8".into())),
+        );
+        log.record(
+            &protobufs::MeshPacket {
+                from: 2,
+                ..Default::default()
+            },
+            &Err(DeviceUpdateError::PacketNotSupported("encrypted".into())),
+        );
+
+        let text = log.stats.by_portnum.get("TEXT_MESSAGE_APP").unwrap();
+        let encrypted = log.stats.by_portnum.get(ENCRYPTED_PORTNUM).unwrap();
+
+        assert_eq!(log.stats.totals.total, 3);
+        assert_eq!((text.handled, text.decode_failure), (1, 1));
+        assert_eq!(encrypted.unsupported, 1);
+        assert_eq!(log.stats.by_sender.get(&1).unwrap().total, 2);
+        assert_eq!(log.stats.unknown_hops_away.total, 3);
+        assert_eq!(log.recent_packets(2).len(), 2);
+    }
+}
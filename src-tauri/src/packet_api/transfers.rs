@@ -0,0 +1,1184 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use log::{debug, trace, warn};
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::device::helpers::generate_rand_id;
+use crate::ipc::DeltaPayload;
+use crate::state::DeviceKey;
+
+use super::fragments::MAX_PAYLOAD_LEN;
+use super::handlers::DeviceUpdateError;
+use super::outbox::{MessagePriority, PendingWrite};
+use super::MeshPacketApi;
+
+/// First byte of file transfer frames sent on the private app portnum
+pub const TRANSFER_PROTOCOL_ID: u8 = 0x02;
+
+/// Largest file that can be offered, transfers are slow enough that larger
+/// files aren't practical
+pub const MAX_TRANSFER_SIZE: usize = 64 * 1024;
+
+/// Longest file name sent in an offer, in bytes
+pub const MAX_FILE_NAME_LEN: usize = 64;
+
+// protocol, kind, transfer id, index
+const CHUNK_HEADER_LEN: usize = 1 + 1 + 4 + 2;
+
+/// Bytes of the file carried by each chunk
+pub const CHUNK_DATA_LEN: usize = MAX_PAYLOAD_LEN - CHUNK_HEADER_LEN;
+
+/// Chunks of a single transfer waiting on an ack at once
+pub const CHUNK_WINDOW: usize = 4;
+
+/// Failed chunks tolerated before an outgoing transfer is abandoned
+pub const MAX_CHUNK_FAILURES: u32 = 10;
+
+/// Unfinished incoming transfers kept per sender, further offers are refused
+pub const MAX_INCOMING_TRANSFERS_PER_PEER: usize = 2;
+
+/// Unfinished incoming transfers kept across all senders
+pub const MAX_INCOMING_TRANSFERS: usize = 8;
+
+/// Time a finished transfer stays listed before it's forgotten. Finished
+/// transfers aren't persisted.
+pub const FINISHED_TRANSFER_RETENTION: Duration = Duration::from_secs(3600);
+
+const OFFER_KIND: u8 = 0x00;
+const ACCEPT_KIND: u8 = 0x01;
+const CHUNK_KIND: u8 = 0x02;
+const CANCEL_KIND: u8 = 0x03;
+const COMPLETE_KIND: u8 = 0x04;
+
+const COMPRESSED_FLAG: u8 = 0x01;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TransferError {
+    #[error("Transfer frame truncated")]
+    Truncated,
+    #[error("Unknown transfer frame kind {0}")]
+    UnknownKind(u8),
+    #[error("File of {0} bytes is too large to transfer")]
+    TooLarge(usize),
+    #[error("File is empty")]
+    Empty,
+    #[error("Transfer {0} not found")]
+    NotFound(u32),
+    #[error("Transfer {0} can't be {1} in its current state")]
+    InvalidState(u32, &'static str),
+    #[error("Failed to compress or decompress file: {0}")]
+    Compression(std::io::Error),
+    #[error("Failed to access transfer store: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to serialize transfers: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferOffer {
+    pub transfer_id: u32,
+    pub size: u32,          // bytes sent over the mesh, after compression
+    pub original_size: u32, // bytes of the file itself
+    pub chunk_count: u16,
+    pub compressed: bool,
+    pub sha256: [u8; 32], // of the uncompressed file
+    pub file_name: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransferFrame {
+    Offer(TransferOffer),
+    Accept {
+        transfer_id: u32,
+        received: Vec<u8>, // bitmap of chunks the receiver already has, to resume
+    },
+    Chunk {
+        transfer_id: u32,
+        index: u16,
+        data: Vec<u8>,
+    },
+    Cancel {
+        transfer_id: u32,
+    },
+    Complete {
+        transfer_id: u32, // the receiver verified the file
+    },
+}
+
+impl TransferFrame {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![TRANSFER_PROTOCOL_ID];
+
+        match self {
+            TransferFrame::Offer(offer) => {
+                bytes.push(OFFER_KIND);
+                bytes.extend_from_slice(&offer.transfer_id.to_le_bytes());
+                bytes.extend_from_slice(&offer.size.to_le_bytes());
+                bytes.extend_from_slice(&offer.original_size.to_le_bytes());
+                bytes.extend_from_slice(&offer.chunk_count.to_le_bytes());
+                bytes.push(if offer.compressed { COMPRESSED_FLAG } else { 0 });
+                bytes.extend_from_slice(&offer.sha256);
+                bytes.extend_from_slice(offer.file_name.as_bytes());
+            }
+            TransferFrame::Accept {
+                transfer_id,
+                received,
+            } => {
+                bytes.push(ACCEPT_KIND);
+                bytes.extend_from_slice(&transfer_id.to_le_bytes());
+                bytes.extend_from_slice(received);
+            }
+            TransferFrame::Chunk {
+                transfer_id,
+                index,
+                data,
+            } => {
+                bytes.push(CHUNK_KIND);
+                bytes.extend_from_slice(&transfer_id.to_le_bytes());
+                bytes.extend_from_slice(&index.to_le_bytes());
+                bytes.extend_from_slice(data);
+            }
+            TransferFrame::Cancel { transfer_id } => {
+                bytes.push(CANCEL_KIND);
+                bytes.extend_from_slice(&transfer_id.to_le_bytes());
+            }
+            TransferFrame::Complete { transfer_id } => {
+                bytes.push(COMPLETE_KIND);
+                bytes.extend_from_slice(&transfer_id.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    /// Decodes a frame, including the leading protocol byte
+    pub fn decode(bytes: &[u8]) -> Result<Self, TransferError> {
+        if bytes.len() < 6 {
+            return Err(TransferError::Truncated);
+        }
+
+        let transfer_id = u32::from_le_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]);
+        let body = &bytes[6..];
+
+        match bytes[1] {
+            OFFER_KIND => {
+                // size, original size, chunk count, flags, hash
+                if body.len() < 4 + 4 + 2 + 1 + 32 {
+                    return Err(TransferError::Truncated);
+                }
+
+                let mut sha256 = [0u8; 32];
+                sha256.copy_from_slice(&body[11..43]);
+
+                Ok(TransferFrame::Offer(TransferOffer {
+                    transfer_id,
+                    size: u32::from_le_bytes([body[0], body[1], body[2], body[3]]),
+                    original_size: u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+                    chunk_count: u16::from_le_bytes([body[8], body[9]]),
+                    compressed: body[10] & COMPRESSED_FLAG != 0,
+                    sha256,
+                    file_name: String::from_utf8_lossy(&body[43..]).into_owned(),
+                }))
+            }
+            ACCEPT_KIND => Ok(TransferFrame::Accept {
+                transfer_id,
+                received: body.to_vec(),
+            }),
+            CHUNK_KIND => {
+                if body.len() < 2 {
+                    return Err(TransferError::Truncated);
+                }
+
+                Ok(TransferFrame::Chunk {
+                    transfer_id,
+                    index: u16::from_le_bytes([body[0], body[1]]),
+                    data: body[2..].to_vec(),
+                })
+            }
+            CANCEL_KIND => Ok(TransferFrame::Cancel { transfer_id }),
+            COMPLETE_KIND => Ok(TransferFrame::Complete { transfer_id }),
+            kind => Err(TransferError::UnknownKind(kind)),
+        }
+    }
+}
+
+fn encode_bitmap(indices: impl Iterator<Item = u16>, count: u16) -> Vec<u8> {
+    let mut bitmap = vec![0u8; (count as usize).div_ceil(8)];
+
+    for i in indices.filter(|i| *i < count) {
+        bitmap[i as usize / 8] |= 1 << (i % 8);
+    }
+
+    bitmap
+}
+
+fn decode_bitmap(bitmap: &[u8], count: u16) -> BTreeSet<u16> {
+    (0..count)
+        .filter(|i| {
+            bitmap
+                .get(*i as usize / 8)
+                .is_some_and(|b| b & (1 << (i % 8)) != 0)
+        })
+        .collect()
+}
+
+/// Keeps only characters that are safe in a file name on every platform
+fn sanitize_file_name(file_name: &str) -> String {
+    let sanitized: String = file_name
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect();
+
+    match sanitized.trim_start_matches('.') {
+        "" => "file".into(),
+        name => name.into(),
+    }
+}
+
+/// Truncates a file name to fit in an offer without splitting a character
+fn truncate_file_name(file_name: &str) -> String {
+    let mut end = file_name.len().min(MAX_FILE_NAME_LEN);
+
+    while !file_name.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    file_name[..end].into()
+}
+
+pub fn get_transfer_store_path(app_data_dir: &Path, device_key: &DeviceKey) -> PathBuf {
+    let file_name: String = device_key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    app_data_dir
+        .join("transfers")
+        .join(format!("{}.json", file_name))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TransferDirection {
+    Outgoing,
+    Incoming,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TransferState {
+    Offered,      // waiting for the receiver to accept
+    Transferring, // chunks are being sent or received
+    Completed,
+    Cancelled,
+    Failed(String),
+}
+
+impl TransferState {
+    fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            TransferState::Completed | TransferState::Cancelled | TransferState::Failed(_)
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTransferStatus {
+    pub transfer_id: u32,
+    pub direction: TransferDirection,
+    pub peer: u32, // node num of the other end of the transfer
+    pub channel: u32,
+    pub file_name: String,
+    pub size: u32, // bytes of the file itself
+    pub compressed: bool,
+    pub chunks_done: u32, // acknowledged when sending, received when receiving
+    pub chunks_total: u32,
+    pub state: TransferState,
+    pub saved_path: Option<String>, // where a received file was written
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Transfer {
+    offer: TransferOffer,
+    direction: TransferDirection,
+    peer: u32,
+    channel: u32,
+    state: TransferState,
+    payload: Vec<u8>,                 // encoded file, only kept when sending
+    received: BTreeMap<u16, Vec<u8>>, // chunks received so far
+    acked: BTreeSet<u16>,             // chunks the receiver acknowledged
+    saved_path: Option<PathBuf>,
+    #[serde(skip)]
+    failures: u32,
+    #[serde(skip)]
+    finished_at: Option<Instant>,
+}
+
+impl Transfer {
+    fn finish(&mut self, state: TransferState) {
+        self.state = state;
+        self.finished_at = Some(Instant::now());
+    }
+
+    fn status(&self) -> FileTransferStatus {
+        let chunks_done = match self.direction {
+            TransferDirection::Outgoing => self.acked.len(),
+            TransferDirection::Incoming => self.received.len(),
+        };
+
+        FileTransferStatus {
+            transfer_id: self.offer.transfer_id,
+            direction: self.direction,
+            peer: self.peer,
+            channel: self.channel,
+            file_name: self.offer.file_name.clone(),
+            size: self.offer.original_size,
+            compressed: self.offer.compressed,
+            chunks_done: chunks_done as u32,
+            chunks_total: self.offer.chunk_count as u32,
+            state: self.state.clone(),
+            saved_path: self
+                .saved_path
+                .as_ref()
+                .map(|p| p.to_string_lossy().into_owned()),
+        }
+    }
+
+    fn received_bitmap(&self) -> Vec<u8> {
+        encode_bitmap(self.received.keys().copied(), self.offer.chunk_count)
+    }
+
+    /// Joins the received chunks, then decompresses and verifies the file
+    fn assemble(&self) -> Result<Vec<u8>, String> {
+        let payload: Vec<u8> = self.received.values().flatten().copied().collect();
+
+        let file = match self.offer.compressed {
+            true => {
+                let mut file = vec![];
+
+                // Bound decompression by the offered size, plus a byte to
+                // detect files larger than offered
+                ZlibDecoder::new(payload.as_slice())
+                    .take(self.offer.original_size as u64 + 1)
+                    .read_to_end(&mut file)
+                    .map_err(|e| format!("Failed to decompress file: {}", e))?;
+
+                file
+            }
+            false => payload,
+        };
+
+        if file.len() != self.offer.original_size as usize {
+            return Err("File size doesn't match offer".into());
+        }
+
+        if Sha256::digest(&file).as_slice() != self.offer.sha256 {
+            return Err("File failed integrity check".into());
+        }
+
+        Ok(file)
+    }
+}
+
+/// Something the transfer manager needs sent to a peer
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutgoingFrame {
+    pub to: u32,
+    pub channel: u32,
+    pub frame: TransferFrame,
+}
+
+/// Tracks file transfers in both directions, and which sent packets carry
+/// which chunks so acks can be matched to them
+#[derive(Debug, Default)]
+pub struct FileTransfers {
+    transfers: HashMap<u32, Transfer>,
+    chunk_packets: HashMap<u32, (u32, u16)>, // packet id to transfer id and chunk index
+    store_path: Option<PathBuf>,
+    dirty: bool,
+}
+
+impl FileTransfers {
+    pub fn list(&self) -> Vec<FileTransferStatus> {
+        let mut transfers: Vec<FileTransferStatus> =
+            self.transfers.values().map(|t| t.status()).collect();
+        transfers.sort_by_key(|t| t.transfer_id);
+        transfers
+    }
+
+    pub fn status(&self, transfer_id: u32) -> Option<FileTransferStatus> {
+        self.transfers.get(&transfer_id).map(|t| t.status())
+    }
+
+    /// Prepares a file to be sent, compressing it if requested and if that
+    /// makes it smaller. Returns the id of the new transfer and its offer.
+    pub fn offer(
+        &mut self,
+        peer: u32,
+        channel: u32,
+        file_name: &str,
+        file: &[u8],
+        compress: bool,
+    ) -> Result<(u32, OutgoingFrame), TransferError> {
+        if file.is_empty() {
+            return Err(TransferError::Empty);
+        }
+
+        if file.len() > MAX_TRANSFER_SIZE {
+            return Err(TransferError::TooLarge(file.len()));
+        }
+
+        let compressed = match compress {
+            true => {
+                let mut encoder = ZlibEncoder::new(vec![], Compression::best());
+                encoder
+                    .write_all(file)
+                    .map_err(TransferError::Compression)?;
+                Some(encoder.finish().map_err(TransferError::Compression)?)
+            }
+            false => None,
+        };
+
+        // Compression only pays off for files that actually shrink
+        let (payload, is_compressed) = match compressed {
+            Some(compressed) if compressed.len() < file.len() => (compressed, true),
+            _ => (file.to_vec(), false),
+        };
+
+        let offer = TransferOffer {
+            transfer_id: generate_rand_id(),
+            size: payload.len() as u32,
+            original_size: file.len() as u32,
+            chunk_count: payload.len().div_ceil(CHUNK_DATA_LEN) as u16,
+            compressed: is_compressed,
+            sha256: Sha256::digest(file).into(),
+            file_name: truncate_file_name(file_name),
+        };
+
+        debug!(
+            "Offering \"{}\" to {} as transfer {} in {} chunks",
+            offer.file_name, peer, offer.transfer_id, offer.chunk_count
+        );
+
+        let transfer_id = offer.transfer_id;
+        let frame = TransferFrame::Offer(offer.clone());
+
+        self.transfers.insert(
+            transfer_id,
+            Transfer {
+                offer,
+                direction: TransferDirection::Outgoing,
+                peer,
+                channel,
+                state: TransferState::Offered,
+                payload,
+                received: BTreeMap::new(),
+                acked: BTreeSet::new(),
+                saved_path: None,
+                failures: 0,
+                finished_at: None,
+            },
+        );
+        self.dirty = true;
+
+        Ok((
+            transfer_id,
+            OutgoingFrame {
+                to: peer,
+                channel,
+                frame,
+            },
+        ))
+    }
+
+    /// Accepts an offered file, reporting any chunks already received so
+    /// an interrupted transfer resumes where it stopped
+    pub fn accept(&mut self, transfer_id: u32) -> Result<OutgoingFrame, TransferError> {
+        let transfer = self
+            .transfers
+            .get_mut(&transfer_id)
+            .ok_or(TransferError::NotFound(transfer_id))?;
+
+        if transfer.direction != TransferDirection::Incoming
+            || transfer.state != TransferState::Offered
+        {
+            return Err(TransferError::InvalidState(transfer_id, "accepted"));
+        }
+
+        transfer.state = TransferState::Transferring;
+        self.dirty = true;
+
+        Ok(OutgoingFrame {
+            to: transfer.peer,
+            channel: transfer.channel,
+            frame: TransferFrame::Accept {
+                transfer_id,
+                received: transfer.received_bitmap(),
+            },
+        })
+    }
+
+    pub fn cancel(&mut self, transfer_id: u32) -> Result<OutgoingFrame, TransferError> {
+        let transfer = self
+            .transfers
+            .get_mut(&transfer_id)
+            .ok_or(TransferError::NotFound(transfer_id))?;
+
+        if transfer.state.is_finished() {
+            return Err(TransferError::InvalidState(transfer_id, "cancelled"));
+        }
+
+        debug!("Cancelling transfer {}", transfer_id);
+
+        transfer.finish(TransferState::Cancelled);
+        transfer.payload.clear();
+        transfer.received.clear();
+        self.chunk_packets.retain(|_, (id, _)| *id != transfer_id);
+        self.dirty = true;
+
+        Ok(OutgoingFrame {
+            to: transfer.peer,
+            channel: transfer.channel,
+            frame: TransferFrame::Cancel { transfer_id },
+        })
+    }
+
+    /// Returns the next chunks of an outgoing transfer to send, keeping at
+    /// most `CHUNK_WINDOW` of them unacknowledged
+    pub fn next_chunks(&self, transfer_id: u32) -> Vec<OutgoingFrame> {
+        let transfer = match self.transfers.get(&transfer_id) {
+            Some(t) if t.state == TransferState::Transferring => t,
+            _ => return vec![],
+        };
+
+        let pending: BTreeSet<u16> = self
+            .chunk_packets
+            .values()
+            .filter(|(id, _)| *id == transfer_id)
+            .map(|(_, index)| *index)
+            .collect();
+
+        (0..transfer.offer.chunk_count)
+            .filter(|i| !transfer.acked.contains(i) && !pending.contains(i))
+            .take(CHUNK_WINDOW.saturating_sub(pending.len()))
+            .map(|index| {
+                let start = index as usize * CHUNK_DATA_LEN;
+                let end = (start + CHUNK_DATA_LEN).min(transfer.payload.len());
+
+                OutgoingFrame {
+                    to: transfer.peer,
+                    channel: transfer.channel,
+                    frame: TransferFrame::Chunk {
+                        transfer_id,
+                        index,
+                        data: transfer.payload[start..end].to_vec(),
+                    },
+                }
+            })
+            .collect()
+    }
+
+    pub fn track_chunk_packet(&mut self, packet_id: u32, transfer_id: u32, index: u16) {
+        self.chunk_packets.insert(packet_id, (transfer_id, index));
+    }
+
+    /// Applies the mesh's routing result for a sent chunk, returning the id
+    /// of the transfer the packet belonged to
+    pub fn handle_chunk_result(
+        &mut self,
+        packet_id: u32,
+        error: protobufs::routing::Error,
+    ) -> Option<u32> {
+        let (transfer_id, index) = self.chunk_packets.remove(&packet_id)?;
+        let transfer = self.transfers.get_mut(&transfer_id)?;
+
+        if error == protobufs::routing::Error::None {
+            trace!("Chunk {} of transfer {} acknowledged", index, transfer_id);
+            transfer.acked.insert(index);
+        } else {
+            transfer.failures += 1;
+
+            warn!(
+                "Chunk {} of transfer {} failed ({}), {} failures",
+                index,
+                transfer_id,
+                error.as_str_name(),
+                transfer.failures
+            );
+
+            if transfer.failures >= MAX_CHUNK_FAILURES {
+                transfer.finish(TransferState::Failed(
+                    "Too many chunks failed to send".into(),
+                ));
+                self.chunk_packets.retain(|_, (id, _)| *id != transfer_id);
+            }
+        }
+
+        self.dirty = true;
+
+        Some(transfer_id)
+    }
+
+    /// Applies a frame received from another node, returning any frames to
+    /// send in response and the id of the transfer that changed
+    pub fn handle_frame(
+        &mut self,
+        from: u32,
+        channel: u32,
+        frame: TransferFrame,
+        received_dir: Option<&Path>,
+    ) -> (Vec<OutgoingFrame>, Option<u32>) {
+        let reply = |frame: TransferFrame| OutgoingFrame {
+            to: from,
+            channel,
+            frame,
+        };
+
+        match frame {
+            TransferFrame::Offer(offer) => {
+                let transfer_id = offer.transfer_id;
+
+                // A repeated offer means the sender reconnected, so resume
+                // from the chunks already received. An id that clashes with
+                // another peer's transfer or one of our own is refused
+                if let Some(transfer) = self.transfers.get(&transfer_id) {
+                    if transfer.direction != TransferDirection::Incoming || transfer.peer != from {
+                        warn!(
+                            "Refusing offer of transfer {} from {}, id already in use",
+                            transfer_id, from
+                        );
+                        return (vec![reply(TransferFrame::Cancel { transfer_id })], None);
+                    }
+
+                    let response = match &transfer.state {
+                        TransferState::Transferring => TransferFrame::Accept {
+                            transfer_id,
+                            received: transfer.received_bitmap(),
+                        },
+                        TransferState::Completed => TransferFrame::Complete { transfer_id },
+                        TransferState::Offered => return (vec![], None),
+                        _ => TransferFrame::Cancel { transfer_id },
+                    };
+
+                    return (vec![reply(response)], None);
+                }
+
+                if offer.size as usize > MAX_TRANSFER_SIZE
+                    || offer.original_size as usize > MAX_TRANSFER_SIZE
+                    || offer.chunk_count as usize != (offer.size as usize).div_ceil(CHUNK_DATA_LEN)
+                {
+                    warn!("Rejecting invalid offer for transfer {}", transfer_id);
+                    return (vec![reply(TransferFrame::Cancel { transfer_id })], None);
+                }
+
+                let pending: Vec<&Transfer> = self
+                    .transfers
+                    .values()
+                    .filter(|t| {
+                        t.direction == TransferDirection::Incoming && !t.state.is_finished()
+                    })
+                    .collect();
+
+                if pending.len() >= MAX_INCOMING_TRANSFERS
+                    || pending.iter().filter(|t| t.peer == from).count()
+                        >= MAX_INCOMING_TRANSFERS_PER_PEER
+                {
+                    warn!(
+                        "Refusing offer of transfer {} from {}, too many transfers pending",
+                        transfer_id, from
+                    );
+                    return (vec![reply(TransferFrame::Cancel { transfer_id })], None);
+                }
+
+                debug!(
+                    "Received offer of \"{}\" from {} as transfer {}",
+                    offer.file_name, from, transfer_id
+                );
+
+                self.transfers.insert(
+                    transfer_id,
+                    Transfer {
+                        offer,
+                        direction: TransferDirection::Incoming,
+                        peer: from,
+                        channel,
+                        state: TransferState::Offered,
+                        payload: vec![],
+                        received: BTreeMap::new(),
+                        acked: BTreeSet::new(),
+                        saved_path: None,
+                        failures: 0,
+                        finished_at: None,
+                    },
+                );
+                self.dirty = true;
+
+                (vec![], Some(transfer_id))
+            }
+            TransferFrame::Accept {
+                transfer_id,
+                received,
+            } => {
+                let transfer = match self.outgoing_from(transfer_id, from) {
+                    Some(t) if !t.state.is_finished() => t,
+                    _ => return (vec![], None),
+                };
+
+                // Chunks the receiver already has don't need to be sent again
+                transfer
+                    .acked
+                    .extend(decode_bitmap(&received, transfer.offer.chunk_count));
+                transfer.state = TransferState::Transferring;
+                self.dirty = true;
+
+                (self.next_chunks(transfer_id), Some(transfer_id))
+            }
+            TransferFrame::Chunk {
+                transfer_id,
+                index,
+                data,
+            } => {
+                let transfer = match self.transfers.get_mut(&transfer_id) {
+                    Some(t)
+                        if t.direction == TransferDirection::Incoming
+                            && t.peer == from
+                            && t.state == TransferState::Transferring
+                            && index < t.offer.chunk_count =>
+                    {
+                        t
+                    }
+                    _ => {
+                        trace!("Ignoring chunk of unknown transfer {}", transfer_id);
+                        return (vec![], None);
+                    }
+                };
+
+                transfer.received.insert(index, data);
+                self.dirty = true;
+
+                if transfer.received.len() < transfer.offer.chunk_count as usize {
+                    return (vec![], Some(transfer_id));
+                }
+
+                let response = match transfer.assemble() {
+                    Ok(file) => match save_received_file(received_dir, transfer, &file) {
+                        Ok(saved_path) => {
+                            debug!("Completed transfer {}", transfer_id);
+
+                            transfer.finish(TransferState::Completed);
+                            transfer.saved_path = saved_path;
+                            TransferFrame::Complete { transfer_id }
+                        }
+                        Err(e) => {
+                            transfer.finish(TransferState::Failed(format!(
+                                "Failed to save file: {}",
+                                e
+                            )));
+                            TransferFrame::Cancel { transfer_id }
+                        }
+                    },
+                    Err(e) => {
+                        warn!("Transfer {} failed: {}", transfer_id, e);
+                        transfer.finish(TransferState::Failed(e));
+                        TransferFrame::Cancel { transfer_id }
+                    }
+                };
+
+                transfer.received.clear();
+
+                (vec![reply(response)], Some(transfer_id))
+            }
+            TransferFrame::Cancel { transfer_id } => {
+                let transfer = match self.transfers.get_mut(&transfer_id) {
+                    Some(t) if t.peer == from && !t.state.is_finished() => t,
+                    _ => return (vec![], None),
+                };
+
+                debug!("Transfer {} cancelled by {}", transfer_id, from);
+
+                transfer.finish(TransferState::Cancelled);
+                transfer.payload.clear();
+                transfer.received.clear();
+                self.chunk_packets.retain(|_, (id, _)| *id != transfer_id);
+                self.dirty = true;
+
+                (vec![], Some(transfer_id))
+            }
+            TransferFrame::Complete { transfer_id } => {
+                let transfer = match self.outgoing_from(transfer_id, from) {
+                    Some(t) if !t.state.is_finished() => t,
+                    _ => return (vec![], None),
+                };
+
+                debug!("Transfer {} confirmed by {}", transfer_id, from);
+
+                transfer.acked = (0..transfer.offer.chunk_count).collect();
+                transfer.finish(TransferState::Completed);
+                transfer.payload.clear();
+                self.chunk_packets.retain(|_, (id, _)| *id != transfer_id);
+                self.dirty = true;
+
+                (vec![], Some(transfer_id))
+            }
+        }
+    }
+
+    fn outgoing_from(&mut self, transfer_id: u32, from: u32) -> Option<&mut Transfer> {
+        self.transfers
+            .get_mut(&transfer_id)
+            .filter(|t| t.direction == TransferDirection::Outgoing && t.peer == from)
+    }
+
+    /// Offers of unfinished outgoing transfers, sent again after reconnecting
+    /// so receivers can report which chunks they're missing
+    pub fn resume_offers(&self) -> Vec<OutgoingFrame> {
+        self.transfers
+            .values()
+            .filter(|t| t.direction == TransferDirection::Outgoing && !t.state.is_finished())
+            .map(|t| OutgoingFrame {
+                to: t.peer,
+                channel: t.channel,
+                frame: TransferFrame::Offer(t.offer.clone()),
+            })
+            .collect()
+    }
+
+    /// Loads transfers left unfinished when the device was last connected,
+    /// and persists transfers to the same path from then on
+    pub fn load(&mut self, path: PathBuf) -> Result<(), TransferError> {
+        if path.exists() {
+            let transfers: Vec<Transfer> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+
+            debug!("Restoring {} transfers from {:?}", transfers.len(), path);
+
+            for transfer in transfers {
+                self.transfers.insert(transfer.offer.transfer_id, transfer);
+            }
+        }
+
+        self.store_path = Some(path);
+
+        Ok(())
+    }
+
+    /// Forgets transfers that finished longer than the retention ago
+    pub fn prune(&mut self, now: Instant) {
+        let is_expired = |t: &Transfer| {
+            t.finished_at.is_some_and(|finished| {
+                now.saturating_duration_since(finished) > FINISHED_TRANSFER_RETENTION
+            })
+        };
+
+        let count = self.transfers.len();
+        self.transfers.retain(|_, t| !is_expired(t));
+
+        if self.transfers.len() != count {
+            trace!("Pruned {} finished transfers", count - self.transfers.len());
+        }
+    }
+
+    /// Serializes unfinished transfers if they changed since they were last
+    /// persisted, to be written once the device lock is released
+    pub fn persist(&mut self) -> Result<Option<PendingWrite>, TransferError> {
+        let path = match self.store_path.as_ref() {
+            Some(p) if self.dirty => p.clone(),
+            _ => return Ok(None),
+        };
+
+        let transfers: Vec<&Transfer> = self
+            .transfers
+            .values()
+            .filter(|t| !t.state.is_finished())
+            .collect();
+
+        let contents = serde_json::to_string(&transfers)?;
+        self.dirty = false;
+
+        Ok(Some(PendingWrite::new(path, contents)))
+    }
+
+    /// Directory received files are written to, alongside the store
+    pub fn received_dir(&self) -> Option<PathBuf> {
+        self.store_path
+            .as_ref()
+            .and_then(|p| p.parent())
+            .map(|p| p.join("received"))
+    }
+}
+
+/// Writes a received file, returning where it was saved. Files are only
+/// kept in memory when there's no app data directory to write to.
+fn save_received_file(
+    received_dir: Option<&Path>,
+    transfer: &mut Transfer,
+    file: &[u8],
+) -> std::io::Result<Option<PathBuf>> {
+    let received_dir = match received_dir {
+        Some(dir) => dir,
+        None => {
+            transfer.payload = file.to_vec();
+            return Ok(None);
+        }
+    };
+
+    std::fs::create_dir_all(received_dir)?;
+
+    let path = received_dir.join(format!(
+        "{}-{}",
+        transfer.offer.transfer_id,
+        sanitize_file_name(&transfer.offer.file_name)
+    ));
+
+    std::fs::write(&path, file)?;
+
+    Ok(Some(path))
+}
+
+impl<R: tauri::Runtime> MeshPacketApi<R> {
+    /// Queues transfer frames to be sent directly to their peers
+    pub fn send_transfer_frames(&mut self, frames: Vec<OutgoingFrame>) {
+        for OutgoingFrame { to, channel, frame } in frames {
+            let packet = self.build_private_packet(to, channel, frame.encode());
+
+            if let TransferFrame::Chunk {
+                transfer_id, index, ..
+            } = frame
+            {
+                self.transfers
+                    .track_chunk_packet(packet.id, transfer_id, index);
+            }
+
+            self.outbox
+                .enqueue_transient(packet, MessagePriority::Routine);
+        }
+    }
+
+    pub fn emit_transfer_update(&mut self, transfer_id: u32) {
+        if let Some(status) = self.transfers.status(transfer_id) {
+            self.dispatcher.enqueue(
+                Some(&self.device_key),
+                DeltaPayload::FileTransferUpdated(status),
+            );
+        }
+    }
+
+    pub fn handle_transfer_frame(
+        &mut self,
+        packet: protobufs::MeshPacket,
+        frame: TransferFrame,
+    ) -> Result<(), DeviceUpdateError> {
+        let received_dir = self.transfers.received_dir();
+        let (frames, changed) = self.transfers.handle_frame(
+            packet.from,
+            packet.channel,
+            frame,
+            received_dir.as_deref(),
+        );
+
+        self.send_transfer_frames(frames);
+
+        if let Some(transfer_id) = changed {
+            self.emit_transfer_update(transfer_id);
+        }
+
+        Ok(())
+    }
+
+    /// Applies the routing result of a sent packet if it carried a file
+    /// chunk, sending the chunks the window has room for
+    pub fn handle_transfer_routing_result(
+        &mut self,
+        packet_id: u32,
+        error: protobufs::routing::Error,
+    ) {
+        let transfer_id = match self.transfers.handle_chunk_result(packet_id, error) {
+            Some(id) => id,
+            None => return,
+        };
+
+        let frames = self.transfers.next_chunks(transfer_id);
+        self.send_transfer_frames(frames);
+        self.emit_transfer_update(transfer_id);
+    }
+
+    /// Fails chunks the outbox gave up on, or that were never acknowledged,
+    /// so they're sent again instead of holding the transfer's window
+    pub fn handle_undelivered_transfer_packets(&mut self) {
+        for packet_id in self.outbox.take_undelivered() {
+            self.handle_transfer_routing_result(packet_id, protobufs::routing::Error::Timeout);
+        }
+    }
+
+    /// Offers unfinished transfers again once the device is configured
+    pub fn resume_file_transfers(&mut self) {
+        let offers = self.transfers.resume_offers();
+
+        if !offers.is_empty() {
+            debug!("Resuming {} outgoing transfers", offers.len());
+        }
+
+        self.send_transfer_frames(offers);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deliver(
+        frames: Vec<OutgoingFrame>,
+        from: u32,
+        to: &mut FileTransfers,
+    ) -> Vec<OutgoingFrame> {
+        frames
+            .into_iter()
+            .flat_map(|f| to.handle_frame(from, f.channel, f.frame, None).0)
+            .collect()
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let frames = [
+            TransferFrame::Offer(TransferOffer {
+                transfer_id: 7,
+                size: 300,
+                original_size: 900,
+                chunk_count: 2,
+                compressed: true,
+                sha256: [3; 32],
+                file_name: "map.png".into(),
+            }),
+            TransferFrame::Accept {
+                transfer_id: 7,
+                received: encode_bitmap([0, 9].into_iter(), 10),
+            },
+            TransferFrame::Chunk {
+                transfer_id: 7,
+                index: 1,
+                data: vec![1, 2, 3],
+            },
+        ];
+
+        for frame in frames {
+            assert_eq!(TransferFrame::decode(&frame.encode()).unwrap(), frame);
+        }
+
+        assert_eq!(
+            decode_bitmap(&encode_bitmap([0, 9].into_iter(), 10), 10),
+            BTreeSet::from([0, 9])
+        );
+    }
+
+    #[test]
+    fn offers_are_limited_per_peer() {
+        let mut sender = FileTransfers::default();
+        let mut receiver = FileTransfers::default();
+
+        for i in 0..=MAX_INCOMING_TRANSFERS_PER_PEER {
+            let (transfer_id, offer) = sender.offer(2, 0, "a.txt", &[1, 2, 3], false).unwrap();
+            let replies = deliver(vec![offer], 1, &mut receiver);
+
+            match i < MAX_INCOMING_TRANSFERS_PER_PEER {
+                true => assert!(replies.is_empty()),
+                false => assert_eq!(replies[0].frame, TransferFrame::Cancel { transfer_id }),
+            }
+        }
+
+        // Other peers still have room
+        let (_, offer) = sender.offer(2, 0, "b.txt", &[1, 2, 3], false).unwrap();
+        assert!(deliver(vec![offer], 3, &mut receiver).is_empty());
+    }
+
+    #[test]
+    fn clashing_offer_leaves_existing_transfer() {
+        let mut sender = FileTransfers::default();
+        let mut receiver = FileTransfers::default();
+
+        let (transfer_id, offer) = sender.offer(2, 0, "a.txt", &[1, 2, 3], false).unwrap();
+        assert!(deliver(vec![offer.clone()], 1, &mut receiver).is_empty());
+
+        // The same id offered by another peer, or echoed back to the sender
+        for (from, transfers) in [(3, &mut receiver), (2, &mut sender)] {
+            let replies = deliver(vec![offer.clone()], from, transfers);
+
+            assert_eq!(replies[0].frame, TransferFrame::Cancel { transfer_id });
+            assert!(!transfers.status(transfer_id).unwrap().state.is_finished());
+        }
+
+        assert_eq!(
+            receiver.status(transfer_id).unwrap().direction,
+            TransferDirection::Incoming
+        );
+        assert_eq!(
+            sender.status(transfer_id).unwrap().direction,
+            TransferDirection::Outgoing
+        );
+    }
+
+    #[test]
+    fn transfer_resumes_and_verifies() {
+        // Noise that won't compress followed by padding that will, so the
+        // file is compressed but still spans several chunks
+        let mut seed = 1u32;
+        let mut file: Vec<u8> = (0..700)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                (seed >> 24) as u8
+            })
+            .collect();
+        file.extend(vec![0u8; 3000]);
+
+        let mut sender = FileTransfers::default();
+        let mut receiver = FileTransfers::default();
+
+        let (transfer_id, offer) = sender.offer(2, 0, "log.txt", &file, true).unwrap();
+
+        assert!(deliver(vec![offer], 1, &mut receiver).is_empty());
+        assert!(receiver.status(transfer_id).unwrap().compressed);
+
+        let accept = receiver.accept(transfer_id).unwrap();
+        let mut chunks = deliver(vec![accept], 2, &mut sender);
+        assert!(!chunks.is_empty());
+
+        // Only the first chunk arrives before the sender disconnects
+        chunks.truncate(1);
+        deliver(chunks, 1, &mut receiver);
+
+        let resumed = deliver(sender.resume_offers(), 1, &mut receiver);
+        assert!(matches!(resumed[0].frame, TransferFrame::Accept { .. }));
+
+        let remaining = deliver(resumed, 2, &mut sender);
+        assert!(remaining
+            .iter()
+            .all(|f| !matches!(f.frame, TransferFrame::Chunk { index: 0, .. })));
+
+        let confirmation = deliver(remaining, 1, &mut receiver);
+        assert_eq!(
+            confirmation[0].frame,
+            TransferFrame::Complete { transfer_id }
+        );
+
+        deliver(confirmation, 2, &mut sender);
+        assert_eq!(
+            sender.status(transfer_id).unwrap().state,
+            TransferState::Completed
+        );
+        assert_eq!(receiver.transfers[&transfer_id].payload, file);
+    }
+}
@@ -0,0 +1,662 @@
+//! Unishox2 text compression, as used by firmware for the compressed text
+//! message portnum. Only the default preset is supported.
+//!
+//! The decoder handles the codes the reference encoder produces for chat
+//! text: character sets, case shifts, Unicode deltas, repeats, back
+//! references and frequent sequences. Hex and template codes are rejected
+//! rather than guessed at. The encoder emits a subset of these codes and
+//! pads with zeros instead of writing a terminator. Its output hasn't been
+//! checked against the reference decoder, so sending compressed text is
+//! opt-in.
+
+const ALPHA: usize = 0;
+const SYM: usize = 1;
+const NUM: usize = 2;
+const DICT: usize = 3;
+const DELTA: usize = 4;
+
+const SETS: [[u8; 28]; 3] = [
+    [
+        0, b' ', b'e', b't', b'a', b'o', b'i', b'n', b's', b'r', b'l', b'c', b'd', b'h', b'u',
+        b'p', b'm', b'b', b'g', b'w', b'f', b'y', b'v', b'k', b'q', b'j', b'x', b'z',
+    ],
+    [
+        b'"', b'{', b'}', b'_', b'<', b'>', b':', b'\n', 0, b'[', b']', b'\\', b';', b'\'', b'\t',
+        b'@', b'*', b'&', b'?', b'!', b'^', b'|', b'\r', b'~', b'`', 0, 0, 0,
+    ],
+    [
+        0, b',', b'.', b'0', b'1', b'9', b'2', b'5', b'-', b'/', b'3', b'4', b'6', b'7', b'8',
+        b'(', b')', b' ', b'=', b'+', b'$', b'%', b'#', 0, 0, 0, 0, 0,
+    ],
+];
+
+// Prefix codes, left aligned in a byte
+const VCODES: [u8; 28] = [
+    0x00, 0x40, 0x60, 0x80, 0x90, 0xA0, 0xB0, 0xC0, 0xD0, 0xD8, 0xE0, 0xE4, 0xE8, 0xEC, 0xEE, 0xF0,
+    0xF2, 0xF4, 0xF6, 0xF7, 0xF8, 0xF9, 0xFA, 0xFB, 0xFC, 0xFD, 0xFE, 0xFF,
+];
+const VCODE_LENS: [u8; 28] = [
+    2, 3, 3, 4, 4, 4, 4, 4, 5, 5, 6, 6, 6, 7, 7, 7, 7, 7, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8,
+];
+
+const HCODES: [u8; 5] = [0x00, 0x40, 0x80, 0xC0, 0xE0];
+const HCODE_LENS: [u8; 5] = [2, 2, 2, 3, 3];
+
+const FREQ_SEQ: [&str; 6] = ["\": \"", "\": ", "</", "=\"", "\":\"", "://"];
+
+// Counts and Unicode deltas use a unary length prefix, followed by the
+// value less the largest value of the shorter lengths
+const COUNT_BIT_LENS: [u8; 5] = [2, 4, 7, 11, 16];
+const COUNT_ADDER: [u32; 5] = [0, 4, 20, 148, 2196];
+
+const UNI_BIT_LENS: [u8; 5] = [6, 12, 14, 16, 21];
+const UNI_ADDER: [u32; 5] = [0, 64, 4160, 20544, 86080];
+
+/// Largest count that can be encoded, repeats and back references longer
+/// than this are split
+const MAX_COUNT: usize = (1 << 16) - 1 + 2196;
+
+const MAGIC_BIT_LEN: usize = 1;
+
+/// Shortest back reference that can be encoded
+const NICE_LEN: usize = 5;
+
+/// Furthest back the encoder looks for a back reference. Messages are
+/// short, so a larger window barely improves compression but makes the
+/// search quadratic in the length of the text.
+const BACK_REFERENCE_WINDOW: usize = 1024;
+
+/// Longest text decompressed. Repeats and back references can expand a
+/// single packet to far more text than any message holds.
+pub const MAX_DECOMPRESSED_LEN: usize = 128 * 1024;
+
+const SWITCH_VCODE: usize = 0;
+const SHIFTED_SPACE_VCODE: usize = 1; // enters continuous Unicode delta mode
+const CRLF_VCODE: usize = 8; // in the symbol set
+const REPEAT_VCODE: usize = 26; // in the number set
+const TERMINATOR_VCODE: usize = 27; // in the number set
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum Unishox2Error {
+    #[error("Unsupported Unishox2 code {1} in set {0}")]
+    UnsupportedCode(usize, usize),
+    #[error("Back reference outside of decoded text")]
+    InvalidBackReference,
+    #[error("Invalid Unicode code point {0}")]
+    InvalidCodePoint(i64),
+    #[error("Decoded text isn't valid UTF-8")]
+    InvalidUtf8,
+    #[error("Decompressed text is longer than {0} bytes")]
+    TooLong(usize),
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.bytes.get(self.position / 8)?;
+        let bit = byte & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+        Some(bit)
+    }
+
+    fn read_bits(&mut self, len: u8) -> Option<u32> {
+        let mut value = 0;
+
+        for _ in 0..len {
+            value = (value << 1) | self.read_bit()? as u32;
+        }
+
+        Some(value)
+    }
+
+    fn read_code(&mut self, codes: &[u8], lens: &[u8]) -> Option<usize> {
+        let mut value = 0u8;
+
+        for len in 1..=8u8 {
+            value |= (self.read_bit()? as u8) << (8 - len);
+
+            if let Some(idx) = (0..codes.len()).find(|i| lens[*i] == len && codes[*i] == value) {
+                return Some(idx);
+            }
+        }
+
+        None
+    }
+
+    fn read_vcode(&mut self) -> Option<usize> {
+        self.read_code(&VCODES, &VCODE_LENS)
+    }
+
+    fn read_hcode(&mut self) -> Option<usize> {
+        self.read_code(&HCODES, &HCODE_LENS)
+    }
+
+    /// Reads up to `limit` one bits, ended by a zero bit below the limit
+    fn read_step_code(&mut self, limit: usize) -> Option<usize> {
+        let mut idx = 0;
+
+        while self.read_bit()? {
+            idx += 1;
+
+            if idx == limit {
+                break;
+            }
+        }
+
+        Some(idx)
+    }
+
+    fn read_count(&mut self) -> Option<usize> {
+        let idx = self.read_step_code(4)?;
+        let value = self.read_bits(COUNT_BIT_LENS[idx])?;
+
+        Some((value + COUNT_ADDER[idx]) as usize)
+    }
+
+    fn read_unicode(&mut self) -> Option<UnicodeCode> {
+        let idx = self.read_step_code(5)?;
+
+        if idx == 5 {
+            return Some(UnicodeCode::Special(self.read_step_code(4)?));
+        }
+
+        let is_negative = self.read_bit()?;
+        let delta = (self.read_bits(UNI_BIT_LENS[idx])? + UNI_ADDER[idx]) as i64;
+
+        Some(UnicodeCode::Delta(if is_negative { -delta } else { delta }))
+    }
+}
+
+enum UnicodeCode {
+    Delta(i64),
+    Special(usize), // space, state switch, comma, period or newline
+}
+
+struct Decoder<'a> {
+    reader: BitReader<'a>,
+    out: Vec<u8>,
+    state: usize,
+    is_all_upper: bool,
+    prev_uni: i64,
+}
+
+impl<'a> Decoder<'a> {
+    /// Decodes the next code, returning false once the input runs out
+    fn step(&mut self) -> Result<bool, Unishox2Error> {
+        if self.state == DELTA {
+            return self.step_delta();
+        }
+
+        let mut vcode = match self.reader.read_vcode() {
+            Some(v) => v,
+            None => return Ok(false),
+        };
+        let mut set = self.state;
+        let mut is_upper = self.is_all_upper;
+
+        if vcode == SWITCH_VCODE {
+            match self.reader.read_hcode() {
+                Some(ALPHA) if self.state != ALPHA => {
+                    self.state = ALPHA;
+                    return Ok(true);
+                }
+                Some(ALPHA) => {
+                    // Switching to the current set shifts case
+                    if self.is_all_upper {
+                        self.is_all_upper = false;
+                        return Ok(true);
+                    }
+
+                    vcode = match self.reader.read_vcode() {
+                        Some(SWITCH_VCODE) => match self.reader.read_vcode() {
+                            Some(SWITCH_VCODE) => {
+                                self.is_all_upper = true;
+                                return Ok(true);
+                            }
+                            Some(v) => v,
+                            None => return Ok(false),
+                        },
+                        Some(SHIFTED_SPACE_VCODE) => {
+                            self.state = DELTA;
+                            return Ok(true);
+                        }
+                        Some(v) => v,
+                        None => return Ok(false),
+                    };
+                    is_upper = true;
+                }
+                Some(DICT) => return self.copy_back_reference(),
+                Some(DELTA) => return self.read_single_unicode(),
+                Some(h) => {
+                    set = h;
+                    vcode = match self.reader.read_vcode() {
+                        Some(v) => v,
+                        None => return Ok(false),
+                    };
+
+                    if set == NUM && vcode == SWITCH_VCODE {
+                        return Err(Unishox2Error::UnsupportedCode(set, vcode));
+                    }
+                }
+                None => return Ok(false),
+            }
+        }
+
+        self.push_char(set, vcode, is_upper)
+    }
+
+    fn step_delta(&mut self) -> Result<bool, Unishox2Error> {
+        match self.reader.read_unicode() {
+            Some(UnicodeCode::Delta(delta)) => self.push_unicode(delta),
+            Some(UnicodeCode::Special(1)) => match self.reader.read_hcode() {
+                Some(h @ (ALPHA | DELTA)) => {
+                    self.state = h;
+                    Ok(true)
+                }
+                Some(DICT) => self.copy_back_reference(),
+                Some(h) => match self.reader.read_vcode() {
+                    Some(v) => self.push_char(h, v, false),
+                    None => Ok(false),
+                },
+                None => Ok(false),
+            },
+            Some(UnicodeCode::Special(code)) => self.push_special(code),
+            None => Ok(false),
+        }
+    }
+
+    fn read_single_unicode(&mut self) -> Result<bool, Unishox2Error> {
+        match self.reader.read_unicode() {
+            Some(UnicodeCode::Delta(delta)) => self.push_unicode(delta),
+            Some(UnicodeCode::Special(1)) => Err(Unishox2Error::UnsupportedCode(DELTA, 1)),
+            Some(UnicodeCode::Special(code)) => self.push_special(code),
+            None => Ok(false),
+        }
+    }
+
+    fn push_unicode(&mut self, delta: i64) -> Result<bool, Unishox2Error> {
+        self.prev_uni += delta;
+
+        let c = u32::try_from(self.prev_uni)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(Unishox2Error::InvalidCodePoint(self.prev_uni))?;
+
+        self.out
+            .extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+
+        Ok(true)
+    }
+
+    fn push_special(&mut self, code: usize) -> Result<bool, Unishox2Error> {
+        let c = match code {
+            0 => b' ',
+            2 => b',',
+            3 => b'.',
+            _ => b'\n',
+        };
+
+        self.out.push(c);
+
+        Ok(true)
+    }
+
+    fn copy_back_reference(&mut self) -> Result<bool, Unishox2Error> {
+        let (len, dist) = match (self.reader.read_count(), self.reader.read_count()) {
+            (Some(len), Some(dist)) => (len + NICE_LEN, dist + NICE_LEN - 1),
+            _ => return Ok(false),
+        };
+
+        if dist > self.out.len() {
+            return Err(Unishox2Error::InvalidBackReference);
+        }
+
+        self.reserve(len)?;
+
+        let start = self.out.len() - dist;
+
+        for i in 0..len {
+            self.out.push(self.out[start + i]);
+        }
+
+        Ok(true)
+    }
+
+    /// Checks that `len` more bytes keep the text within the limit
+    fn reserve(&self, len: usize) -> Result<(), Unishox2Error> {
+        match self.out.len() + len > MAX_DECOMPRESSED_LEN {
+            true => Err(Unishox2Error::TooLong(MAX_DECOMPRESSED_LEN)),
+            false => Ok(()),
+        }
+    }
+
+    fn push_char(
+        &mut self,
+        set: usize,
+        vcode: usize,
+        is_upper: bool,
+    ) -> Result<bool, Unishox2Error> {
+        match SETS[set][vcode] {
+            c @ b'a'..=b'z' => {
+                self.state = ALPHA;
+                self.out
+                    .push(if is_upper { c.to_ascii_uppercase() } else { c });
+            }
+            c @ b'0'..=b'9' => {
+                self.state = NUM;
+                self.out.push(c);
+            }
+            0 => match (set, vcode) {
+                (SYM, CRLF_VCODE) => self.out.extend_from_slice(b"\r\n"),
+                (SYM, 25..=27) => self.out.extend_from_slice(FREQ_SEQ[vcode - 25].as_bytes()),
+                (NUM, 23..=25) => self.out.extend_from_slice(FREQ_SEQ[vcode - 20].as_bytes()),
+                (NUM, REPEAT_VCODE) => {
+                    let count = match self.reader.read_count() {
+                        Some(count) => count + 4,
+                        None => return Ok(false),
+                    };
+                    let c = *self.out.last().ok_or(Unishox2Error::InvalidBackReference)?;
+
+                    self.reserve(count)?;
+                    self.out.extend(std::iter::repeat(c).take(count));
+                }
+                (NUM, TERMINATOR_VCODE) => return Ok(false),
+                _ => return Err(Unishox2Error::UnsupportedCode(set, vcode)),
+            },
+            c => self.out.push(c),
+        }
+
+        Ok(true)
+    }
+}
+
+/// Decompresses Unishox2 compressed text. Bits left over at the end that
+/// don't form a complete code pad the final byte and are ignored.
+pub fn decompress(bytes: &[u8]) -> Result<String, Unishox2Error> {
+    let mut decoder = Decoder {
+        reader: BitReader {
+            bytes,
+            position: MAGIC_BIT_LEN,
+        },
+        out: vec![],
+        state: ALPHA,
+        is_all_upper: false,
+        prev_uni: 0,
+    };
+
+    while decoder.step()? {
+        decoder.reserve(0)?;
+    }
+
+    String::from_utf8(decoder.out).map_err(|_| Unishox2Error::InvalidUtf8)
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    /// Appends the lowest `len` bits of a value, most significant first
+    fn write_bits(&mut self, value: u32, len: u8) {
+        for i in (0..len).rev() {
+            if self.len % 8 == 0 {
+                self.bytes.push(0);
+            }
+
+            if value & (1 << i) != 0 {
+                *self.bytes.last_mut().expect("Byte was pushed") |= 0x80 >> (self.len % 8);
+            }
+
+            self.len += 1;
+        }
+    }
+
+    fn write_code(&mut self, code: u8, len: u8) {
+        self.write_bits((code >> (8 - len)) as u32, len);
+    }
+
+    fn write_vcode(&mut self, vcode: usize) {
+        self.write_code(VCODES[vcode], VCODE_LENS[vcode]);
+    }
+
+    fn write_switch(&mut self, set: usize) {
+        self.write_vcode(SWITCH_VCODE);
+        self.write_code(HCODES[set], HCODE_LENS[set]);
+    }
+
+    fn write_step_code(&mut self, idx: usize, limit: usize) {
+        self.write_bits((1 << idx) - 1, idx as u8);
+
+        if idx < limit {
+            self.write_bits(0, 1);
+        }
+    }
+
+    fn write_count(&mut self, count: usize) {
+        debug_assert!(count <= MAX_COUNT, "Count {} can't be encoded", count);
+
+        let count = count as u32;
+        let idx = (1..COUNT_ADDER.len())
+            .take_while(|i| count >= COUNT_ADDER[*i])
+            .last()
+            .unwrap_or(0);
+
+        self.write_step_code(idx, 4);
+        self.write_bits(count - COUNT_ADDER[idx], COUNT_BIT_LENS[idx]);
+    }
+
+    fn write_unicode(&mut self, code_point: i64, prev: i64) {
+        let diff = (code_point - prev).unsigned_abs() as u32;
+        let idx = (1..UNI_ADDER.len())
+            .take_while(|i| diff >= UNI_ADDER[*i])
+            .last()
+            .unwrap_or(0);
+
+        self.write_step_code(idx, 5);
+        self.write_bits((code_point < prev) as u32, 1);
+        self.write_bits(diff - UNI_ADDER[idx], UNI_BIT_LENS[idx]);
+    }
+}
+
+fn set_index(set: usize, c: u8) -> Option<usize> {
+    match c {
+        0 => None,
+        c => SETS[set].iter().position(|s| *s == c),
+    }
+}
+
+/// Finds the longest occurrence of the text at `i` within the window
+/// before it, returning its length and distance back
+fn find_back_reference(bytes: &[u8], i: usize) -> Option<(usize, usize)> {
+    if i < NICE_LEN || i + NICE_LEN > bytes.len() {
+        return None;
+    }
+
+    let mut longest: Option<(usize, usize)> = None;
+    let end = bytes.len().min(i + MAX_COUNT + NICE_LEN);
+
+    for j in (i.saturating_sub(BACK_REFERENCE_WINDOW)..=i - NICE_LEN).rev() {
+        let mut k = i;
+
+        while k < end && j + (k - i) < i && bytes[k] == bytes[j + (k - i)] {
+            k += 1;
+        }
+
+        // Don't end a match partway through a UTF-8 character
+        while k > i && k < bytes.len() && bytes[k] >> 6 == 2 {
+            k -= 1;
+        }
+
+        let len = k - i;
+
+        if len > NICE_LEN && !longest.is_some_and(|(l, _)| l >= len) {
+            longest = Some((len, i - j));
+        }
+    }
+
+    longest
+}
+
+/// Compresses text with Unishox2, or returns `None` if it contains control
+/// characters that can't be represented
+pub fn compress(text: &str) -> Option<Vec<u8>> {
+    let bytes = text.as_bytes();
+    let mut writer = BitWriter::default();
+    let mut state = ALPHA;
+    let mut prev_uni: i64 = 0;
+    let mut i = 0;
+
+    writer.write_bits(1, MAGIC_BIT_LEN as u8);
+
+    while i < bytes.len() {
+        if let Some((len, dist)) = find_back_reference(bytes, i) {
+            writer.write_switch(DICT);
+            writer.write_count(len - NICE_LEN);
+            writer.write_count(dist - (NICE_LEN - 1));
+            i += len;
+            continue;
+        }
+
+        let c = bytes[i];
+
+        if !c.is_ascii() {
+            let ch = text[i..].chars().next()?;
+
+            writer.write_switch(DELTA);
+            writer.write_unicode(ch as i64, prev_uni);
+            prev_uni = ch as i64;
+            i += ch.len_utf8();
+            continue;
+        }
+
+        // Runs of five or more of the same character, split if they're too
+        // long for a single count
+        let run = bytes[i..]
+            .iter()
+            .take_while(|b| **b == c)
+            .count()
+            .min(MAX_COUNT + 4);
+
+        if i > 0 && bytes[i - 1] == c && run >= 4 {
+            if state != NUM {
+                writer.write_switch(NUM);
+            }
+
+            writer.write_vcode(REPEAT_VCODE);
+            writer.write_count(run - 4);
+            i += run;
+            continue;
+        }
+
+        if c == b'\r' && bytes.get(i + 1) == Some(&b'\n') {
+            writer.write_switch(SYM);
+            writer.write_vcode(CRLF_VCODE);
+            i += 2;
+            continue;
+        }
+
+        if c.is_ascii_uppercase() {
+            if state != ALPHA {
+                writer.write_switch(ALPHA);
+                state = ALPHA;
+            }
+
+            writer.write_switch(ALPHA);
+            writer.write_vcode(set_index(ALPHA, c.to_ascii_lowercase())?);
+        } else if let Some(v) = set_index(NUM, c).filter(|_| state == NUM) {
+            writer.write_vcode(v);
+        } else if let Some(v) = set_index(ALPHA, c) {
+            if state != ALPHA {
+                writer.write_switch(ALPHA);
+                state = ALPHA;
+            }
+
+            writer.write_vcode(v);
+        } else if let Some(v) = set_index(NUM, c) {
+            writer.write_switch(NUM);
+            writer.write_vcode(v);
+
+            if c.is_ascii_digit() {
+                state = NUM;
+            }
+        } else {
+            writer.write_switch(SYM);
+            writer.write_vcode(set_index(SYM, c)?);
+        }
+
+        i += 1;
+    }
+
+    // The final byte is padded with zeros, which can't form a complete code
+    Some(writer.bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trips() {
+        for text in [
+            "",
+            "Hello World, meet at 10:30 near the \"old\" bridge!",
+            "ALL CAPS then lower, MiXeD 42 {json: [1, 2]}\r\nnext\tline\n",
+            "aaaaaaaaaa-----!!!!!!",
+            "repeat after me: repeat after me: repeat after me",
+            "Ünïcödé 🙂 テスト, done.",
+        ] {
+            let compressed = compress(text).unwrap();
+
+            assert_eq!(decompress(&compressed).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn decodes_hand_encoded_streams() {
+        // Encoded by hand from the default preset's code tables, not taken
+        // from the reference encoder. Unlike `compress`, each stream ends
+        // with a terminator and is padded with ones
+        let vectors: [(&[u8], &str); 3] = [
+            // magic 1, shift 00 00, h 1110110, i 1011, terminator 00 10 11111111
+            (&[0x87, 0x6b, 0x2f, 0xff], "Hi"),
+            // magic 1, a 1001, repeat 00 10 11111110, count 0 01, terminator
+            (&[0xc9, 0x7f, 0x12, 0xff], "aaaaaa"),
+            // magic 1, a-f, back reference 00 110, length 0 01, distance 0 10,
+            // terminator
+            (
+                &[0xcf, 0xae, 0x7a, 0x7f, 0x06, 0x28, 0xbf, 0xff],
+                "abcdefabcdef",
+            ),
+        ];
+
+        for (compressed, text) in vectors {
+            assert_eq!(decompress(compressed).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn long_runs_are_split_and_output_is_bounded() {
+        let text = format!("x{}", "a".repeat(MAX_COUNT + 100));
+        assert_eq!(decompress(&compress(&text).unwrap()).unwrap(), text);
+
+        let text = "a".repeat(MAX_DECOMPRESSED_LEN + 1);
+        assert_eq!(
+            decompress(&compress(&text).unwrap()),
+            Err(Unishox2Error::TooLong(MAX_DECOMPRESSED_LEN))
+        );
+    }
+
+    #[test]
+    fn english_text_shrinks() {
+        let text = "the quick brown fox jumps over the lazy dog near the river";
+
+        assert!(compress(text).unwrap().len() < text.len() * 3 / 4);
+        assert_eq!(compress("bell \u{7}"), None);
+    }
+}
@@ -0,0 +1,567 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, info, trace, warn};
+use meshtastic::api::{ConnectedStreamApi, StreamApi};
+use meshtastic::packet::PacketRouter;
+use meshtastic::protobufs;
+use meshtastic::ts::specta::{self, Type};
+use meshtastic::utils::stream::build_serial_stream;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tauri::async_runtime::{self, JoinHandle};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::device::backup::{diff_config_backups, ConfigFieldDiff, DeviceConfigBackup};
+use crate::device::helpers::get_current_time_u32;
+use crate::device::MeshDevice;
+use crate::ipc::{events, helpers::apply_device_bulk_config, DeviceBulkConfig};
+use crate::state::provisioning::ProvisioningStateInner;
+
+use self::router::ProvisioningRouter;
+
+pub mod router;
+
+pub const PORT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+pub const CONFIGURATION_TIMEOUT: Duration = Duration::from_secs(30);
+pub const REBOOT_DELAY: Duration = Duration::from_secs(15);
+pub const RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Firmware truncates short names longer than four characters
+pub const MAX_SHORT_NAME_LENGTH: usize = 4;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisioningProfile {
+    /// Configuration applied to every radio
+    pub config: DeviceBulkConfig,
+
+    /// Owner long name template, e.g. `TEAM-{n}` or `TEAM-{n:03}`
+    pub long_name_template: String,
+
+    /// Owner short name template, truncated to four characters
+    pub short_name_template: String,
+
+    /// Value of `{n}` for the first radio provisioned
+    pub start_index: u32,
+
+    pub baud_rate: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ProvisioningStatus {
+    InProgress,
+    AlreadyProvisioned,
+    Verified,
+    VerificationFailed,
+    Failed(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisioningReport {
+    pub port_name: String,
+    pub index: Option<u32>,
+    pub node_num: Option<u32>,
+    pub long_name: Option<String>,
+    pub short_name: Option<String>,
+    pub status: ProvisioningStatus,
+    pub mismatches: Vec<ConfigFieldDiff>, // fields that didn't match the profile after reboot
+    pub started_at: u32,                  // secs
+    pub finished_at: Option<u32>,         // secs
+}
+
+impl ProvisioningReport {
+    fn new(port_name: String) -> Self {
+        Self {
+            port_name,
+            index: None,
+            node_num: None,
+            long_name: None,
+            short_name: None,
+            status: ProvisioningStatus::InProgress,
+            mismatches: vec![],
+            started_at: get_current_time_u32(),
+            finished_at: None,
+        }
+    }
+
+    fn finish(mut self, status: ProvisioningStatus) -> Self {
+        self.status = status;
+        self.finished_at = Some(get_current_time_u32());
+        self
+    }
+}
+
+/// Renders an owner name template, replacing `{n}` with the radio's index.
+/// A zero-padded width can be given as `{n:03}`.
+///
+/// # Arguments
+///
+/// * `template` - The name template to render.
+/// * `index` - The index of the radio being provisioned.
+///
+/// # Returns
+///
+/// * `String` - The rendered name.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(render_name_template("TEAM-{n:02}", 7), "TEAM-07");
+/// ```
+pub fn render_name_template(template: &str, index: u32) -> String {
+    let mut rendered = String::new();
+    let mut remaining = template;
+
+    while let Some(start) = remaining.find("{n") {
+        rendered.push_str(&remaining[..start]);
+
+        let placeholder = &remaining[start..];
+        let end = match placeholder.find('}') {
+            Some(end) => end,
+            None => break,
+        };
+
+        let width = placeholder[2..end]
+            .strip_prefix(':')
+            .and_then(|w| w.parse::<usize>().ok());
+
+        match width {
+            Some(width) => rendered.push_str(&format!("{:0width$}", index, width = width)),
+            None if end == 2 => rendered.push_str(&index.to_string()),
+            None => rendered.push_str(&placeholder[..=end]),
+        }
+
+        remaining = &placeholder[end + 1..];
+    }
+
+    rendered.push_str(remaining);
+    rendered
+}
+
+struct ProvisioningContext {
+    app_handle: tauri::AppHandle,
+    profile: ProvisioningProfile,
+    next_index: AtomicU32,
+    provisioned_nodes: async_runtime::Mutex<HashSet<u32>>,
+    active_ports: async_runtime::Mutex<HashSet<String>>,
+    state: ProvisioningStateInner,
+}
+
+impl ProvisioningContext {
+    async fn publish_report(&self, report: &ProvisioningReport) {
+        {
+            let mut state_guard = self.state.lock().await;
+            let existing = state_guard
+                .reports
+                .iter_mut()
+                .find(|r| r.port_name == report.port_name && r.started_at == report.started_at);
+
+            match existing {
+                Some(r) => *r = report.clone(),
+                None => state_guard.reports.push(report.clone()),
+            }
+        }
+
+        if let Err(e) = events::dispatch_provisioning_report(&self.app_handle, report) {
+            warn!("Failed to dispatch provisioning report: {}", e);
+        }
+    }
+}
+
+/// Lists the names of all USB serial ports, excluding built-in ports
+/// that can't be Meshtastic radios.
+pub fn list_usb_serial_ports() -> Result<HashSet<String>, String> {
+    let ports = tokio_serial::available_ports()
+        .map_err(|e| format!("Error getting available serial ports: {:?}", e))?
+        .into_iter()
+        .filter(|port| matches!(port.port_type, tokio_serial::SerialPortType::UsbPort(_)))
+        .map(|port| port.port_name)
+        .collect();
+
+    Ok(ports)
+}
+
+/// Spawns a task that polls for newly attached USB serial ports and
+/// provisions each radio that appears with the given profile. Ports
+/// in `ignored_ports` are assumed to be attached already and are skipped.
+pub fn spawn_provisioning_watcher(
+    app_handle: tauri::AppHandle,
+    profile: ProvisioningProfile,
+    ignored_ports: HashSet<String>,
+    state: ProvisioningStateInner,
+) -> JoinHandle<()> {
+    let context = Arc::new(ProvisioningContext {
+        app_handle,
+        next_index: AtomicU32::new(profile.start_index),
+        profile,
+        provisioned_nodes: async_runtime::Mutex::new(HashSet::new()),
+        active_ports: async_runtime::Mutex::new(HashSet::new()),
+        state,
+    });
+
+    async_runtime::spawn(async move {
+        info!("Starting provisioning watcher");
+
+        let mut known_ports = ignored_ports;
+
+        loop {
+            match list_usb_serial_ports() {
+                Ok(ports) => {
+                    // Forget ports that have been unplugged so a new radio on the
+                    // same port is provisioned, unless the port disappeared because
+                    // its radio is rebooting mid-provisioning
+                    {
+                        let active_ports = context.active_ports.lock().await;
+                        known_ports.retain(|p| ports.contains(p) || active_ports.contains(p));
+                    }
+
+                    for port_name in ports {
+                        if !known_ports.insert(port_name.clone()) {
+                            continue;
+                        }
+
+                        debug!("Detected new serial port \"{}\"", port_name);
+
+                        let context = context.clone();
+                        async_runtime::spawn(async move {
+                            provision_port(context, port_name).await;
+                        });
+                    }
+                }
+                Err(e) => warn!("{}", e),
+            }
+
+            tokio::time::sleep(PORT_POLL_INTERVAL).await;
+        }
+    })
+}
+
+async fn provision_port(context: Arc<ProvisioningContext>, port_name: String) {
+    context.active_ports.lock().await.insert(port_name.clone());
+
+    let report = provision_radio(&context, port_name.clone()).await;
+
+    info!(
+        "Finished provisioning radio on port \"{}\": {:?}",
+        port_name, report.status
+    );
+
+    context.publish_report(&report).await;
+    context.active_ports.lock().await.remove(&port_name);
+}
+
+/// Provisions the radio on a single port. A radio that was already
+/// provisioned in this session under a different port name is reported
+/// as `AlreadyProvisioned` and left untouched.
+async fn provision_radio(context: &ProvisioningContext, port_name: String) -> ProvisioningReport {
+    let profile = &context.profile;
+    let mut report = ProvisioningReport::new(port_name.clone());
+
+    let (connection, _decoded_listener, router) =
+        match connect_and_configure(&port_name, profile.baud_rate).await {
+            Ok(c) => c,
+            Err(e) => return report.finish(ProvisioningStatus::Failed(e)),
+        };
+
+    let node_num = router.device.my_node_info.my_node_num;
+    report.node_num = Some(node_num);
+
+    if !context.provisioned_nodes.lock().await.insert(node_num) {
+        debug!(
+            "Radio {} on port \"{}\" already provisioned, skipping",
+            node_num, port_name
+        );
+        disconnect(connection).await;
+        return report.finish(ProvisioningStatus::AlreadyProvisioned);
+    }
+
+    let report = configure_radio(context, port_name, report, connection, router).await;
+
+    // Only verified radios count as provisioned, so a radio that failed
+    // is provisioned again when it is reattached
+    if !matches!(report.status, ProvisioningStatus::Verified) {
+        context.provisioned_nodes.lock().await.remove(&node_num);
+    }
+
+    report
+}
+
+async fn configure_radio(
+    context: &ProvisioningContext,
+    port_name: String,
+    mut report: ProvisioningReport,
+    mut connection: ConnectedStreamApi,
+    mut router: ProvisioningRouter,
+) -> ProvisioningReport {
+    let profile = &context.profile;
+    let node_num = router.device.my_node_info.my_node_num;
+
+    let index = context.next_index.fetch_add(1, Ordering::SeqCst);
+    let owner = build_owner(&router.device, profile, index);
+
+    report.index = Some(index);
+    report.long_name = Some(owner.long_name.clone());
+    report.short_name = Some(owner.short_name.clone());
+
+    context.publish_report(&report).await;
+
+    info!(
+        "Provisioning radio {} on port \"{}\" as \"{}\"",
+        node_num, port_name, owner.long_name
+    );
+
+    if let Some(Err(e)) = profile
+        .config
+        .module
+        .as_ref()
+        .map(|m| router.device.compatibility.require_module_config(m))
+    {
+        disconnect(connection).await;
+        return report.finish(ProvisioningStatus::Failed(e));
+    }
+
+    let apply_result = apply_device_bulk_config(
+        &mut connection,
+        &mut router,
+        Some(owner.clone()),
+        profile.config.clone(),
+    )
+    .await;
+
+    disconnect(connection).await;
+
+    if let Err(e) = apply_result {
+        return report.finish(ProvisioningStatus::Failed(e.to_string()));
+    }
+
+    // Radio reboots once the configuration transaction is committed
+
+    tokio::time::sleep(REBOOT_DELAY).await;
+
+    let mut reconnect_result = Err("No reconnection attempted".to_string());
+
+    for attempt in 1..=RECONNECT_ATTEMPTS {
+        trace!(
+            "Reconnecting to port \"{}\" for verification, attempt {}",
+            port_name,
+            attempt
+        );
+
+        reconnect_result = connect_and_configure(&port_name, profile.baud_rate).await;
+
+        if reconnect_result.is_ok() {
+            break;
+        }
+
+        tokio::time::sleep(PORT_POLL_INTERVAL).await;
+    }
+
+    let (connection, _decoded_listener, router) = match reconnect_result {
+        Ok(c) => c,
+        Err(e) => {
+            return report.finish(ProvisioningStatus::Failed(format!(
+                "Failed to reconnect for verification: {}",
+                e
+            )))
+        }
+    };
+
+    disconnect(connection).await;
+
+    match verify_provisioned_device(&router.device, profile, &owner) {
+        Ok(mismatches) if mismatches.is_empty() => report.finish(ProvisioningStatus::Verified),
+        Ok(mismatches) => {
+            report.mismatches = mismatches;
+            report.finish(ProvisioningStatus::VerificationFailed)
+        }
+        Err(e) => report.finish(ProvisioningStatus::Failed(e)),
+    }
+}
+
+async fn connect_and_configure(
+    port_name: &str,
+    baud_rate: Option<u32>,
+) -> Result<
+    (
+        ConnectedStreamApi,
+        UnboundedReceiver<protobufs::FromRadio>,
+        ProvisioningRouter,
+    ),
+    String,
+> {
+    let stream = build_serial_stream(port_name.to_string(), baud_rate, Some(true), Some(false))
+        .map_err(|e| e.to_string())?;
+
+    let mut router = ProvisioningRouter::new();
+
+    let stream_api = StreamApi::new();
+    let (mut decoded_listener, stream_api) = stream_api.connect(stream).await;
+
+    let connection = stream_api
+        .configure(router.device.config_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let deadline = tokio::time::Instant::now() + CONFIGURATION_TIMEOUT;
+
+    while !router.is_configured() {
+        let packet = match tokio::time::timeout_at(deadline, decoded_listener.recv()).await {
+            Ok(Some(packet)) => packet,
+            Ok(None) => {
+                disconnect(connection).await;
+                return Err("Connection closed during configuration".into());
+            }
+            Err(_) => {
+                disconnect(connection).await;
+                return Err("Configuration timed out. Is this a Meshtastic device?".into());
+            }
+        };
+
+        if let Err(e) = router.handle_packet_from_radio(packet) {
+            trace!("{}", e);
+        }
+    }
+
+    Ok((connection, decoded_listener, router))
+}
+
+async fn disconnect(connection: ConnectedStreamApi) {
+    if let Err(e) = connection.disconnect().await {
+        debug!("Failed to disconnect from device: {:?}", e);
+    }
+}
+
+fn build_owner(device: &MeshDevice, profile: &ProvisioningProfile, index: u32) -> protobufs::User {
+    let current_owner = device
+        .nodes
+        .get(&device.my_node_info.my_node_num)
+        .and_then(|node| node.user.clone())
+        .unwrap_or_default();
+
+    protobufs::User {
+        long_name: render_name_template(&profile.long_name_template, index),
+        short_name: render_name_template(&profile.short_name_template, index)
+            .chars()
+            .take(MAX_SHORT_NAME_LENGTH)
+            .collect(),
+        ..current_owner
+    }
+}
+
+/// Overlays each configuration section set in `overlay` onto `base`
+fn overlay_sections<T>(base: &T, overlay: &T) -> Result<T, serde_json::Error>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut base_value = serde_json::to_value(base)?;
+
+    if let (Some(base_map), serde_json::Value::Object(overlay_map)) =
+        (base_value.as_object_mut(), serde_json::to_value(overlay)?)
+    {
+        for (key, value) in overlay_map {
+            if value.is_object() {
+                base_map.insert(key, value);
+            }
+        }
+    }
+
+    serde_json::from_value(base_value)
+}
+
+/// Compares the configuration read back from a rebooted radio against
+/// the provisioning profile, returning every field that doesn't match.
+fn verify_provisioned_device(
+    device: &MeshDevice,
+    profile: &ProvisioningProfile,
+    owner: &protobufs::User,
+) -> Result<Vec<ConfigFieldDiff>, String> {
+    let actual = DeviceConfigBackup::from_device(device);
+    let mut expected = actual.clone();
+
+    if let Some(radio) = &profile.config.radio {
+        expected.config = overlay_sections(&expected.config, radio).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(module) = &profile.config.module {
+        expected.module_config =
+            overlay_sections(&expected.module_config, module).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(channels) = &profile.config.channels {
+        for channel in channels {
+            match expected
+                .channels
+                .iter_mut()
+                .find(|c| c.index == channel.index)
+            {
+                Some(c) => *c = channel.clone(),
+                None => expected.channels.push(channel.clone()),
+            }
+        }
+
+        expected.channels.sort_by_key(|channel| channel.index);
+    }
+
+    // A radio that came back without its owner must not pass verification
+    expected.owner = Some(owner.clone());
+
+    diff_config_backups(&actual, &expected).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_index_template() {
+        assert_eq!(render_name_template("TEAM-{n}", 12), "TEAM-12");
+    }
+
+    #[test]
+    fn padded_index_template() {
+        assert_eq!(render_name_template("TEAM-{n:03}", 7), "TEAM-007");
+        assert_eq!(render_name_template("T{n:02}", 123), "T123");
+    }
+
+    #[test]
+    fn template_without_placeholder() {
+        assert_eq!(render_name_template("BASE", 1), "BASE");
+    }
+
+    #[test]
+    fn unknown_placeholder_preserved() {
+        assert_eq!(render_name_template("{name}-{n}", 3), "{name}-3");
+    }
+
+    #[test]
+    fn multiple_placeholders() {
+        assert_eq!(render_name_template("{n}/{n:02}", 4), "4/04");
+    }
+
+    #[test]
+    fn missing_owner_fails_verification() {
+        let profile = ProvisioningProfile {
+            config: DeviceBulkConfig {
+                radio: None,
+                module: None,
+                channels: None,
+            },
+            long_name_template: "TEAM-{n}".into(),
+            short_name_template: "T{n}".into(),
+            start_index: 1,
+            baud_rate: None,
+        };
+
+        let device = MeshDevice::new();
+        let owner = build_owner(&device, &profile, 1);
+
+        let mismatches =
+            verify_provisioned_device(&device, &profile, &owner).expect("verification should run");
+
+        assert!(!mismatches.is_empty());
+    }
+}
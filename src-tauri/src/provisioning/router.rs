@@ -0,0 +1,87 @@
+use log::trace;
+use meshtastic::packet::PacketRouter;
+use meshtastic::protobufs;
+use meshtastic::types::NodeId;
+
+use crate::device::{helpers::get_current_time_u32, MeshChannel, MeshDevice, SerialDeviceStatus};
+use crate::packet_api::handlers::DeviceUpdateError;
+
+/// A minimal packet router used while provisioning. Unlike `MeshPacketApi`,
+/// it only records the configuration reported by the radio and never
+/// dispatches events, so radios being provisioned don't appear in the UI.
+pub struct ProvisioningRouter {
+    pub device: MeshDevice,
+}
+
+impl ProvisioningRouter {
+    pub fn new() -> Self {
+        Self {
+            device: MeshDevice::new(),
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.device.status == SerialDeviceStatus::Configured
+    }
+}
+
+impl PacketRouter<(), DeviceUpdateError> for ProvisioningRouter {
+    fn source_node_id(&self) -> NodeId {
+        NodeId::new(self.device.my_node_info.my_node_num)
+    }
+
+    fn handle_packet_from_radio(
+        &mut self,
+        packet: protobufs::FromRadio,
+    ) -> Result<(), DeviceUpdateError> {
+        let variant = match packet.payload_variant {
+            Some(v) => v,
+            None => {
+                return Err(DeviceUpdateError::GeneralFailure(
+                    "No payload variant".into(),
+                ))
+            }
+        };
+
+        match variant {
+            protobufs::from_radio::PayloadVariant::Channel(channel) => {
+                self.device.add_channel(MeshChannel {
+                    config: channel,
+                    last_interaction: get_current_time_u32(),
+                    messages: vec![],
+                });
+            }
+            protobufs::from_radio::PayloadVariant::Config(config) => {
+                self.device.set_config(config);
+            }
+            protobufs::from_radio::PayloadVariant::ModuleConfig(module_config) => {
+                self.device.set_module_config(module_config);
+            }
+            protobufs::from_radio::PayloadVariant::MyInfo(my_node_info) => {
+                self.device.set_my_node_info(my_node_info);
+            }
+            protobufs::from_radio::PayloadVariant::NodeInfo(node_info) => {
+                self.device.add_node_info(node_info);
+            }
+            protobufs::from_radio::PayloadVariant::ConfigCompleteId(config_id) => {
+                if config_id == self.device.config_id {
+                    self.device.set_status(SerialDeviceStatus::Configured);
+                }
+            }
+            variant => {
+                trace!("Ignoring radio message while provisioning: {:?}", variant);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_mesh_packet(
+        &mut self,
+        packet: protobufs::MeshPacket,
+    ) -> Result<(), DeviceUpdateError> {
+        trace!("Ignoring mesh packet while provisioning: {:?}", packet);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::async_runtime;
+
+use crate::analysis::calibration::PropagationCalibration;
+use crate::analysis::propagation::PropagationModel;
+
+use super::DeviceKey;
+
+/// Propagation calibrations by device, kept across reconnects since the
+/// terrain they were fitted to doesn't change with the connection
+#[derive(Default)]
+pub struct AnalysisSession {
+    pub calibrations: HashMap<DeviceKey, PropagationCalibration>,
+}
+
+impl AnalysisSession {
+    /// Model fitted to the device's mesh if it has been calibrated, textbook
+    /// values otherwise
+    pub fn propagation_model(&self, device_key: &DeviceKey) -> PropagationModel {
+        self.calibrations
+            .get(device_key)
+            .map(|c| c.model)
+            .unwrap_or_default()
+    }
+}
+
+pub type AnalysisStateInner = Arc<async_runtime::Mutex<AnalysisSession>>;
+
+pub struct AnalysisState {
+    pub inner: AnalysisStateInner,
+}
+
+impl AnalysisState {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(async_runtime::Mutex::new(AnalysisSession::default())),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use crate::ipc::dispatcher::EventDispatcher;
+
+pub struct EventDispatcherState {
+    pub inner: EventDispatcher,
+}
+
+impl EventDispatcherState {
+    pub fn new(app_handle: tauri::AppHandle) -> Self {
+        Self {
+            inner: EventDispatcher::new(app_handle),
+        }
+    }
+}
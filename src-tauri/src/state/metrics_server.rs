@@ -0,0 +1,18 @@
+use std::sync::Arc;
+use tauri::async_runtime;
+
+use crate::metrics::server::MetricsServer;
+
+pub type MetricsServerStateInner = Arc<async_runtime::Mutex<Option<MetricsServer>>>;
+
+pub struct MetricsServerState {
+    pub inner: MetricsServerStateInner,
+}
+
+impl MetricsServerState {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(async_runtime::Mutex::new(None)),
+        }
+    }
+}
@@ -1,6 +1,10 @@
+pub mod analysis;
 pub mod autoconnect;
+pub mod dispatcher;
 pub mod graph;
 pub mod mesh_devices;
+pub mod metrics_server;
+pub mod provisioning;
 pub mod radio_connections;
 
 pub type DeviceKey = String;
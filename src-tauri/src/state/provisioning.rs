@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use tauri::async_runtime::{self, JoinHandle};
+
+use crate::provisioning::ProvisioningReport;
+
+#[derive(Default)]
+pub struct ProvisioningSession {
+    pub watcher_handle: Option<JoinHandle<()>>,
+    pub reports: Vec<ProvisioningReport>,
+}
+
+pub type ProvisioningStateInner = Arc<async_runtime::Mutex<ProvisioningSession>>;
+
+pub struct ProvisioningState {
+    pub inner: ProvisioningStateInner,
+}
+
+impl ProvisioningState {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(async_runtime::Mutex::new(ProvisioningSession::default())),
+        }
+    }
+}
@@ -16,6 +16,7 @@ import { ApplicationSettingsPage } from "@components/pages/config/ApplicationSet
 import { ChannelConfigPage } from "@components/pages/config/ChannelConfigPage";
 import { RadioConfigPage } from "@components/pages/config/RadioConfigPage";
 import { GraphDebuggerPage } from "@components/pages/GraphDebuggerPage";
+import { DeviceLogPage } from "@components/pages/DeviceLogPage";
 
 import { AppRoutes } from "@utils/routing";
 
@@ -84,6 +85,8 @@ export const App = () => {
             element={<GraphDebuggerPage />}
           />
 
+          <Route path={AppRoutes.DEVICE_LOGS} element={<DeviceLogPage />} />
+
           <Route path="*" element={<FallbackPage />} />
         </Route>
       </Routes>
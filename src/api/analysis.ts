@@ -0,0 +1,45 @@
+import { invoke } from "@tauri-apps/api";
+import { CoverageReport } from "@app/types/analysis";
+import {
+  app_analysis_calibration_PropagationCalibration,
+  app_analysis_coverage_CoverageOptions,
+  app_analysis_relay_RelayPlacementOptions,
+  app_analysis_relay_RelayPlacementReport,
+} from "@bindings/index";
+import { DeviceKey } from "@utils/connections";
+
+export const recommendRelayPlacement = async (
+  deviceKey: DeviceKey,
+  options?: app_analysis_relay_RelayPlacementOptions,
+) => {
+  const response = (await invoke("recommend_relay_placement", {
+    deviceKey: deviceKey,
+    options: options ?? null,
+  })) as app_analysis_relay_RelayPlacementReport;
+
+  return response;
+};
+
+export const estimateCoverage = async (
+  deviceKey: DeviceKey,
+  options?: app_analysis_coverage_CoverageOptions,
+) => {
+  const response = (await invoke("estimate_coverage", {
+    deviceKey: deviceKey,
+    options: options ?? null,
+  })) as CoverageReport;
+
+  return response;
+};
+
+export const calibratePropagationModel = async (deviceKey: DeviceKey) => {
+  const response = (await invoke("calibrate_propagation_model", {
+    deviceKey: deviceKey,
+  })) as app_analysis_calibration_PropagationCalibration;
+
+  return response;
+};
+
+export const resetPropagationModel = async (deviceKey: DeviceKey) => {
+  await invoke("reset_propagation_model", { deviceKey: deviceKey });
+};
@@ -1,9 +1,15 @@
 import { listen } from "@tauri-apps/api/event";
-import { useDispatch } from "react-redux";
+import { useDispatch, useStore } from "react-redux";
 import { warn } from "tauri-plugin-log-api";
 
+import * as backendMeshApi from "@api/mesh";
+import type { RootState } from "@app/store";
 import { MeshGraph } from "@app/types/graph";
-import { app_device_MeshDevice } from "@bindings/index";
+import {
+  app_device_MeshDevice,
+  app_ipc_DeltaBatch,
+  app_ipc_DeltaPayload,
+} from "@bindings/index";
 
 import { connectionSliceActions } from "@features/connection/slice";
 import { deviceSliceActions } from "@features/device/slice";
@@ -115,3 +121,102 @@ export const useCreateGraphUpdateChannel = () => {
 
   return createChannel;
 };
+
+export const useCreateDeltaEventChannel = () => {
+  const dispatch = useDispatch();
+  const store = useStore<RootState>();
+
+  const createChannel = async () => {
+    let lastSequence: number | null = null;
+    let resyncBuffer: app_ipc_DeltaBatch[] | null = null;
+
+    const applyDelta = (delta: app_ipc_DeltaPayload) => {
+      switch (delta.event) {
+        case "node_updated":
+          dispatch(deviceSliceActions.upsertNode(delta.payload));
+          break;
+        case "message_added":
+          dispatch(deviceSliceActions.addMessage(delta.payload));
+          break;
+        case "message_state_changed":
+          dispatch(deviceSliceActions.setMessageState(delta.payload));
+          break;
+        case "waypoint_added":
+          dispatch(deviceSliceActions.addWaypoint(delta.payload));
+          break;
+        case "graph_node_updated":
+          dispatch(graphSliceActions.upsertNode(delta.payload));
+          break;
+        case "edge_updated":
+          dispatch(graphSliceActions.upsertEdge(delta.payload));
+          break;
+        case "node_removed":
+          dispatch(graphSliceActions.removeNode(delta.payload));
+          break;
+        case "outbox_updated":
+          dispatch(deviceSliceActions.setOutboxStatus(delta.payload));
+          break;
+        case "fragment_progress":
+          dispatch(deviceSliceActions.setFragmentProgress(delta.payload));
+          break;
+        case "file_transfer_updated":
+          dispatch(deviceSliceActions.upsertFileTransfer(delta.payload));
+          break;
+        case "detection_event":
+          dispatch(deviceSliceActions.addDetectionEvent(delta.payload));
+          break;
+      }
+    };
+
+    const resync = async (deviceKey: DeviceKey | null) => {
+      const key = deviceKey ?? store.getState().devices.primaryDeviceKey;
+      if (!key) return;
+
+      warn(`Missed delta event after sequence ${lastSequence}, resyncing`);
+      resyncBuffer = [];
+
+      try {
+        const snapshot = await backendMeshApi.resyncState(key);
+
+        dispatch(deviceSliceActions.setDevice(snapshot.device));
+        dispatch(graphSliceActions.setGraph(snapshot.graph));
+        lastSequence = snapshot.sequence;
+      } finally {
+        const buffered = resyncBuffer;
+        resyncBuffer = null;
+
+        for (const batch of buffered) {
+          handleBatch(batch);
+        }
+      }
+    };
+
+    const handleBatch = (batch: app_ipc_DeltaBatch) => {
+      if (resyncBuffer) {
+        resyncBuffer.push(batch);
+        return;
+      }
+
+      for (const event of batch.events) {
+        // Already reflected in the most recent resync snapshot
+        if (lastSequence !== null && event.sequence <= lastSequence) continue;
+
+        if (lastSequence !== null && event.sequence !== lastSequence + 1) {
+          resync(batch.deviceKey);
+          return;
+        }
+
+        applyDelta(event.delta);
+        lastSequence = event.sequence;
+      }
+    };
+
+    const unlisten = await listen<app_ipc_DeltaBatch>("delta_batch", (event) =>
+      handleBatch(event.payload),
+    );
+
+    return unlisten;
+  };
+
+  return createChannel;
+};
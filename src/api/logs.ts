@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api";
+import {
+  app_packet_api_device_logs_DeviceLogEntry,
+  app_packet_api_device_logs_DeviceLogFilter,
+} from "@bindings/index";
+import { DeviceKey } from "@utils/connections";
+
+export const getDeviceLogs = async (
+  deviceKey: DeviceKey,
+  filter?: app_packet_api_device_logs_DeviceLogFilter,
+) => {
+  const response = (await invoke("get_device_logs", {
+    deviceKey: deviceKey,
+    filter: filter ?? null,
+  })) as app_packet_api_device_logs_DeviceLogEntry[];
+
+  return response;
+};
+
+export const clearDeviceLogs = async (deviceKey: DeviceKey) => {
+  await invoke("clear_device_logs", { deviceKey: deviceKey });
+};
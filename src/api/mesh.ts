@@ -1,16 +1,26 @@
 import { invoke } from "@tauri-apps/api";
-import { app_device_NormalizedWaypoint } from "@bindings/index";
+import {
+  app_device_NormalizedWaypoint,
+  app_packet_api_outbox_MessagePriority,
+  app_packet_api_outbox_OutboxPolicy,
+  app_packet_api_outbox_OutboxStatus,
+} from "@bindings/index";
+import { ResyncSnapshot } from "@app/types/events";
 import { DeviceKey } from "@utils/connections";
 
 export const sendText = async (
   deviceKey: DeviceKey,
   deviceChannel: number,
   text: string,
+  priority?: app_packet_api_outbox_MessagePriority,
+  compress?: boolean,
 ) => {
   const response = (await invoke("send_text", {
     deviceKey: deviceKey,
     channel: deviceChannel,
     text: text,
+    priority: priority ?? null,
+    compress: compress ?? null,
   })) as undefined;
 
   return response;
@@ -20,11 +30,13 @@ export const sendWaypoint = async (
   deviceKey: DeviceKey,
   deviceChannel: number,
   waypoint: app_device_NormalizedWaypoint,
+  priority?: app_packet_api_outbox_MessagePriority,
 ) => {
   const response = (await invoke("send_waypoint", {
     deviceKey: deviceKey,
     channel: deviceChannel,
     waypoint: waypoint,
+    priority: priority ?? null,
   })) as undefined;
 
   return response;
@@ -41,3 +53,43 @@ export const deleteWaypoint = async (
 
   return response;
 };
+
+export const getOutboxStatus = async (deviceKey: DeviceKey) => {
+  const response = (await invoke("get_outbox_status", {
+    deviceKey: deviceKey,
+  })) as app_packet_api_outbox_OutboxStatus;
+
+  return response;
+};
+
+export const setOutboxPolicy = async (
+  deviceKey: DeviceKey,
+  policy: app_packet_api_outbox_OutboxPolicy,
+) => {
+  const response = (await invoke("set_outbox_policy", {
+    deviceKey: deviceKey,
+    policy: policy,
+  })) as app_packet_api_outbox_OutboxStatus;
+
+  return response;
+};
+
+export const cancelQueuedMessage = async (
+  deviceKey: DeviceKey,
+  messageId: number,
+) => {
+  const response = (await invoke("cancel_queued_message", {
+    deviceKey: deviceKey,
+    messageId: messageId,
+  })) as undefined;
+
+  return response;
+};
+
+export const resyncState = async (deviceKey: DeviceKey) => {
+  const response = (await invoke("resync_state", {
+    deviceKey: deviceKey,
+  })) as ResyncSnapshot;
+
+  return response;
+};
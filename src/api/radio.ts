@@ -58,3 +58,17 @@ export const updateDeviceConfigBulk = async (
 
   return response;
 };
+
+export const updateNeighborInfoConfig = async (
+  deviceKey: DeviceKey,
+  enabled: boolean,
+  updateInterval: number,
+) => {
+  const response = (await invoke("update_neighbor_info_config", {
+    deviceKey: deviceKey,
+    enabled: enabled,
+    updateInterval: updateInterval,
+  })) as undefined;
+
+  return response;
+};
@@ -0,0 +1,55 @@
+import { invoke } from "@tauri-apps/api";
+import { app_packet_api_transfers_FileTransferStatus } from "@bindings/index";
+import { DeviceKey } from "@utils/connections";
+
+export const offerFileTransfer = async (
+  deviceKey: DeviceKey,
+  to: number,
+  channel: number,
+  fileName: string,
+  data: number[],
+  compress?: boolean,
+) => {
+  const response = (await invoke("offer_file_transfer", {
+    deviceKey: deviceKey,
+    to: to,
+    channel: channel,
+    fileName: fileName,
+    data: data,
+    compress: compress ?? null,
+  })) as app_packet_api_transfers_FileTransferStatus;
+
+  return response;
+};
+
+export const acceptFileTransfer = async (
+  deviceKey: DeviceKey,
+  transferId: number,
+) => {
+  const response = (await invoke("accept_file_transfer", {
+    deviceKey: deviceKey,
+    transferId: transferId,
+  })) as app_packet_api_transfers_FileTransferStatus;
+
+  return response;
+};
+
+export const cancelFileTransfer = async (
+  deviceKey: DeviceKey,
+  transferId: number,
+) => {
+  const response = (await invoke("cancel_file_transfer", {
+    deviceKey: deviceKey,
+    transferId: transferId,
+  })) as undefined;
+
+  return response;
+};
+
+export const getFileTransfers = async (deviceKey: DeviceKey) => {
+  const response = (await invoke("get_file_transfers", {
+    deviceKey: deviceKey,
+  })) as app_packet_api_transfers_FileTransferStatus[];
+
+  return response;
+};
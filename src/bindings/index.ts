@@ -102,7 +102,7 @@ export type meshtastic_protobufs_XModem = { control: number; seq: number; crc16:
  */
 export type meshtastic_protobufs_Telemetry = { time: number; variant: meshtastic_protobufs_telemetry_Variant | null }
 
-export type meshtastic_protobufs_config_lo_ra_config_RegionCode = "unset" | "us" | "eu433" | "eu868" | "cn" | "jp" | "anz" | "kr" | "tw" | "ru" | "in" | "nz865" | "th" | "lora24" | "ua433" | "ua868"
+export type meshtastic_protobufs_config_lo_ra_config_RegionCode = "unset" | "us" | "eu433" | "eu868" | "cn" | "jp" | "anz" | "kr" | "tw" | "ru" | "in" | "nz865" | "th" | "lora24" | "ua433" | "ua868" | "my433" | "my919" | "sg923"
 
 /**
  * 
@@ -114,7 +114,7 @@ export type meshtastic_protobufs_config_lo_ra_config_RegionCode = "unset" | "us"
  */
 export type meshtastic_protobufs_LogRecord = { message: string; time: number; source: string; level: number }
 
-export type app_device_MeshNode = { nodeNum: number; lastHeard: app_device_LastHeardMetadata | null; user: meshtastic_protobufs_User | null; deviceMetrics: app_device_MeshNodeDeviceMetrics[]; environmentMetrics: app_device_MeshNodeEnvironmentMetrics[]; positionMetrics: app_device_NormalizedPosition[] }
+export type app_device_MeshNode = { nodeNum: number; lastHeard: app_device_LastHeardMetadata | null; user: meshtastic_protobufs_User | null; deviceMetrics: app_device_MeshNodeDeviceMetrics[]; environmentMetrics: app_device_MeshNodeEnvironmentMetrics[]; airQualityMetrics: app_device_MeshNodeAirQualityMetrics[]; powerMetrics: app_device_MeshNodePowerMetrics[]; paxcountMetrics: app_device_MeshNodePaxcountMetrics[]; positionMetrics: app_device_NormalizedPosition[] }
 
 export type app_device_WaypointPacket = { packet: meshtastic_protobufs_MeshPacket; data: app_device_NormalizedWaypoint }
 
@@ -122,7 +122,7 @@ export type app_device_WaypointPacket = { packet: meshtastic_protobufs_MeshPacke
  * 
  * TODO: REPLACE
  */
-export type meshtastic_protobufs_admin_message_ModuleConfigType = "mqttConfig" | "serialConfig" | "extnotifConfig" | "storeforwardConfig" | "rangetestConfig" | "telemetryConfig" | "cannedmsgConfig" | "audioConfig" | "remotehardwareConfig" | "neighborinfoConfig" | "ambientlightingConfig" | "detectionsensorConfig"
+export type meshtastic_protobufs_admin_message_ModuleConfigType = "mqttConfig" | "serialConfig" | "extnotifConfig" | "storeforwardConfig" | "rangetestConfig" | "telemetryConfig" | "cannedmsgConfig" | "audioConfig" | "remotehardwareConfig" | "neighborinfoConfig" | "ambientlightingConfig" | "detectionsensorConfig" | "paxcounterConfig"
 
 /**
  * 
@@ -134,7 +134,7 @@ export type meshtastic_protobufs_module_config_serial_config_SerialMode = "defau
  * 
  * Configuration for both device and environment metrics
  */
-export type meshtastic_protobufs_module_config_TelemetryConfig = { deviceUpdateInterval: number; environmentUpdateInterval: number; environmentMeasurementEnabled: boolean; environmentScreenEnabled: boolean; environmentDisplayFahrenheit: boolean; airQualityEnabled: boolean; airQualityInterval: number }
+export type meshtastic_protobufs_module_config_TelemetryConfig = { deviceUpdateInterval: number; environmentUpdateInterval: number; environmentMeasurementEnabled: boolean; environmentScreenEnabled: boolean; environmentDisplayFahrenheit: boolean; airQualityEnabled: boolean; airQualityInterval: number; powerMeasurementEnabled: boolean; powerUpdateInterval: number; powerScreenEnabled: boolean }
 
 /**
  * 
@@ -172,11 +172,11 @@ export type meshtastic_protobufs_AirQualityMetrics = { pm10Standard: number; pm2
  */
 export type meshtastic_protobufs_DeviceProfile = { longName: string | null; shortName: string | null; channelUrl: string | null; config: meshtastic_protobufs_LocalConfig | null; moduleConfig: meshtastic_protobufs_LocalModuleConfig | null }
 
-export type meshtastic_protobufs_LocalModuleConfig = { mqtt: meshtastic_protobufs_module_config_MqttConfig | null; serial: meshtastic_protobufs_module_config_SerialConfig | null; externalNotification: meshtastic_protobufs_module_config_ExternalNotificationConfig | null; storeForward: meshtastic_protobufs_module_config_StoreForwardConfig | null; rangeTest: meshtastic_protobufs_module_config_RangeTestConfig | null; telemetry: meshtastic_protobufs_module_config_TelemetryConfig | null; cannedMessage: meshtastic_protobufs_module_config_CannedMessageConfig | null; audio: meshtastic_protobufs_module_config_AudioConfig | null; remoteHardware: meshtastic_protobufs_module_config_RemoteHardwareConfig | null; neighborInfo: meshtastic_protobufs_module_config_NeighborInfoConfig | null; ambientLighting: meshtastic_protobufs_module_config_AmbientLightingConfig | null; detectionSensor: meshtastic_protobufs_module_config_DetectionSensorConfig | null; version: number }
+export type meshtastic_protobufs_LocalModuleConfig = { mqtt: meshtastic_protobufs_module_config_MqttConfig | null; serial: meshtastic_protobufs_module_config_SerialConfig | null; externalNotification: meshtastic_protobufs_module_config_ExternalNotificationConfig | null; storeForward: meshtastic_protobufs_module_config_StoreForwardConfig | null; rangeTest: meshtastic_protobufs_module_config_RangeTestConfig | null; telemetry: meshtastic_protobufs_module_config_TelemetryConfig | null; cannedMessage: meshtastic_protobufs_module_config_CannedMessageConfig | null; audio: meshtastic_protobufs_module_config_AudioConfig | null; remoteHardware: meshtastic_protobufs_module_config_RemoteHardwareConfig | null; neighborInfo: meshtastic_protobufs_module_config_NeighborInfoConfig | null; ambientLighting: meshtastic_protobufs_module_config_AmbientLightingConfig | null; detectionSensor: meshtastic_protobufs_module_config_DetectionSensorConfig | null; paxcounter: meshtastic_protobufs_module_config_PaxcounterConfig | null; version: number }
 
 export type app_device_NormalizedNodeInfo = { num: number; user: meshtastic_protobufs_User | null; position: meshtastic_protobufs_Position | null; snr: number; lastHeard: number; deviceMetrics: meshtastic_protobufs_DeviceMetrics | null; channel: number }
 
-export type app_device_ChannelMessageState = "pending" | "acknowledged" | { error: string }
+export type app_device_ChannelMessageState = "queued" | "pending" | "sent" | "acknowledged" | "cancelled" | { error: string }
 
 export type meshtastic_protobufs_QueueStatus = { res: number; free: number; maxlen: number; meshPacketId: number }
 
@@ -207,7 +207,7 @@ export type meshtastic_protobufs_MyNodeInfo = { myNodeNum: number; rebootCount:
  * 
  * TODO: REPLACE
  */
-export type meshtastic_protobufs_module_config_PayloadVariant = { mqtt: meshtastic_protobufs_module_config_MqttConfig } | { serial: meshtastic_protobufs_module_config_SerialConfig } | { externalNotification: meshtastic_protobufs_module_config_ExternalNotificationConfig } | { storeForward: meshtastic_protobufs_module_config_StoreForwardConfig } | { rangeTest: meshtastic_protobufs_module_config_RangeTestConfig } | { telemetry: meshtastic_protobufs_module_config_TelemetryConfig } | { cannedMessage: meshtastic_protobufs_module_config_CannedMessageConfig } | { audio: meshtastic_protobufs_module_config_AudioConfig } | { remoteHardware: meshtastic_protobufs_module_config_RemoteHardwareConfig } | { neighborInfo: meshtastic_protobufs_module_config_NeighborInfoConfig } | { ambientLighting: meshtastic_protobufs_module_config_AmbientLightingConfig } | { detectionSensor: meshtastic_protobufs_module_config_DetectionSensorConfig }
+export type meshtastic_protobufs_module_config_PayloadVariant = { mqtt: meshtastic_protobufs_module_config_MqttConfig } | { serial: meshtastic_protobufs_module_config_SerialConfig } | { externalNotification: meshtastic_protobufs_module_config_ExternalNotificationConfig } | { storeForward: meshtastic_protobufs_module_config_StoreForwardConfig } | { rangeTest: meshtastic_protobufs_module_config_RangeTestConfig } | { telemetry: meshtastic_protobufs_module_config_TelemetryConfig } | { cannedMessage: meshtastic_protobufs_module_config_CannedMessageConfig } | { audio: meshtastic_protobufs_module_config_AudioConfig } | { remoteHardware: meshtastic_protobufs_module_config_RemoteHardwareConfig } | { neighborInfo: meshtastic_protobufs_module_config_NeighborInfoConfig } | { ambientLighting: meshtastic_protobufs_module_config_AmbientLightingConfig } | { detectionSensor: meshtastic_protobufs_module_config_DetectionSensorConfig } | { paxcounter: meshtastic_protobufs_module_config_PaxcounterConfig }
 
 /**
  * 
@@ -232,7 +232,7 @@ export type meshtastic_protobufs_position_AltSource = "altUnset" | "altManual" |
  * 
  * Lora Config
  */
-export type meshtastic_protobufs_config_LoRaConfig = { usePreset: boolean; modemPreset: number; bandwidth: number; spreadFactor: number; codingRate: number; frequencyOffset: number; region: number; hopLimit: number; txEnabled: boolean; txPower: number; channelNum: number; overrideDutyCycle: boolean; sx126XRxBoostedGain: boolean; overrideFrequency: number; ignoreIncoming: number[] }
+export type meshtastic_protobufs_config_LoRaConfig = { usePreset: boolean; modemPreset: number; bandwidth: number; spreadFactor: number; codingRate: number; frequencyOffset: number; region: number; hopLimit: number; txEnabled: boolean; txPower: number; channelNum: number; overrideDutyCycle: boolean; sx126XRxBoostedGain: boolean; overrideFrequency: number; ignoreIncoming: number[]; ignoreMqtt: boolean }
 
 /**
  * 
@@ -258,7 +258,7 @@ export type meshtastic_protobufs_LocalConfig = { device: meshtastic_protobufs_co
  * 
  * TODO: REPLACE
  */
-export type meshtastic_protobufs_admin_message_PayloadVariant = { getChannelRequest: number } | { getChannelResponse: meshtastic_protobufs_Channel } | { getOwnerRequest: boolean } | { getOwnerResponse: meshtastic_protobufs_User } | { getConfigRequest: number } | { getConfigResponse: meshtastic_protobufs_Config } | { getModuleConfigRequest: number } | { getModuleConfigResponse: meshtastic_protobufs_ModuleConfig } | { getCannedMessageModuleMessagesRequest: boolean } | { getCannedMessageModuleMessagesResponse: string } | { getDeviceMetadataRequest: boolean } | { getDeviceMetadataResponse: meshtastic_protobufs_DeviceMetadata } | { getRingtoneRequest: boolean } | { getRingtoneResponse: string } | { getDeviceConnectionStatusRequest: boolean } | { getDeviceConnectionStatusResponse: meshtastic_protobufs_DeviceConnectionStatus } | { setHamMode: meshtastic_protobufs_HamParameters } | { getNodeRemoteHardwarePinsRequest: boolean } | { getNodeRemoteHardwarePinsResponse: meshtastic_protobufs_NodeRemoteHardwarePinsResponse } | { setOwner: meshtastic_protobufs_User } | { setChannel: meshtastic_protobufs_Channel } | { setConfig: meshtastic_protobufs_Config } | { setModuleConfig: meshtastic_protobufs_ModuleConfig } | { setCannedMessageModuleMessages: string } | { setRingtoneMessage: string } | { beginEditSettings: boolean } | { commitEditSettings: boolean } | { rebootOtaSeconds: number } | { exitSimulator: boolean } | { rebootSeconds: number } | { shutdownSeconds: number } | { factoryReset: number } | { nodedbReset: number }
+export type meshtastic_protobufs_admin_message_PayloadVariant = { getChannelRequest: number } | { getChannelResponse: meshtastic_protobufs_Channel } | { getOwnerRequest: boolean } | { getOwnerResponse: meshtastic_protobufs_User } | { getConfigRequest: number } | { getConfigResponse: meshtastic_protobufs_Config } | { getModuleConfigRequest: number } | { getModuleConfigResponse: meshtastic_protobufs_ModuleConfig } | { getCannedMessageModuleMessagesRequest: boolean } | { getCannedMessageModuleMessagesResponse: string } | { getDeviceMetadataRequest: boolean } | { getDeviceMetadataResponse: meshtastic_protobufs_DeviceMetadata } | { getRingtoneRequest: boolean } | { getRingtoneResponse: string } | { getDeviceConnectionStatusRequest: boolean } | { getDeviceConnectionStatusResponse: meshtastic_protobufs_DeviceConnectionStatus } | { setHamMode: meshtastic_protobufs_HamParameters } | { getNodeRemoteHardwarePinsRequest: boolean } | { getNodeRemoteHardwarePinsResponse: meshtastic_protobufs_NodeRemoteHardwarePinsResponse } | { enterDfuModeRequest: boolean } | { deleteFileRequest: string } | { setOwner: meshtastic_protobufs_User } | { setChannel: meshtastic_protobufs_Channel } | { setConfig: meshtastic_protobufs_Config } | { setModuleConfig: meshtastic_protobufs_ModuleConfig } | { setCannedMessageModuleMessages: string } | { setRingtoneMessage: string } | { removeByNodenum: number } | { beginEditSettings: boolean } | { commitEditSettings: boolean } | { rebootOtaSeconds: number } | { exitSimulator: boolean } | { rebootSeconds: number } | { shutdownSeconds: number } | { factoryReset: number } | { nodedbReset: number }
 
 /**
  * 
@@ -300,19 +300,19 @@ export type meshtastic_protobufs_NodeRemoteHardwarePinsResponse = { nodeRemoteHa
  */
 export type meshtastic_protobufs_HardwareMessage = { type: number; gpioMask: string; gpioValue: string }
 
-export type app_device_MeshDevice = { configId: number; ready: boolean; status: app_device_SerialDeviceStatus; channels: { [key: number]: app_device_MeshChannel }; config: meshtastic_protobufs_LocalConfig; moduleConfig: meshtastic_protobufs_LocalModuleConfig; myNodeInfo: meshtastic_protobufs_MyNodeInfo; nodes: { [key: number]: app_device_MeshNode }; regionUnset: boolean; deviceMetrics: meshtastic_protobufs_DeviceMetrics; waypoints: { [key: number]: app_device_NormalizedWaypoint }; neighbors: { [key: number]: app_device_NeighborInfoPacket }; configInProgress: boolean }
+export type app_device_MeshDevice = { configId: number; ready: boolean; status: app_device_SerialDeviceStatus; channels: { [key: number]: app_device_MeshChannel }; config: meshtastic_protobufs_LocalConfig; moduleConfig: meshtastic_protobufs_LocalModuleConfig; myNodeInfo: meshtastic_protobufs_MyNodeInfo; metadata: meshtastic_protobufs_DeviceMetadata | null; compatibility: app_device_compatibility_DeviceCompatibility; nodes: { [key: number]: app_device_MeshNode }; regionUnset: boolean; deviceMetrics: meshtastic_protobufs_DeviceMetrics; waypoints: { [key: number]: app_device_NormalizedWaypoint }; neighbors: { [key: number]: app_device_NeighborInfoPacket }; detectionEvents: { [key: number]: app_device_DetectionEvent[] }; configInProgress: boolean; remoteNodes: { [key: number]: app_device_RemoteNodeConfig }; telemetryRetention: app_device_telemetry_TelemetryRetentionPolicy; telemetryAlerts: app_device_alerts_TelemetryAlertMonitor }
 
 /**
  * 
  * Defines the device's role on the Mesh network
  */
-export type meshtastic_protobufs_config_device_config_Role = "client" | "clientMute" | "router" | "routerClient" | "repeater" | "tracker" | "sensor"
+export type meshtastic_protobufs_config_device_config_Role = "client" | "clientMute" | "router" | "routerClient" | "repeater" | "tracker" | "sensor" | "tak" | "clientHidden" | "lostAndFound" | "takTracker"
 
 /**
  * 
  * Position Config
  */
-export type meshtastic_protobufs_config_PositionConfig = { positionBroadcastSecs: number; positionBroadcastSmartEnabled: boolean; fixedPosition: boolean; gpsEnabled: boolean; gpsUpdateInterval: number; gpsAttemptTime: number; positionFlags: number; rxGpio: number; txGpio: number; broadcastSmartMinimumDistance: number; broadcastSmartMinimumIntervalSecs: number }
+export type meshtastic_protobufs_config_PositionConfig = { positionBroadcastSecs: number; positionBroadcastSmartEnabled: boolean; fixedPosition: boolean; gpsEnabled: boolean; gpsUpdateInterval: number; gpsAttemptTime: number; positionFlags: number; rxGpio: number; txGpio: number; broadcastSmartMinimumDistance: number; broadcastSmartMinimumIntervalSecs: number; gpsEnGpio: number; gpsMode: number }
 
 export type app_device_TextPacket = { packet: meshtastic_protobufs_MeshPacket; data: string }
 
@@ -325,8 +325,9 @@ export type meshtastic_protobufs_RtttlConfig = { ringtone: string }
 /**
  * 
  * Bit field of boolean configuration options, indicating which optional
- * fields to include when assembling POSITION messages
- * Longitude and latitude are always included (also time if GPS-synced)
+ * fields to include when assembling POSITION messages.
+ * Longitude, latitude, altitude, speed, heading, and DOP
+ * are always included (also time if GPS-synced)
  * NOTE: the more fields are included, the larger the message will be -
  * leading to longer airtime and a higher risk of packet loss
  */
@@ -343,13 +344,13 @@ export type meshtastic_protobufs_SerialConnectionStatus = { baud: number; isConn
  * 001 - 063 = From Router
  * 064 - 127 = From Client
  */
-export type meshtastic_protobufs_store_and_forward_RequestResponse = "unset" | "routerError" | "routerHeartbeat" | "routerPing" | "routerPong" | "routerBusy" | "routerHistory" | "routerStats" | "clientError" | "clientHistory" | "clientStats" | "clientPing" | "clientPong" | "clientAbort"
+export type meshtastic_protobufs_store_and_forward_RequestResponse = "unset" | "routerError" | "routerHeartbeat" | "routerPing" | "routerPong" | "routerBusy" | "routerHistory" | "routerStats" | "routerTextDirect" | "routerTextBroadcast" | "clientError" | "clientHistory" | "clientStats" | "clientPing" | "clientPong" | "clientAbort"
 
 export type meshtastic_protobufs_mesh_packet_PayloadVariant = { decoded: meshtastic_protobufs_Data } | { encrypted: number[] }
 
 export type meshtastic_protobufs_config_bluetooth_config_PairingMode = "randomPin" | "fixedPin" | "noPin"
 
-export type meshtastic_protobufs_telemetry_Variant = { deviceMetrics: meshtastic_protobufs_DeviceMetrics } | { environmentMetrics: meshtastic_protobufs_EnvironmentMetrics } | { airQualityMetrics: meshtastic_protobufs_AirQualityMetrics }
+export type meshtastic_protobufs_telemetry_Variant = { deviceMetrics: meshtastic_protobufs_DeviceMetrics } | { environmentMetrics: meshtastic_protobufs_EnvironmentMetrics } | { airQualityMetrics: meshtastic_protobufs_AirQualityMetrics } | { powerMetrics: meshtastic_protobufs_PowerMetrics }
 
 /**
  * 
@@ -370,7 +371,7 @@ export type meshtastic_protobufs_telemetry_Variant = { deviceMetrics: meshtastic
  * preshared key, drops all existing nodes, force our node to rejoin this new group)
  * Full information about a node on the mesh
  */
-export type meshtastic_protobufs_NodeInfo = { num: number; user: meshtastic_protobufs_User | null; position: meshtastic_protobufs_Position | null; snr: number; lastHeard: number; deviceMetrics: meshtastic_protobufs_DeviceMetrics | null; channel: number }
+export type meshtastic_protobufs_NodeInfo = { num: number; user: meshtastic_protobufs_User | null; position: meshtastic_protobufs_Position | null; snr: number; lastHeard: number; deviceMetrics: meshtastic_protobufs_DeviceMetrics | null; channel: number; viaMqtt: boolean; hopsAway: number }
 
 /**
  * 
@@ -394,7 +395,7 @@ export type meshtastic_protobufs_RemoteHardwarePinType = "unknown" | "digitalRea
  * 
  * a gps position
  */
-export type meshtastic_protobufs_Position = { latitudeI: number; longitudeI: number; altitude: number; time: number; locationSource: number; altitudeSource: number; timestamp: number; timestampMillisAdjust: number; altitudeHae: number; altitudeGeoidalSeparation: number; pdop: number; hdop: number; vdop: number; gpsAccuracy: number; groundSpeed: number; groundTrack: number; fixQuality: number; fixType: number; satsInView: number; sensorId: number; nextUpdate: number; seqNumber: number }
+export type meshtastic_protobufs_Position = { latitudeI: number; longitudeI: number; altitude: number; time: number; locationSource: number; altitudeSource: number; timestamp: number; timestampMillisAdjust: number; altitudeHae: number; altitudeGeoidalSeparation: number; pdop: number; hdop: number; vdop: number; gpsAccuracy: number; groundSpeed: number; groundTrack: number; fixQuality: number; fixType: number; satsInView: number; sensorId: number; nextUpdate: number; seqNumber: number; precisionBits: number }
 
 /**
  * 
@@ -443,7 +444,7 @@ export type meshtastic_protobufs_module_config_CannedMessageConfig = { rotary1En
  * 0xff - broadcast
  * 0 through 3 - for future use
  */
-export type meshtastic_protobufs_User = { id: string; longName: string; shortName: string; macaddr: number[]; hwModel: number; isLicensed: boolean }
+export type meshtastic_protobufs_User = { id: string; longName: string; shortName: string; macaddr: number[]; hwModel: number; isLicensed: boolean; role: number }
 
 export type app_device_ChannelMessageWithState = { payload: app_device_ChannelMessagePayload; state: app_device_ChannelMessageState }
 
@@ -482,7 +483,7 @@ export type meshtastic_protobufs_ToRadio = { payloadVariant: meshtastic_protobuf
  * FIXME: explain how apps use channels for security.
  * explain how remote settings and remote gpio are managed as an example
  */
-export type meshtastic_protobufs_ChannelSettings = { channelNum: number; psk: number[]; name: string; id: number; uplinkEnabled: boolean; downlinkEnabled: boolean }
+export type meshtastic_protobufs_ChannelSettings = { channelNum: number; psk: number[]; name: string; id: number; uplinkEnabled: boolean; downlinkEnabled: boolean; moduleSettings: meshtastic_protobufs_ModuleSettings | null }
 
 export type meshtastic_protobufs_x_modem_Control = "nul" | "soh" | "stx" | "eot" | "ack" | "nak" | "can" | "ctrlz"
 
@@ -498,7 +499,7 @@ export type meshtastic_protobufs_Channel = { index: number; settings: meshtastic
  */
 export type meshtastic_protobufs_log_record_Level = "unset" | "critical" | "error" | "warning" | "info" | "debug" | "trace"
 
-export type meshtastic_protobufs_NodeInfoLite = { num: number; user: meshtastic_protobufs_User | null; position: meshtastic_protobufs_PositionLite | null; snr: number; lastHeard: number; deviceMetrics: meshtastic_protobufs_DeviceMetrics | null; channel: number }
+export type meshtastic_protobufs_NodeInfoLite = { num: number; user: meshtastic_protobufs_User | null; position: meshtastic_protobufs_PositionLite | null; snr: number; lastHeard: number; deviceMetrics: meshtastic_protobufs_DeviceMetrics | null; channel: number; viaMqtt: boolean; hopsAway: number }
 
 export type app_ipc_DeviceBulkConfig = { radio: meshtastic_protobufs_LocalConfig | null; module: meshtastic_protobufs_LocalModuleConfig | null; channels: meshtastic_protobufs_Channel[] | null }
 
@@ -557,13 +558,13 @@ export type meshtastic_protobufs_config_lo_ra_config_ModemPreset = "longFast" |
  * 
  * TODO: REPLACE
  */
-export type meshtastic_protobufs_store_and_forward_Variant = { stats: meshtastic_protobufs_store_and_forward_Statistics } | { history: meshtastic_protobufs_store_and_forward_History } | { heartbeat: meshtastic_protobufs_store_and_forward_Heartbeat } | { empty: boolean }
+export type meshtastic_protobufs_store_and_forward_Variant = { stats: meshtastic_protobufs_store_and_forward_Statistics } | { history: meshtastic_protobufs_store_and_forward_History } | { heartbeat: meshtastic_protobufs_store_and_forward_Heartbeat } | { text: number[] }
 
 /**
  * 
  * Defines the device's behavior for how messages are rebroadcast
  */
-export type meshtastic_protobufs_config_device_config_RebroadcastMode = "all" | "allSkipDecoding" | "localOnly"
+export type meshtastic_protobufs_config_device_config_RebroadcastMode = "all" | "allSkipDecoding" | "localOnly" | "knownOnly"
 
 /**
  * 
@@ -601,7 +602,7 @@ export type meshtastic_protobufs_Waypoint = { id: number; latitudeI: number; lon
  * 
  * Log levels, chosen to match python logging conventions.
  */
-export type meshtastic_protobufs_to_radio_PayloadVariant = { packet: meshtastic_protobufs_MeshPacket } | { wantConfigId: number } | { disconnect: boolean } | { xmodemPacket: meshtastic_protobufs_XModem } | { mqttClientProxyMessage: meshtastic_protobufs_MqttClientProxyMessage }
+export type meshtastic_protobufs_to_radio_PayloadVariant = { packet: meshtastic_protobufs_MeshPacket } | { wantConfigId: number } | { disconnect: boolean } | { xmodemPacket: meshtastic_protobufs_XModem } | { mqttClientProxyMessage: meshtastic_protobufs_MqttClientProxyMessage } | { heartbeat: meshtastic_protobufs_Heartbeat }
 
 /**
  * 
@@ -623,7 +624,7 @@ export type meshtastic_protobufs_HamParameters = { callSign: string; txPower: nu
  * only payload_variant is sent in the payload portion of the LORA packet.
  * The other fields are either not sent at all, or sent in the special 16 byte LORA header.
  */
-export type meshtastic_protobufs_MeshPacket = { from: number; to: number; channel: number; id: number; rxTime: number; rxSnr: number; hopLimit: number; wantAck: boolean; priority: number; rxRssi: number; delayed: number; payloadVariant: meshtastic_protobufs_mesh_packet_PayloadVariant | null }
+export type meshtastic_protobufs_MeshPacket = { from: number; to: number; channel: number; id: number; rxTime: number; rxSnr: number; hopLimit: number; wantAck: boolean; priority: number; rxRssi: number; delayed: number; viaMqtt: boolean; hopStart: number; payloadVariant: meshtastic_protobufs_mesh_packet_PayloadVariant | null }
 
 export type app_ipc_APMincutStringResults = { apResult: number[]; mincutResult: ([number, number])[]; diffcenResult: { [key: number]: { [key: number]: { [key: number]: number } } } }
 
@@ -631,13 +632,13 @@ export type app_ipc_APMincutStringResults = { apResult: number[]; mincutResult:
  * 
  * Supported I2C Sensors for telemetry in Meshtastic
  */
-export type meshtastic_protobufs_TelemetrySensorType = "sensorUnset" | "bme280" | "bme680" | "mcp9808" | "ina260" | "ina219" | "bmp280" | "shtc3" | "lps22" | "qmc6310" | "qmi8658" | "qmc5883L" | "sht31" | "pmsa003I"
+export type meshtastic_protobufs_TelemetrySensorType = "sensorUnset" | "bme280" | "bme680" | "mcp9808" | "ina260" | "ina219" | "bmp280" | "shtc3" | "lps22" | "qmc6310" | "qmi8658" | "qmc5883L" | "sht31" | "pmsa003I" | "ina3221" | "bmp085"
 
 /**
  * 
  * External Notifications Config
  */
-export type meshtastic_protobufs_module_config_ExternalNotificationConfig = { enabled: boolean; outputMs: number; output: number; outputVibra: number; outputBuzzer: number; active: boolean; alertMessage: boolean; alertMessageVibra: boolean; alertMessageBuzzer: boolean; alertBell: boolean; alertBellVibra: boolean; alertBellBuzzer: boolean; usePwm: boolean; nagTimeout: number }
+export type meshtastic_protobufs_module_config_ExternalNotificationConfig = { enabled: boolean; outputMs: number; output: number; outputVibra: number; outputBuzzer: number; active: boolean; alertMessage: boolean; alertMessageVibra: boolean; alertMessageBuzzer: boolean; alertBell: boolean; alertBellVibra: boolean; alertBellBuzzer: boolean; usePwm: boolean; nagTimeout: number; useI2SAsBuzzer: boolean }
 
 export type app_device_MeshNodePositionMetrics = { metrics: app_device_NormalizedPosition; timestamp: number; snr: number }
 
@@ -655,7 +656,7 @@ export type app_ipc_ConfigurationStatus = { deviceKey: string; successful: boole
  */
 export type meshtastic_protobufs_NodeRemoteHardwarePin = { nodeNum: number; pin: meshtastic_protobufs_RemoteHardwarePin | null }
 
-export type app_device_LastHeardMetadata = { timestamp: number; snr: number; channel: number }
+export type app_device_LastHeardMetadata = { timestamp: number; snr: number; rssi: number; channel: number; hopsAway: number | null; viaMqtt: boolean }
 
 /**
  * 
@@ -678,6 +679,7 @@ export type meshtastic_protobufs_channel_Role = "disabled" | "primary" | "second
 export type meshtastic_protobufs_config_display_config_OledType = "oledAuto" | "oledSsd1306" | "oledSh1106" | "oledSh1107"
 
 /**
+ * 
  * Ambient Lighting Module - Settings for control of onboard LEDs to allow users to adjust the brightness levels and respective color levels.
  * Initially created for the RAK14001 RGB LED module.
  */
@@ -711,7 +713,7 @@ export type meshtastic_protobufs_Config = { payloadVariant: meshtastic_protobufs
  * 
  * MQTT Client Config
  */
-export type meshtastic_protobufs_module_config_MqttConfig = { enabled: boolean; address: string; username: string; password: string; encryptionEnabled: boolean; jsonEnabled: boolean; tlsEnabled: boolean; root: string; proxyToClientEnabled: boolean }
+export type meshtastic_protobufs_module_config_MqttConfig = { enabled: boolean; address: string; username: string; password: string; encryptionEnabled: boolean; jsonEnabled: boolean; tlsEnabled: boolean; root: string; proxyToClientEnabled: boolean; mapReportingEnabled: boolean; mapReportSettings: meshtastic_protobufs_module_config_MapReportSettings | null }
 
 /**
  * 
@@ -775,7 +777,7 @@ export type meshtastic_protobufs_mesh_packet_Delayed = "noDelay" | "broadcast" |
  * We have change to this 'portnum' based scheme for specifying app handlers for particular payloads.
  * This change is backwards compatible by treating the legacy OPAQUE/CLEAR_TEXT values identically.
  */
-export type meshtastic_protobufs_PortNum = "unknownApp" | "textMessageApp" | "remoteHardwareApp" | "positionApp" | "nodeinfoApp" | "routingApp" | "adminApp" | "textMessageCompressedApp" | "waypointApp" | "audioApp" | "detectionSensorApp" | "replyApp" | "ipTunnelApp" | "serialApp" | "storeForwardApp" | "rangeTestApp" | "telemetryApp" | "zpsApp" | "simulatorApp" | "tracerouteApp" | "neighborinfoApp" | "privateApp" | "atakForwarder" | "max"
+export type meshtastic_protobufs_PortNum = "unknownApp" | "textMessageApp" | "remoteHardwareApp" | "positionApp" | "nodeinfoApp" | "routingApp" | "adminApp" | "textMessageCompressedApp" | "waypointApp" | "audioApp" | "detectionSensorApp" | "replyApp" | "ipTunnelApp" | "paxcounterApp" | "serialApp" | "storeForwardApp" | "rangeTestApp" | "telemetryApp" | "zpsApp" | "simulatorApp" | "tracerouteApp" | "neighborinfoApp" | "atakPlugin" | "mapReportApp" | "privateApp" | "atakForwarder" | "max"
 
 /**
  * 
@@ -784,7 +786,7 @@ export type meshtastic_protobufs_PortNum = "unknownApp" | "textMessageApp" | "re
  * Because they will be used to find firmware filenames in the android app for OTA updates.
  * To match the old style filenames, _ is converted to -, p is converted to .
  */
-export type meshtastic_protobufs_HardwareModel = "unset" | "tloraV2" | "tloraV1" | "tloraV211P6" | "tbeam" | "heltecV20" | "tbeamV0P7" | "techo" | "tloraV11P3" | "rak4631" | "heltecV21" | "heltecV1" | "lilygoTbeamS3Core" | "rak11200" | "nanoG1" | "tloraV211P8" | "tloraT3S3" | "nanoG1Explorer" | "nanoG2Ultra" | "loraType" | "stationG1" | "rak11310" | "loraRelayV1" | "nrf52840Dk" | "ppr" | "genieblocks" | "nrf52Unknown" | "portduino" | "androidSim" | "diyV1" | "nrf52840Pca10059" | "drDev" | "m5Stack" | "heltecV3" | "heltecWslV3" | "betafpv2400Tx" | "betafpv900NanoTx" | "rpiPico" | "heltecWirelessTracker" | "heltecWirelessPaper" | "tdeck" | "twatchS3" | "picomputerS3" | "heltecHt62" | "privateHw"
+export type meshtastic_protobufs_HardwareModel = "unset" | "tloraV2" | "tloraV1" | "tloraV211P6" | "tbeam" | "heltecV20" | "tbeamV0P7" | "techo" | "tloraV11P3" | "rak4631" | "heltecV21" | "heltecV1" | "lilygoTbeamS3Core" | "rak11200" | "nanoG1" | "tloraV211P8" | "tloraT3S3" | "nanoG1Explorer" | "nanoG2Ultra" | "loraType" | "stationG1" | "rak11310" | "senseloraRp2040" | "senseloraS3" | "canaryone" | "rp2040Lora" | "stationG2" | "loraRelayV1" | "nrf52840Dk" | "ppr" | "genieblocks" | "nrf52Unknown" | "portduino" | "androidSim" | "diyV1" | "nrf52840Pca10059" | "drDev" | "m5Stack" | "heltecV3" | "heltecWslV3" | "betafpv2400Tx" | "betafpv900NanoTx" | "rpiPico" | "heltecWirelessTracker" | "heltecWirelessPaper" | "tdeck" | "twatchS3" | "picomputerS3" | "heltecHt62" | "ebyteEsp32S3" | "esp32S3Pico" | "chatter2" | "heltecWirelessPaperV10" | "heltecWirelessTrackerV10" | "privateHw"
 
 export type app_device_UserPacket = { packet: meshtastic_protobufs_MeshPacket; data: meshtastic_protobufs_User }
 
@@ -804,7 +806,7 @@ export type meshtastic_protobufs_store_and_forward_Heartbeat = { period: number;
  * 
  * Configuration
  */
-export type meshtastic_protobufs_config_DeviceConfig = { role: number; serialEnabled: boolean; debugLogEnabled: boolean; buttonGpio: number; buzzerGpio: number; rebroadcastMode: number; nodeInfoBroadcastSecs: number; doubleTapAsButtonPress: boolean; isManaged: boolean }
+export type meshtastic_protobufs_config_DeviceConfig = { role: number; serialEnabled: boolean; debugLogEnabled: boolean; buttonGpio: number; buzzerGpio: number; rebroadcastMode: number; nodeInfoBroadcastSecs: number; doubleTapAsButtonPress: boolean; isManaged: boolean; disableTripleClick: boolean }
 
 export type app_device_MeshNodeDeviceMetrics = { metrics: meshtastic_protobufs_DeviceMetrics; timestamp: number; snr: number }
 
@@ -828,3 +830,303 @@ export type meshtastic_protobufs_Data = { portnum: number; payload: number[]; wa
  */
 export type meshtastic_protobufs_module_config_RemoteHardwareConfig = { enabled: boolean; allowUndefinedPinAccess: boolean; availablePins: meshtastic_protobufs_RemoteHardwarePin[] }
 
+/**
+ * 
+ * TODO: REPLACE
+ */
+export type meshtastic_protobufs_Paxcount = { wifi: number; ble: number; uptime: number }
+
+/**
+ * 
+ * Power Metrics (voltage / current / etc)
+ */
+export type meshtastic_protobufs_PowerMetrics = { ch1Voltage: number; ch1Current: number; ch2Voltage: number; ch2Current: number; ch3Voltage: number; ch3Current: number }
+
+/**
+ * 
+ * ATAK Contact
+ * <contact endpoint='0.0.0.0:4242:tcp' phone='+12345678' callsign='FALKE'/>
+ */
+export type meshtastic_protobufs_Contact = { callsign: string; deviceCallsign: string }
+
+/**
+ * 
+ * Packets for the official ATAK Plugin
+ */
+export type meshtastic_protobufs_TakPacket = { isCompressed: boolean; contact: meshtastic_protobufs_Contact | null; group: meshtastic_protobufs_Group | null; status: meshtastic_protobufs_Status | null; payloadVariant: meshtastic_protobufs_tak_packet_PayloadVariant | null }
+
+/**
+ * 
+ * ATAK GeoChat message
+ */
+export type meshtastic_protobufs_GeoChat = { message: string; to: string | null }
+
+/**
+ * 
+ * Role of the group member
+ */
+export type meshtastic_protobufs_MemberRole = "unspecifed" | "teamMember" | "teamLead" | "hq" | "sniper" | "medic" | "forwardObserver" | "rto" | "k9"
+
+/**
+ * 
+ * ATAK Group
+ * <__group role='Team Member' name='Cyan'/>
+ */
+export type meshtastic_protobufs_Group = { role: number; team: number }
+
+/**
+ * 
+ * Information about a node intended to be reported unencrypted to a map using MQTT.
+ */
+export type meshtastic_protobufs_MapReport = { longName: string; shortName: string; role: number; hwModel: number; firmwareVersion: string; region: number; modemPreset: number; hasDefaultChannel: boolean; latitudeI: number; longitudeI: number; altitude: number; positionPrecision: number; numOnlineLocalNodes: number }
+
+/**
+ * 
+ * A heartbeat message is sent to the node from the client to keep the connection alive.
+ * This is currently only needed to keep serial connections alive, but can be used by any PhoneAPI.
+ */
+export type meshtastic_protobufs_Heartbeat = null
+
+/**
+ * 
+ * Settings for reporting unencrypted information about our node to a map via MQTT
+ */
+export type meshtastic_protobufs_module_config_MapReportSettings = { publishIntervalSecs: number; positionPrecision: number }
+
+/**
+ * 
+ * ATAK EUD Status
+ * <status battery='100' />
+ */
+export type meshtastic_protobufs_Status = { battery: number }
+
+export type meshtastic_protobufs_config_position_config_GpsMode = "disabled" | "enabled" | "notPresent"
+
+/**
+ * 
+ * This message is specifically for modules to store per-channel configuration data.
+ */
+export type meshtastic_protobufs_ModuleSettings = { positionPrecision: number }
+
+/**
+ * 
+ * Config for the Paxcounter Module
+ */
+export type meshtastic_protobufs_module_config_PaxcounterConfig = { enabled: boolean; paxcounterUpdateInterval: number }
+
+/**
+ * 
+ * Position Location Information from ATAK
+ */
+export type meshtastic_protobufs_Pli = { latitudeI: number; longitudeI: number; altitude: number; speed: number; course: number }
+
+export type meshtastic_protobufs_Team = "unspecifedColor" | "white" | "yellow" | "orange" | "magenta" | "red" | "maroon" | "purple" | "darkBlue" | "blue" | "cyan" | "teal" | "green" | "darkGreen" | "brown"
+
+/**
+ * 
+ * The payload of the packet
+ */
+export type meshtastic_protobufs_tak_packet_PayloadVariant = { pli: meshtastic_protobufs_Pli } | { chat: meshtastic_protobufs_GeoChat }
+
+/**
+ * Configuration of a remote node, populated from admin message responses
+ */
+export type app_device_RemoteNodeConfig = { nodeNum: number; config: meshtastic_protobufs_LocalConfig; moduleConfig: meshtastic_protobufs_LocalModuleConfig; channels: { [key: number]: meshtastic_protobufs_Channel }; user: meshtastic_protobufs_User | null; metadata: meshtastic_protobufs_DeviceMetadata | null; lastResponse: number | null }
+
+/**
+ * A versioned snapshot of all user-editable configuration on a device
+ */
+export type app_device_backup_DeviceConfigBackup = { version: number; createdAt: number; owner: meshtastic_protobufs_User | null; config: meshtastic_protobufs_LocalConfig; moduleConfig: meshtastic_protobufs_LocalModuleConfig; channels: meshtastic_protobufs_Channel[] }
+
+/**
+ * A single configuration field that differs between two backups.
+ * Values are rendered as JSON, and are `None` when the field is absent.
+ */
+export type app_device_backup_ConfigFieldDiff = { path: string; currentValue: string | null; incomingValue: string | null }
+
+export type app_device_backup_ConfigBackupFormat = "yaml" | "json"
+
+export type app_provisioning_ProvisioningProfile = { config: app_ipc_DeviceBulkConfig; longNameTemplate: string; shortNameTemplate: string; startIndex: number; baudRate: number | null }
+
+export type app_provisioning_ProvisioningReport = { portName: string; index: number | null; nodeNum: number | null; longName: string | null; shortName: string | null; status: app_provisioning_ProvisioningStatus; mismatches: app_device_backup_ConfigFieldDiff[]; startedAt: number; finishedAt: number | null }
+
+export type app_provisioning_ProvisioningStatus = "inProgress" | "alreadyProvisioned" | "verified" | "verificationFailed" | { failed: string }
+
+export type app_device_channel_url_ChannelImportPreview = { changes: app_device_channel_url_ChannelImportChange[]; channels: meshtastic_protobufs_Channel[]; loraConfig: meshtastic_protobufs_config_LoRaConfig | null; loraConfigChanged: boolean }
+
+export type app_device_channel_url_ChannelImportChange = { index: number; name: string; action: app_device_channel_url_ChannelImportAction }
+
+export type app_device_channel_url_ChannelImportAction = "added" | "replaced" | "unchanged" | "removed"
+
+export type app_device_channel_url_ChannelUrl = { url: string; qrCodeSvg: string }
+
+/**
+ * Summary of all samples in a series received within one hour
+ */
+export type app_device_telemetry_TelemetryAggregate = { bucketStart: number; sampleCount: number; fields: { [key: string]: app_device_telemetry_TelemetryFieldAggregate } }
+
+/**
+ * Historical telemetry for a node, returned by range queries
+ */
+export type app_device_telemetry_NodeTelemetryRange = { nodeNum: number; samples: app_device_telemetry_TelemetrySamples; aggregates: app_device_telemetry_TelemetryAggregate[] }
+
+export type app_device_telemetry_TelemetrySeriesKind = "deviceMetrics" | "environmentMetrics" | "airQualityMetrics" | "powerMetrics" | "paxcount" | "position"
+
+export type app_device_telemetry_TelemetrySamples = { kind: "deviceMetrics"; samples: app_device_MeshNodeDeviceMetrics[] } | { kind: "environmentMetrics"; samples: app_device_MeshNodeEnvironmentMetrics[] } | { kind: "airQualityMetrics"; samples: app_device_MeshNodeAirQualityMetrics[] } | { kind: "powerMetrics"; samples: app_device_MeshNodePowerMetrics[] } | { kind: "paxcount"; samples: app_device_MeshNodePaxcountMetrics[] } | { kind: "position"; samples: app_device_MeshNodePositionMetrics[] }
+
+/**
+ * Limits applied to every telemetry series stored on a node
+ */
+export type app_device_telemetry_TelemetryRetentionPolicy = { maxRawSamples: number; maxHourlyAggregates: number; recentSamples: number }
+
+export type app_device_telemetry_TelemetryFieldAggregate = { min: number; max: number; mean: number; count: number }
+
+export type app_ipc_MessageStateChangedPayload = { channel: number; messageId: number; state: app_device_ChannelMessageState }
+
+/**
+ * The sequence number increases by one for every delta event, so the UI
+ * can detect a missed event and request a full resync.
+ */
+export type app_ipc_DeltaEvent = { sequence: number; delta: app_ipc_DeltaPayload }
+
+export type app_ipc_MessageAddedPayload = { channel: number; message: app_device_ChannelMessageWithState }
+
+export type app_ipc_EdgeUpdatedPayload = { source: app_graph_ds_node_GraphNode; target: app_graph_ds_node_GraphNode; edge: app_graph_ds_edge_GraphEdge }
+
+/**
+ * A fine-grained update to device or graph state, emitted in place of
+ * re-sending the full `MeshDevice` or `MeshGraph`
+ */
+export type app_ipc_DeltaPayload = { event: "node_updated"; payload: app_device_MeshNode } | { event: "message_added"; payload: app_ipc_MessageAddedPayload } | { event: "message_state_changed"; payload: app_ipc_MessageStateChangedPayload } | { event: "waypoint_added"; payload: app_device_NormalizedWaypoint } | { event: "graph_node_updated"; payload: app_graph_ds_node_GraphNode } | { event: "edge_updated"; payload: app_ipc_EdgeUpdatedPayload } | { event: "node_removed"; payload: number } | { event: "outbox_updated"; payload: app_packet_api_outbox_OutboxStatus } | { event: "fragment_progress"; payload: app_packet_api_fragments_FragmentProgress } | { event: "file_transfer_updated"; payload: app_packet_api_transfers_FileTransferStatus } | { event: "detection_event"; payload: app_device_DetectionEvent }
+
+export type app_ipc_DeltaBatch = { deviceKey: string | null; events: app_ipc_DeltaEvent[] }
+
+export type app_device_alerts_TelemetryAlert = { ruleId: string; nodeNum: number; field: string; value: number; comparison: app_device_alerts_ThresholdComparison; threshold: number; timestamp: number }
+
+export type app_device_alerts_ThresholdComparison = "above" | "below"
+
+export type app_ipc_TelemetryAlertEvent = { deviceKey: string; alert: app_device_alerts_TelemetryAlert }
+
+export type app_device_MeshNodeAirQualityMetrics = { metrics: meshtastic_protobufs_AirQualityMetrics; timestamp: number; snr: number }
+
+export type app_device_MeshNodePowerMetrics = { metrics: meshtastic_protobufs_PowerMetrics; timestamp: number; snr: number }
+
+/**
+ * Checks incoming telemetry against user-defined thresholds. An alert is
+ * only raised when a reading crosses its threshold, not for every reading
+ * that stays past it.
+ */
+export type app_device_alerts_TelemetryAlertMonitor = { rules: app_device_alerts_TelemetryAlertRule[] }
+
+export type app_device_alerts_TelemetryAlertRule = { id: string; kind: app_device_telemetry_TelemetrySeriesKind; field: string; comparison: app_device_alerts_ThresholdComparison; threshold: number; nodeNum: number | null }
+
+export type app_device_telemetry_export_TelemetryExportFormat = "csv" | "parquet"
+
+export type app_device_telemetry_export_TelemetryExportOptions = { directory: string; format: app_device_telemetry_export_TelemetryExportFormat; kinds: app_device_telemetry_TelemetrySeriesKind[]; nodeNums: number[] | null; start: number | null; end: number | null }
+
+export type app_device_telemetry_export_ExportedTelemetryFile = { kind: app_device_telemetry_TelemetrySeriesKind; path: string; rows: number }
+
+export type app_packet_api_stats_PacketCounts = { total: number; handled: number; unsupported: number; decodeFailure: number; failed: number }
+
+export type app_packet_api_stats_PacketOutcome = "handled" | "unsupported" | "decodeFailure" | "failed"
+
+/**
+ * Counters for every mesh packet received by a device since it connected
+ */
+export type app_packet_api_stats_PacketStats = { since: number; totals: app_packet_api_stats_PacketCounts; byPortnum: { [key: string]: app_packet_api_stats_PacketCounts }; bySender: { [key: number]: app_packet_api_stats_PacketCounts }; byHopsAway: { [key: number]: app_packet_api_stats_PacketCounts }; unknownHopsAway: app_packet_api_stats_PacketCounts }
+
+export type app_packet_api_stats_RawPacketLogEntry = { receivedAt: number; portnum: string; hopsAway: number | null; outcome: app_packet_api_stats_PacketOutcome; error: string | null; packet: meshtastic_protobufs_MeshPacket }
+
+export type app_packet_api_outbox_OutboxStatus = { queued: number; inFlight: number; awaitingAck: number; radioFree: number | null; radioCapacity: number | null; dutyCycleLimited: boolean; policy: app_packet_api_outbox_OutboxPolicy }
+
+export type app_packet_api_outbox_OutboxPolicy = { maxAttempts: number; retrySpacingSecs: number; minSendIntervalMs: number; dutyCycleLimit: number | null }
+
+export type app_packet_api_outbox_MessagePriority = "routine" | "tactical" | "emergency"
+
+export type app_packet_api_fragments_FragmentProgress = { from: number; channel: number; messageId: number; received: number; total: number; expired: boolean }
+
+export type app_packet_api_transfers_FileTransferStatus = { transferId: number; direction: app_packet_api_transfers_TransferDirection; peer: number; channel: number; fileName: string; size: number; compressed: boolean; chunksDone: number; chunksTotal: number; state: app_packet_api_transfers_TransferState; savedPath: string | null }
+
+export type app_packet_api_transfers_TransferDirection = "outgoing" | "incoming"
+
+export type app_packet_api_transfers_TransferState = "offered" | "transferring" | "completed" | "cancelled" | { failed: string }
+
+/**
+ * A detection sensor trigger, such as a PIR or door sensor, reported by a node
+ */
+export type app_device_DetectionEvent = { nodeNum: number; text: string; timestamp: number; position: app_device_NormalizedPosition | null }
+
+export type app_device_MeshNodePaxcountMetrics = { metrics: meshtastic_protobufs_Paxcount; timestamp: number; snr: number }
+
+export type app_device_PaxcountPacket = { packet: meshtastic_protobufs_MeshPacket; data: meshtastic_protobufs_Paxcount }
+
+/**
+ * Client features that depend on the connected device's firmware
+ */
+export type app_device_compatibility_DeviceFeature = "neighborInfo" | "detectionSensor" | "paxcounter"
+
+export type app_device_compatibility_FeatureSupport = { feature: app_device_compatibility_DeviceFeature; supported: boolean; reason: string | null }
+
+/**
+ * Features the connected device's firmware can service, derived from the
+ * metadata it reports while configuring
+ */
+export type app_device_compatibility_DeviceCompatibility = { firmwareVersion: app_device_compatibility_FirmwareVersion | null; features: app_device_compatibility_FeatureSupport[]; warnings: string[] }
+
+/**
+ * Firmware version as reported in `DeviceMetadata`, e.g. "2.2.15.abc1234"
+ */
+export type app_device_compatibility_FirmwareVersion = { major: number; minor: number; patch: number }
+
+/**
+ * Ordered from least to most severe
+ */
+export type app_packet_api_device_logs_DeviceLogLevel = "unset" | "trace" | "debug" | "info" | "warning" | "error" | "critical"
+
+export type app_ipc_DeviceLogEvent = { deviceKey: string; entry: app_packet_api_device_logs_DeviceLogEntry }
+
+export type app_packet_api_device_logs_DeviceLogFilter = { minLevel: app_packet_api_device_logs_DeviceLogLevel | null; text: string | null; limit: number | null }
+
+export type app_packet_api_device_logs_DeviceLogEntry = { time: number; level: app_packet_api_device_logs_DeviceLogLevel; source: string; message: string }
+
+export type app_analysis_geo_GeoBounds = { south: number; west: number; north: number; east: number }
+
+/**
+ * Transmit and receive characteristics of a link between two radios
+ * sharing the same LoRa settings
+ */
+export type app_analysis_propagation_LinkBudget = { txPowerDbm: number; antennaGainDbi: number; frequencyMhz: number; bandwidthKhz: number; spreadFactor: number }
+
+export type app_analysis_geo_GeoPoint = { latitude: number; longitude: number }
+
+/**
+ * Log-distance path loss model, anchored to free space loss at the
+ * reference distance. An exponent of 2 is free space.
+ */
+export type app_analysis_propagation_PropagationModel = { pathLossExponent: number; referenceDistanceM: number; fadeMarginDb: number }
+
+export type app_analysis_relay_RelayCandidate = { location: app_analysis_geo_GeoPoint; links: app_analysis_relay_RelayLink[]; newlyConnectedPairs: number; weakNodesServed: number; score: number }
+
+export type app_analysis_propagation_LinkPrediction = { distanceM: number; pathLossDb: number; rssiDbm: number; snrDb: number; marginDb: number }
+
+export type app_analysis_relay_RelayPlacementReport = { budget: app_analysis_propagation_LinkBudget; model: app_analysis_propagation_PropagationModel; nodes: app_analysis_relay_NodeConnectivity[]; componentCount: number; candidates: app_analysis_relay_RelayCandidate[] }
+
+export type app_analysis_relay_RelayPlacementOptions = { candidates: app_analysis_geo_GeoPoint[] | null; gridSize: number | null; maxResults: number | null }
+
+export type app_analysis_relay_NodeLinkStatus = "isolated" | "weak" | "connected"
+
+export type app_analysis_relay_RelayLink = { nodeNum: number; prediction: app_analysis_propagation_LinkPrediction }
+
+export type app_analysis_relay_NodeConnectivity = { nodeNum: number; position: app_analysis_geo_GeoPoint | null; neighborCount: number; bestSnr: number | null; status: app_analysis_relay_NodeLinkStatus }
+
+export type app_analysis_coverage_CoverageOptions = { bounds: app_analysis_geo_GeoBounds | null; cellSizeM: number | null; model: app_analysis_propagation_PropagationModel | null }
+
+export type app_analysis_calibration_CalibrationSampleSource = "neighborInfo" | "directPacket"
+
+/**
+ * Propagation model fitted to links observed on the mesh
+ */
+export type app_analysis_calibration_PropagationCalibration = { model: app_analysis_propagation_PropagationModel; sampleCount: number; rmseDb: number; rSquared: number; calibratedAt: number; samples: app_analysis_calibration_CalibrationSample[] }
+
+export type app_analysis_calibration_CalibrationSample = { from: number; to: number; distanceM: number; pathLossDb: number; source: app_analysis_calibration_CalibrationSampleSource }
+
@@ -6,6 +6,7 @@ import * as backendGraphApi from "@api/graph";
 
 import {
   useCreateConfigStatusChannel,
+  useCreateDeltaEventChannel,
   useCreateDeviceDisconnectChannel,
   useCreateDeviceUpdateChannel,
   useCreateGraphUpdateChannel,
@@ -22,6 +23,7 @@ export const AppInitWrapper = ({ children }: PropsWithChildren) => {
   const createConfigStatusChannel = useCreateConfigStatusChannel();
   const createRebootChannel = useCreateRebootChannel();
   const createGraphUpdateChannel = useCreateGraphUpdateChannel();
+  const createDeltaEventChannel = useCreateDeltaEventChannel();
 
   const [hasLoaded, setLoaded] = useState(false);
 
@@ -50,6 +52,7 @@ export const AppInitWrapper = ({ children }: PropsWithChildren) => {
     const unlistenConfigStatus = await createConfigStatusChannel();
     const unlistenReboot = await createRebootChannel();
     const unlistenGraphUpdate = await createGraphUpdateChannel();
+    const unlistenDeltaEvents = await createDeltaEventChannel();
 
     setLoaded(true);
 
@@ -59,6 +62,7 @@ export const AppInitWrapper = ({ children }: PropsWithChildren) => {
       unlistenConfigStatus();
       unlistenReboot();
       unlistenGraphUpdate();
+      unlistenDeltaEvents();
     };
   }, []);
 
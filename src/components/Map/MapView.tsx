@@ -2,7 +2,7 @@ import { MapboxOverlay, MapboxOverlayProps } from "@deck.gl/mapbox/typed";
 import * as Dialog from "@radix-ui/react-dialog";
 import * as Separator from "@radix-ui/react-separator";
 import { PickingInfo } from "deck.gl/typed";
-import { MapPin, X } from "lucide-react";
+import { Layers, MapPin, X } from "lucide-react";
 import maplibregl from "maplibre-gl";
 import { useCallback, useState } from "react";
 import { useTranslation } from "react-i18next";
@@ -20,6 +20,10 @@ import {
 } from "react-map-gl";
 import { useDispatch, useSelector } from "react-redux";
 import { useDebounce } from "react-use";
+import { error } from "tauri-plugin-log-api";
+
+import * as backendAnalysisApi from "@api/analysis";
+import type { CoverageReport } from "@app/types/analysis";
 
 import type { app_device_NormalizedWaypoint } from "@bindings/index";
 
@@ -32,12 +36,17 @@ import { MapNodeTooltip } from "@components/Map/MapNodeTooltip";
 import { MapEdgeTooltip } from "@components/Map/MapEdgeTooltip";
 import { createGraphNodesLayer } from "@components/Map/layers/graphNodes";
 import { createGraphEdgesLayer } from "@components/Map/layers/graphEdges";
+import { createCoverageLayer } from "@components/Map/layers/coverage";
 
 import { MeshWaypoint } from "@components/Waypoints/MeshWaypoint";
 import { WaypointMenu } from "@components/Waypoints/WaypointMenu";
 
 import { selectMapConfigState } from "@features/appConfig/selectors";
-import { selectAllNodes, selectAllWaypoints } from "@features/device/selectors";
+import {
+  selectAllNodes,
+  selectAllWaypoints,
+  selectPrimaryDeviceKey,
+} from "@features/device/selectors";
 import { selectGraph } from "@features/graph/selectors";
 import { selectMapState } from "@features/map/selectors";
 import { mapSliceActions } from "@features/map/slice";
@@ -74,6 +83,7 @@ export const MapView = () => {
   const showInfoPane = useSelector(selectInfoPane());
   const graph = useSelector(selectGraph());
   const nodes = useSelector(selectAllNodes());
+  const primaryDeviceKey = useSelector(selectPrimaryDeviceKey());
 
   const { viewState } = useSelector(selectMapState());
   const { style } = useSelector(selectMapConfigState());
@@ -97,6 +107,8 @@ export const MapView = () => {
   const [localViewState, setLocalViewState] =
     useState<Partial<ViewState>>(viewState);
 
+  const [coverage, setCoverage] = useState<CoverageReport | null>(null);
+
   const handleNodeClick = useCallback(
     (info: PickingInfo) => {
       console.warn("Node click", info);
@@ -113,6 +125,7 @@ export const MapView = () => {
   );
 
   const layers = [
+    createCoverageLayer(coverage?.grid ?? null), // Drawn beneath the mesh
     createGraphEdgesLayer(graph, nodes, setEdgeHoverInfo), // Need this above nodes
     createGraphNodesLayer(
       graph,
@@ -128,6 +141,21 @@ export const MapView = () => {
     setContextMenuEvent(null);
   };
 
+  const handleToggleCoverage = async () => {
+    setContextMenuEvent(null);
+
+    if (coverage || !primaryDeviceKey) {
+      setCoverage(null);
+      return;
+    }
+
+    try {
+      setCoverage(await backendAnalysisApi.estimateCoverage(primaryDeviceKey));
+    } catch (e) {
+      error(`Failed to estimate coverage: ${e}`);
+    }
+  };
+
   const handleContextMenu = (e: MapLayerMouseEvent) => {
     setContextMenuEvent(e);
     setLastRightClickLngLat(e.lngLat);
@@ -206,6 +234,18 @@ export const MapView = () => {
                       orientation="horizontal"
                     />
 
+                    <MapContextOption
+                      text={
+                        coverage
+                          ? t("map.contextMenu.hideCoverage")
+                          : t("map.contextMenu.showCoverage")
+                      }
+                      renderIcon={(c) => (
+                        <Layers className={c} strokeWidth={1.5} />
+                      )}
+                      onClick={handleToggleCoverage}
+                    />
+
                     <MapContextOption
                       text={t("map.contextMenu.close")}
                       renderIcon={(c) => <X className={c} strokeWidth={1.5} />}
@@ -0,0 +1,46 @@
+import { GeoJsonLayer } from "deck.gl/typed";
+
+import type { CoverageCellProperties } from "@app/types/analysis";
+
+type CoverageGrid = GeoJSON.FeatureCollection<
+  GeoJSON.Polygon,
+  CoverageCellProperties
+>;
+
+// Margin above which a cell is drawn fully green, in dB
+const STRONG_MARGIN_DB = 20;
+
+const getCellColor = (
+  cell: GeoJSON.Feature<GeoJSON.Polygon, CoverageCellProperties>,
+): [number, number, number, number] => {
+  const { covered, marginDb } = cell.properties;
+
+  // Dead zones are shaded so they stand out from unexplored areas
+  if (!covered) return [239, 68, 68, 40];
+
+  const strength = Math.min(marginDb / STRONG_MARGIN_DB, 1);
+
+  return [
+    Math.round(234 - strength * 200),
+    Math.round(179 + strength * 18),
+    Math.round(8 + strength * 86),
+    90,
+  ];
+};
+
+export const createCoverageLayer = (grid: CoverageGrid | null) => {
+  return new GeoJsonLayer<CoverageCellProperties>({
+    id: "coverage",
+    data: grid ?? { type: "FeatureCollection", features: [] },
+    visible: !!grid,
+
+    pickable: false,
+    stroked: false,
+    filled: true,
+
+    getFillColor: (cell) =>
+      getCellColor(
+        cell as GeoJSON.Feature<GeoJSON.Polygon, CoverageCellProperties>,
+      ),
+  });
+};
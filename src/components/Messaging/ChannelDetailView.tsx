@@ -3,6 +3,8 @@ import { useDispatch, useSelector } from "react-redux";
 import { useNavigate } from "react-router-dom";
 import { warn } from "tauri-plugin-log-api";
 
+import { i18next } from "@app/i18n";
+
 import type { app_device_MeshChannel } from "@bindings/index";
 
 import { MessagingInput } from "@components/Messaging/MessagingInput";
@@ -10,7 +12,10 @@ import { TextMessageBubble } from "@components/Messaging/TextMessageBubble";
 import { ConfigTitlebar } from "@components/config/ConfigTitlebar";
 
 import { useDeviceApi } from "@features/device/api";
-import { selectPrimaryDeviceKey } from "@features/device/selectors";
+import {
+  selectIncomingFragments,
+  selectPrimaryDeviceKey,
+} from "@features/device/selectors";
 
 import { getChannelName, getNumMessagesText } from "@utils/messaging";
 import { AppRoutes } from "@utils/routing";
@@ -26,6 +31,9 @@ export const ChannelDetailView = ({
 }: IChannelDetailViewProps) => {
   const dispatch = useDispatch();
   const primaryDeviceKey = useSelector(selectPrimaryDeviceKey());
+  const incomingFragments = Object.values(
+    useSelector(selectIncomingFragments()),
+  ).filter((progress) => progress.channel === channel.config.index);
 
   const deviceApi = useDeviceApi();
 
@@ -68,6 +76,17 @@ export const ChannelDetailView = ({
               key={m.payload.packet.id}
             />
           ))}
+          {incomingFragments.map((progress) => (
+            <p
+              className="pr-6 text-xs font-normal text-gray-400"
+              key={`${progress.from}:${progress.messageId}`}
+            >
+              {i18next.t("messaging.receivingFragments", {
+                received: progress.received,
+                total: progress.total,
+              })}
+            </p>
+          ))}
         </div>
 
         <div className="flex flex-row gap-4">
@@ -13,8 +13,10 @@ import { MapOverlayButton } from "@components/Map/MapOverlayButton";
 import { MeshWaypoint } from "@components/Waypoints/MeshWaypoint";
 
 import { selectMapConfigState } from "@features/appConfig/selectors";
+import { useDeviceApi } from "@features/device/api";
 import {
   selectConnectedDeviceNodeId,
+  selectPrimaryDeviceKey,
   selectUserByNodeId,
 } from "@features/device/selectors";
 import { uiSliceActions } from "@features/ui/slice";
@@ -39,10 +41,22 @@ const getAcknowledgementText = (
     return { text: i18next.t("messaging.acknowledged"), isError: false };
   }
 
+  if (message.state === "queued") {
+    return { text: i18next.t("messaging.queued"), isError: false };
+  }
+
   if (message.state === "pending") {
     return { text: i18next.t("messaging.transmitting"), isError: false };
   }
 
+  if (message.state === "sent") {
+    return { text: i18next.t("messaging.sent"), isError: false };
+  }
+
+  if (message.state === "cancelled") {
+    return { text: i18next.t("messaging.cancelled"), isError: false };
+  }
+
   return { text: message.state.error, isError: true };
 };
 
@@ -52,10 +66,12 @@ export const TextMessageBubble = ({
 }: ITextMessageBubbleProps) => {
   const dispatch = useDispatch();
   const navigateTo = useNavigate();
+  const deviceApi = useDeviceApi();
   const { packet, type } = message.payload;
 
   const user = useSelector(selectUserByNodeId(packet.from));
   const ownNodeId = useSelector(selectConnectedDeviceNodeId());
+  const primaryDeviceKey = useSelector(selectPrimaryDeviceKey());
   const { style } = useSelector(selectMapConfigState());
 
   const { displayText: usernameDisplayText, isSelf } = formatMessageUsername(
@@ -70,6 +86,14 @@ export const TextMessageBubble = ({
     navigateTo(AppRoutes.MAP);
   };
 
+  const handleCancelClick = () => {
+    if (!primaryDeviceKey) return;
+    deviceApi.cancelQueuedMessage({
+      deviceKey: primaryDeviceKey,
+      messageId: packet.id,
+    });
+  };
+
   if (isSelf) {
     const { text, isError } = getAcknowledgementText(message);
 
@@ -147,6 +171,15 @@ export const TextMessageBubble = ({
           }`}
         >
           {text}
+          {message.state === "queued" && (
+            <button
+              type="button"
+              className="ml-2 font-semibold text-gray-500 dark:text-gray-400 hover:underline"
+              onClick={handleCancelClick}
+            >
+              {i18next.t("messaging.cancelQueued")}
+            </button>
+          )}
         </p>
       </div>
     );
@@ -13,6 +13,7 @@ import {
   MapPin,
   MessagesSquare,
   Network,
+  ScrollText,
   RadioTower,
   Router,
   Settings,
@@ -141,6 +142,15 @@ export const Sidebar = () => {
               <Network strokeWidth={1.5} className="w-6 h-6" />
             </SidebarIcon>
 
+            <SidebarIcon
+              name={t("sidebar.deviceLogs")}
+              isActive={location.pathname === AppRoutes.DEVICE_LOGS}
+              isSidebarExpanded={isSidebarExpanded}
+              onClick={() => navigateTo(AppRoutes.DEVICE_LOGS)}
+            >
+              <ScrollText strokeWidth={1.5} className="w-6 h-6" />
+            </SidebarIcon>
+
             <SidebarIcon
               name={t("sidebar.applicationState")}
               isActive={location.pathname === AppRoutes.APPLICATION_STATE}
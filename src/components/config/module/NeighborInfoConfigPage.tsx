@@ -0,0 +1,149 @@
+import { RotateCcw } from "lucide-react";
+import { useEffect, useMemo, useState } from "react";
+import { DeepPartial, useForm } from "react-hook-form";
+import { useTranslation } from "react-i18next";
+import { useDispatch, useSelector } from "react-redux";
+
+import debounce from "lodash.debounce";
+
+// import ConfigLabel from "@components/config/ConfigLabel";
+import { ConfigInput } from "@components/config/ConfigInput";
+import { ConfigTitlebar } from "@components/config/ConfigTitlebar";
+
+import {
+  selectCurrentModuleConfig,
+  selectEditedModuleConfig,
+} from "@features/config/selectors";
+import {
+  NeighborInfoModuleConfigInput,
+  configSliceActions,
+} from "@features/config/slice";
+
+import {
+  selectDevice,
+  selectFeatureSupport,
+} from "@features/device/selectors";
+import { getDefaultConfigInput } from "@utils/form";
+
+export interface INeighborInfoConfigPageProps {
+  className?: string;
+}
+
+// See https://github.com/react-hook-form/react-hook-form/issues/10378
+const parseNeighborInfoModuleConfigInput = (
+  d: DeepPartial<NeighborInfoModuleConfigInput>,
+): DeepPartial<NeighborInfoModuleConfigInput> => ({
+  ...d,
+  updateInterval: parseInt(d.updateInterval as unknown as string),
+});
+
+export const NeighborInfoConfigPage = ({
+  className = "",
+}: INeighborInfoConfigPageProps) => {
+  const { t } = useTranslation();
+
+  const dispatch = useDispatch();
+  const device = useSelector(selectDevice());
+  const featureSupport = useSelector(selectFeatureSupport("neighborInfo"));
+  const featureUnsupported = featureSupport?.supported === false;
+
+  const currentConfig = useSelector(selectCurrentModuleConfig());
+  const editedConfig = useSelector(selectEditedModuleConfig());
+
+  const [moduleDisabled, setModuleDisabled] = useState(
+    !device?.moduleConfig.neighborInfo?.enabled ?? true,
+  );
+
+  const defaultValues = useMemo(
+    () =>
+      getDefaultConfigInput(
+        device?.moduleConfig.neighborInfo ?? undefined,
+        editedConfig.neighborInfo ?? undefined,
+      ),
+    [device, editedConfig],
+  );
+
+  const updateStateFlags = (d: DeepPartial<NeighborInfoModuleConfigInput>) => {
+    setModuleDisabled(!d.enabled);
+  };
+
+  useEffect(() => {
+    if (!defaultValues) return;
+    updateStateFlags(defaultValues);
+  }, [updateStateFlags, defaultValues]);
+
+  const {
+    register,
+    reset,
+    watch,
+    formState: { errors },
+  } = useForm<NeighborInfoModuleConfigInput>({
+    defaultValues: device?.moduleConfig.neighborInfo ?? undefined,
+  });
+
+  const updateConfigHander = useMemo(
+    () =>
+      debounce(
+        (d: DeepPartial<NeighborInfoModuleConfigInput>) => {
+          const data = parseNeighborInfoModuleConfigInput(d);
+          updateStateFlags(data);
+          dispatch(
+            configSliceActions.updateModuleConfig({ neighborInfo: data }),
+          );
+        },
+        500,
+        { leading: true },
+      ),
+    [dispatch, updateStateFlags],
+  );
+
+  watch(updateConfigHander);
+
+  // Cancel handlers when unmounting
+  useEffect(() => {
+    return () => updateConfigHander.cancel();
+  }, [updateConfigHander]);
+
+  const handleFormReset = () => {
+    if (!currentConfig?.neighborInfo) return;
+    reset(currentConfig.neighborInfo);
+    dispatch(configSliceActions.updateModuleConfig({ neighborInfo: null }));
+  };
+
+  return (
+    <div className={`${className} flex-1 h-screen`}>
+      <ConfigTitlebar
+        title={t("config.module.neighborInfo.title")}
+        subtitle={t("config.module.neighborInfo.description")}
+        renderIcon={(c) => <RotateCcw className={c} />}
+        buttonTooltipText={t("config.discardChanges")}
+        onIconClick={handleFormReset}
+      >
+        <div className="flex flex-col gap-6">
+          {featureUnsupported && (
+            <p className="text-sm font-normal text-red-500 dark:text-red-400">
+              {featureSupport.reason ??
+                t("config.module.neighborInfo.unsupportedFirmware")}
+            </p>
+          )}
+
+          <ConfigInput
+            type="checkbox"
+            text={t("config.module.neighborInfo.neighborInfoEnabled")}
+            disabled={featureUnsupported}
+            error={errors.enabled?.message as string}
+            {...register("enabled")}
+          />
+
+          <ConfigInput
+            type="number"
+            text={t("config.module.neighborInfo.updateInterval")}
+            disabled={moduleDisabled || featureUnsupported}
+            error={errors.updateInterval?.message as string}
+            {...register("updateInterval")}
+          />
+        </div>
+      </ConfigTitlebar>
+    </div>
+  );
+};
@@ -0,0 +1,138 @@
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { useSelector } from "react-redux";
+
+import * as backendLogsApi from "@api/logs";
+import type {
+  app_ipc_DeviceLogEvent,
+  app_packet_api_device_logs_DeviceLogEntry,
+  app_packet_api_device_logs_DeviceLogLevel,
+} from "@bindings/index";
+import { NavigationBacktrace } from "@components/NavigationBacktrace";
+import { selectPrimaryDeviceKey } from "@features/device/selectors";
+import { useAsyncUnlistenUseEffect } from "@utils/ui";
+
+const MAX_DISPLAYED_LOGS = 500;
+
+const LOG_LEVELS: app_packet_api_device_logs_DeviceLogLevel[] = [
+  "trace",
+  "debug",
+  "info",
+  "warning",
+  "error",
+  "critical",
+];
+
+const matchesFilter = (
+  entry: app_packet_api_device_logs_DeviceLogEntry,
+  minLevel: app_packet_api_device_logs_DeviceLogLevel,
+  text: string,
+) => {
+  const needle = text.trim().toLowerCase();
+
+  return (
+    LOG_LEVELS.indexOf(entry.level) >= LOG_LEVELS.indexOf(minLevel) &&
+    (!needle ||
+      entry.message.toLowerCase().includes(needle) ||
+      entry.source.toLowerCase().includes(needle))
+  );
+};
+
+export const DeviceLogPage = () => {
+  const { t } = useTranslation();
+
+  const deviceKey = useSelector(selectPrimaryDeviceKey());
+
+  const [minLevel, setMinLevel] =
+    useState<app_packet_api_device_logs_DeviceLogLevel>("debug");
+  const [text, setText] = useState("");
+  const [entries, setEntries] = useState<
+    app_packet_api_device_logs_DeviceLogEntry[]
+  >([]);
+
+  useEffect(() => {
+    if (!deviceKey) return;
+
+    backendLogsApi
+      .getDeviceLogs(deviceKey, {
+        minLevel,
+        text: text || null,
+        limit: MAX_DISPLAYED_LOGS,
+      })
+      .then(setEntries);
+  }, [deviceKey, minLevel, text]);
+
+  useAsyncUnlistenUseEffect(async () => {
+    return await listen<app_ipc_DeviceLogEvent>("device_log", (event) => {
+      const { deviceKey: key, entry } = event.payload;
+      if (key !== deviceKey || !matchesFilter(entry, minLevel, text)) return;
+
+      setEntries((prev) => [...prev, entry].slice(-MAX_DISPLAYED_LOGS));
+    });
+  }, [deviceKey, minLevel, text]);
+
+  const handleClear = async () => {
+    if (!deviceKey) return;
+
+    await backendLogsApi.clearDeviceLogs(deviceKey);
+    setEntries([]);
+  };
+
+  return (
+    <div className="flex flex-col w-full h-screen bg-white dark:bg-gray-800">
+      <div className="flex justify-center align-middle px-9 min-h-[5rem] border-b border-gray-100 dark:border-gray-700">
+        <NavigationBacktrace
+          className="my-auto mr-auto"
+          levels={[t("sidebar.deviceLogs")]}
+        />
+      </div>
+
+      <div className="flex flex-row gap-4 px-9 py-4">
+        <select
+          className="default-overlay border border-gray-100 dark:border-gray-700 rounded-lg px-3 py-2 text-sm"
+          value={minLevel}
+          onChange={(e) =>
+            setMinLevel(
+              e.target.value as app_packet_api_device_logs_DeviceLogLevel,
+            )
+          }
+        >
+          {LOG_LEVELS.map((level) => (
+            <option key={level} value={level}>
+              {t(`deviceLogs.levels.${level}`)}
+            </option>
+          ))}
+        </select>
+
+        <input
+          className="flex-1 default-overlay border border-gray-100 dark:border-gray-700 rounded-lg px-3 py-2 text-sm"
+          placeholder={t("deviceLogs.search")}
+          value={text}
+          onChange={(e) => setText(e.target.value)}
+        />
+
+        <button
+          type="button"
+          className="px-3 py-2 text-sm rounded-lg border border-gray-100 dark:border-gray-700 text-gray-600 dark:text-gray-300"
+          onClick={handleClear}
+        >
+          {t("deviceLogs.clear")}
+        </button>
+      </div>
+
+      <div className="flex-1 overflow-auto px-9 pb-6 font-mono text-xs text-gray-700 dark:text-gray-300">
+        {entries.length ? (
+          entries.map((entry, index) => (
+            <p key={`${entry.time}-${index}`} className="whitespace-pre-wrap">
+              {new Date(entry.time * 1000).toLocaleTimeString()}{" "}
+              {entry.level.toUpperCase()} [{entry.source}] {entry.message}
+            </p>
+          ))
+        ) : (
+          <p className="text-gray-400">{t("deviceLogs.empty")}</p>
+        )}
+      </div>
+    </div>
+  );
+};
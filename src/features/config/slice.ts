@@ -17,6 +17,7 @@ import type {
   meshtastic_protobufs_module_config_CannedMessageConfig,
   meshtastic_protobufs_module_config_ExternalNotificationConfig,
   meshtastic_protobufs_module_config_MqttConfig,
+  meshtastic_protobufs_module_config_NeighborInfoConfig,
   meshtastic_protobufs_module_config_RangeTestConfig,
   meshtastic_protobufs_module_config_RemoteHardwareConfig,
   meshtastic_protobufs_module_config_SerialConfig,
@@ -40,6 +41,8 @@ export type ExternalNotificationModuleConfigInput =
   meshtastic_protobufs_module_config_ExternalNotificationConfig;
 export type MQTTModuleConfigInput =
   meshtastic_protobufs_module_config_MqttConfig;
+export type NeighborInfoModuleConfigInput =
+  meshtastic_protobufs_module_config_NeighborInfoConfig;
 export type RangeTestModuleConfigInput =
   meshtastic_protobufs_module_config_RangeTestConfig;
 export type RemoteHardwareModuleConfigInput =
@@ -72,6 +75,7 @@ export interface IModuleConfigState {
   cannedMessage: CannedMessageModuleConfigInput | null;
   externalNotification: ExternalNotificationModuleConfigInput | null;
   mqtt: MQTTModuleConfigInput | null;
+  neighborInfo: NeighborInfoModuleConfigInput | null;
   rangeTest: RangeTestModuleConfigInput | null;
   remoteHardware: RemoteHardwareModuleConfigInput | null;
   serial: SerialModuleConfigInput | null;
@@ -105,6 +109,7 @@ export const initialConfigState: IConfigState = {
     cannedMessage: null,
     externalNotification: null,
     mqtt: null,
+    neighborInfo: null,
     rangeTest: null,
     remoteHardware: null,
     serial: null,
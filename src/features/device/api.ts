@@ -3,9 +3,12 @@ import { useDispatch } from "react-redux";
 import * as backendRadioApi from "@api/radio";
 import * as backendMeshApi from "@api/mesh";
 import * as backendConnectionApi from "@api/connection";
+import * as backendTransfersApi from "@api/transfers";
 
 import {
   app_device_NormalizedWaypoint,
+  app_packet_api_outbox_MessagePriority,
+  app_packet_api_outbox_OutboxPolicy,
   meshtastic_protobufs_User,
 } from "@bindings/index";
 
@@ -30,6 +33,12 @@ export enum DeviceApiActions {
   UpdateUserConfig = "device/updateUserConfig",
   SendWaypoint = "device/sendWaypoint",
   DeleteWaypoint = "device/deleteWaypoint",
+  SetOutboxPolicy = "device/setOutboxPolicy",
+  CancelQueuedMessage = "device/cancelQueuedMessage",
+  GetFileTransfers = "device/getFileTransfers",
+  OfferFileTransfer = "device/offerFileTransfer",
+  AcceptFileTransfer = "device/acceptFileTransfer",
+  CancelFileTransfer = "device/cancelFileTransfer",
 }
 
 export const useDeviceApi = () => {
@@ -176,6 +185,8 @@ export const useDeviceApi = () => {
     deviceKey: string;
     text: string;
     channel: number;
+    priority?: app_packet_api_outbox_MessagePriority;
+    compress?: boolean;
   }) => {
     const TYPE = DeviceApiActions.SendText;
 
@@ -184,6 +195,8 @@ export const useDeviceApi = () => {
         payload.deviceKey,
         payload.channel,
         payload.text,
+        payload.priority,
+        payload.compress,
       );
     });
   };
@@ -203,6 +216,7 @@ export const useDeviceApi = () => {
     deviceKey: string;
     waypoint: app_device_NormalizedWaypoint;
     channel: number;
+    priority?: app_packet_api_outbox_MessagePriority;
   }) => {
     const TYPE = DeviceApiActions.SendWaypoint;
 
@@ -211,6 +225,7 @@ export const useDeviceApi = () => {
         payload.deviceKey,
         payload.channel,
         payload.waypoint,
+        payload.priority,
       );
     });
   };
@@ -229,6 +244,102 @@ export const useDeviceApi = () => {
     });
   };
 
+  const setOutboxPolicy = async (payload: {
+    deviceKey: string;
+    policy: app_packet_api_outbox_OutboxPolicy;
+  }) => {
+    const TYPE = DeviceApiActions.SetOutboxPolicy;
+
+    await trackRequestOperation(TYPE, dispatch, async () => {
+      const status = await backendMeshApi.setOutboxPolicy(
+        payload.deviceKey,
+        payload.policy,
+      );
+
+      dispatch(deviceSliceActions.setOutboxStatus(status));
+    });
+  };
+
+  const cancelQueuedMessage = async (payload: {
+    deviceKey: string;
+    messageId: number;
+  }) => {
+    const TYPE = DeviceApiActions.CancelQueuedMessage;
+
+    await trackRequestOperation(TYPE, dispatch, async () => {
+      await backendMeshApi.cancelQueuedMessage(
+        payload.deviceKey,
+        payload.messageId,
+      );
+    });
+  };
+
+  const getFileTransfers = async (payload: { deviceKey: string }) => {
+    const TYPE = DeviceApiActions.GetFileTransfers;
+
+    await trackRequestOperation(TYPE, dispatch, async () => {
+      const transfers = await backendTransfersApi.getFileTransfers(
+        payload.deviceKey,
+      );
+
+      dispatch(deviceSliceActions.setFileTransfers(transfers));
+    });
+  };
+
+  const offerFileTransfer = async (payload: {
+    deviceKey: string;
+    to: number;
+    channel: number;
+    fileName: string;
+    data: Uint8Array;
+    compress?: boolean;
+  }) => {
+    const TYPE = DeviceApiActions.OfferFileTransfer;
+
+    await trackRequestOperation(TYPE, dispatch, async () => {
+      const transfer = await backendTransfersApi.offerFileTransfer(
+        payload.deviceKey,
+        payload.to,
+        payload.channel,
+        payload.fileName,
+        Array.from(payload.data),
+        payload.compress,
+      );
+
+      dispatch(deviceSliceActions.upsertFileTransfer(transfer));
+    });
+  };
+
+  const acceptFileTransfer = async (payload: {
+    deviceKey: string;
+    transferId: number;
+  }) => {
+    const TYPE = DeviceApiActions.AcceptFileTransfer;
+
+    await trackRequestOperation(TYPE, dispatch, async () => {
+      const transfer = await backendTransfersApi.acceptFileTransfer(
+        payload.deviceKey,
+        payload.transferId,
+      );
+
+      dispatch(deviceSliceActions.upsertFileTransfer(transfer));
+    });
+  };
+
+  const cancelFileTransfer = async (payload: {
+    deviceKey: string;
+    transferId: number;
+  }) => {
+    const TYPE = DeviceApiActions.CancelFileTransfer;
+
+    await trackRequestOperation(TYPE, dispatch, async () => {
+      await backendTransfersApi.cancelFileTransfer(
+        payload.deviceKey,
+        payload.transferId,
+      );
+    });
+  };
+
   return {
     getAutoConnectPort,
     getAvailableSerialPorts,
@@ -239,5 +350,11 @@ export const useDeviceApi = () => {
     updateUserConfig,
     sendWaypoint,
     deleteWaypoint,
+    setOutboxPolicy,
+    cancelQueuedMessage,
+    getFileTransfers,
+    offerFileTransfer,
+    acceptFileTransfer,
+    cancelFileTransfer,
   };
 };
@@ -1,10 +1,16 @@
 import type { RootState } from "@app/store";
 
 import type {
+  app_device_compatibility_DeviceFeature,
+  app_device_compatibility_FeatureSupport,
+  app_device_DetectionEvent,
   app_device_MeshChannel,
   app_device_MeshDevice,
   app_device_MeshNode,
   app_device_NormalizedWaypoint,
+  app_packet_api_fragments_FragmentProgress,
+  app_packet_api_outbox_OutboxStatus,
+  app_packet_api_transfers_FileTransferStatus,
   meshtastic_protobufs_User,
 } from "@bindings/index";
 
@@ -23,6 +29,38 @@ export const selectDevice =
   (state: RootState): app_device_MeshDevice | null =>
     state.devices.device;
 
+// Features are assumed supported until the device reports its firmware
+export const selectFeatureSupport =
+  (feature: app_device_compatibility_DeviceFeature) =>
+  (state: RootState): app_device_compatibility_FeatureSupport | null =>
+    state.devices.device?.compatibility?.features.find(
+      (support) => support.feature === feature,
+    ) ?? null;
+
+export const selectOutboxStatus =
+  () =>
+  (state: RootState): app_packet_api_outbox_OutboxStatus | null =>
+    state.devices.outboxStatus;
+
+export const selectIncomingFragments =
+  () =>
+  (
+    state: RootState,
+  ): Record<string, app_packet_api_fragments_FragmentProgress> =>
+    state.devices.incomingFragments;
+
+export const selectFileTransfers =
+  () =>
+  (
+    state: RootState,
+  ): Record<number, app_packet_api_transfers_FileTransferStatus> =>
+    state.devices.fileTransfers;
+
+export const selectDetectionEvents =
+  (nodeNum: number) =>
+  (state: RootState): app_device_DetectionEvent[] =>
+    state.devices.detectionEvents[nodeNum] ?? [];
+
 export const selectConnectedDeviceNodeId =
   () =>
   (state: RootState): number | null =>
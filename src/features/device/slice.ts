@@ -1,19 +1,40 @@
 import { PayloadAction, createSlice } from "@reduxjs/toolkit";
 
-import type { app_device_MeshDevice } from "@bindings/index";
+import type {
+  app_device_DetectionEvent,
+  app_device_MeshDevice,
+  app_device_MeshNode,
+  app_device_NormalizedWaypoint,
+  app_ipc_MessageAddedPayload,
+  app_ipc_MessageStateChangedPayload,
+  app_packet_api_fragments_FragmentProgress,
+  app_packet_api_outbox_OutboxStatus,
+  app_packet_api_transfers_FileTransferStatus,
+} from "@bindings/index";
 
 export interface IDeviceState {
   device: app_device_MeshDevice | null;
   availableSerialPorts: string[] | null;
   primaryDeviceKey: string | null; // port or socket ddress
   autoConnectPort: string | null; // Port to automatically connect to on startup
+  outboxStatus: app_packet_api_outbox_OutboxStatus | null;
+  incomingFragments: Record<string, app_packet_api_fragments_FragmentProgress>; // keyed by sender and message id
+  fileTransfers: Record<number, app_packet_api_transfers_FileTransferStatus>; // keyed by transfer id
+  detectionEvents: Record<number, app_device_DetectionEvent[]>; // keyed by node num
 }
 
+// Matches the number of events the backend keeps for each node
+const MAX_DETECTION_EVENTS_PER_NODE = 100;
+
 export const initialDeviceState: IDeviceState = {
   device: null,
   availableSerialPorts: null,
   primaryDeviceKey: null,
   autoConnectPort: null,
+  outboxStatus: null,
+  incomingFragments: {},
+  fileTransfers: {},
+  detectionEvents: {},
 };
 
 export const deviceSlice = createSlice({
@@ -41,6 +62,95 @@ export const deviceSlice = createSlice({
     setAutoConnectPort: (state, action: PayloadAction<string | null>) => {
       state.autoConnectPort = action.payload;
     },
+
+    upsertNode: (state, action: PayloadAction<app_device_MeshNode>) => {
+      if (!state.device) return;
+      state.device.nodes[action.payload.nodeNum] = action.payload;
+    },
+
+    addMessage: (state, action: PayloadAction<app_ipc_MessageAddedPayload>) => {
+      const channel = state.device?.channels[action.payload.channel];
+      if (!channel) return;
+
+      channel.messages.push(action.payload.message);
+      channel.lastInteraction = Math.floor(Date.now() / 1000);
+    },
+
+    setMessageState: (
+      state,
+      action: PayloadAction<app_ipc_MessageStateChangedPayload>,
+    ) => {
+      const channel = state.device?.channels[action.payload.channel];
+      if (!channel) return;
+
+      const message = channel.messages.find(
+        (m) => m.payload.packet.id === action.payload.messageId,
+      );
+
+      if (message) {
+        message.state = action.payload.state;
+      }
+    },
+
+    setOutboxStatus: (
+      state,
+      action: PayloadAction<app_packet_api_outbox_OutboxStatus | null>,
+    ) => {
+      state.outboxStatus = action.payload;
+    },
+
+    setFragmentProgress: (
+      state,
+      action: PayloadAction<app_packet_api_fragments_FragmentProgress>,
+    ) => {
+      const { from, messageId, received, total, expired } = action.payload;
+      const key = `${from}:${messageId}`;
+
+      if (expired || received >= total) {
+        delete state.incomingFragments[key];
+        return;
+      }
+
+      state.incomingFragments[key] = action.payload;
+    },
+
+    setFileTransfers: (
+      state,
+      action: PayloadAction<app_packet_api_transfers_FileTransferStatus[]>,
+    ) => {
+      state.fileTransfers = Object.fromEntries(
+        action.payload.map((transfer) => [transfer.transferId, transfer]),
+      );
+    },
+
+    upsertFileTransfer: (
+      state,
+      action: PayloadAction<app_packet_api_transfers_FileTransferStatus>,
+    ) => {
+      state.fileTransfers[action.payload.transferId] = action.payload;
+    },
+
+    addDetectionEvent: (
+      state,
+      action: PayloadAction<app_device_DetectionEvent>,
+    ) => {
+      const events = [
+        ...(state.detectionEvents[action.payload.nodeNum] ?? []),
+        action.payload,
+      ];
+
+      state.detectionEvents[action.payload.nodeNum] = events.slice(
+        -MAX_DETECTION_EVENTS_PER_NODE,
+      );
+    },
+
+    addWaypoint: (
+      state,
+      action: PayloadAction<app_device_NormalizedWaypoint>,
+    ) => {
+      if (!state.device) return;
+      state.device.waypoints[action.payload.id] = action.payload;
+    },
   },
 });
 
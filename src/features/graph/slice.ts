@@ -1,4 +1,8 @@
-import { MeshGraph } from "@app/types/graph";
+import { MeshGraph, StableGraph } from "@app/types/graph";
+import {
+  app_graph_ds_node_GraphNode,
+  app_ipc_EdgeUpdatedPayload,
+} from "@bindings/index";
 import { PayloadAction, createSlice } from "@reduxjs/toolkit";
 
 export type IGraphState = {
@@ -16,7 +20,72 @@ export const graphSlice = createSlice({
     setGraph: (state, action: PayloadAction<MeshGraph>) => {
       state.graph = action.payload.graph;
     },
+
+    upsertNode: (state, action: PayloadAction<app_graph_ds_node_GraphNode>) => {
+      state.graph ??= createEmptyGraph();
+      upsertGraphNode(state.graph, action.payload);
+    },
+
+    upsertEdge: (state, action: PayloadAction<app_ipc_EdgeUpdatedPayload>) => {
+      state.graph ??= createEmptyGraph();
+
+      const { source, target, edge } = action.payload;
+      const sourceIndex = upsertGraphNode(state.graph, source);
+      const targetIndex = upsertGraphNode(state.graph, target);
+
+      const existing = state.graph.edges.find(
+        ([from, to]) => from === sourceIndex && to === targetIndex,
+      );
+
+      if (existing) {
+        existing[2] = edge;
+      } else {
+        state.graph.edges.push([sourceIndex, targetIndex, edge]);
+      }
+    },
+
+    removeNode: (state, action: PayloadAction<number>) => {
+      if (!state.graph) return;
+
+      const index = state.graph.nodes.findIndex(
+        (n) => n.nodeNum === action.payload,
+      );
+      if (index === -1) return;
+
+      // Edges reference nodes by index, so shift indices past the removed node
+      state.graph.nodes.splice(index, 1);
+      state.graph.edges = state.graph.edges
+        .filter(([from, to]) => from !== index && to !== index)
+        .map(([from, to, edge]) => [
+          from > index ? from - 1 : from,
+          to > index ? to - 1 : to,
+          edge,
+        ]);
+    },
   },
 });
 
+const createEmptyGraph = (): StableGraph => ({
+  edge_property: "directed",
+  edges: [],
+  node_holes: [],
+  nodes: [],
+});
+
+// Returns the index of the node within the graph's node list
+const upsertGraphNode = (
+  graph: StableGraph,
+  node: app_graph_ds_node_GraphNode,
+): number => {
+  const index = graph.nodes.findIndex((n) => n.nodeNum === node.nodeNum);
+
+  if (index === -1) {
+    graph.nodes.push(node);
+    return graph.nodes.length - 1;
+  }
+
+  graph.nodes[index] = node;
+  return index;
+};
+
 export const { actions: graphSliceActions, reducer: graphReducer } = graphSlice;
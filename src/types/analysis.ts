@@ -0,0 +1,24 @@
+import {
+  app_analysis_geo_GeoBounds,
+  app_analysis_propagation_LinkBudget,
+  app_analysis_propagation_PropagationModel,
+} from "@bindings/index";
+
+export interface CoverageCellProperties {
+  nodeNum: number;
+  rssiDbm: number;
+  snrDb: number;
+  marginDb: number;
+  covered: boolean;
+}
+
+export interface CoverageReport {
+  budget: app_analysis_propagation_LinkBudget;
+  model: app_analysis_propagation_PropagationModel;
+  bounds: app_analysis_geo_GeoBounds;
+  rows: number;
+  columns: number;
+  cellSizeM: number;
+  coveredFraction: number;
+  grid: GeoJSON.FeatureCollection<GeoJSON.Polygon, CoverageCellProperties>;
+}
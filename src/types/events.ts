@@ -0,0 +1,9 @@
+import { app_device_MeshDevice } from "@bindings/index";
+
+import { MeshGraph } from "@app/types/graph";
+
+export interface ResyncSnapshot {
+  sequence: number;
+  device: app_device_MeshDevice;
+  graph: MeshGraph;
+}
@@ -9,4 +9,5 @@ export enum AppRoutes {
   APPLICATION_STATE = "/app-state",
   APPLICATION_SETTINGS = "/settings",
   GRAPH_DEBUGGER = "/graph-debugger",
+  DEVICE_LOGS = "/device-logs",
 }